@@ -1,12 +1,16 @@
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::net::SocketAddr;
 
+use log::warn;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::UdpSocket,
 };
 use udpflow::UdpStreamRemote;
 
-use solana_gossip_proto::wire::{Payload, PACKET_DATA_SIZE};
+use solana_gossip_proto::{
+    utils::unspecified_ip_for,
+    wire::{Payload, PACKET_DATA_SIZE},
+};
 
 use crate::errors::Result;
 
@@ -16,23 +20,32 @@ pub struct Connection {
 
 impl Connection {
     pub async fn connect(addr: SocketAddr) -> Result<Connection> {
-        let local_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        // same family as the entrypoint, an IPv4 socket cannot reach an IPv6 one
+        let local_addr = SocketAddr::new(unspecified_ip_for(&addr), 0);
         let local_socket = UdpSocket::bind(local_addr).await?;
         let socket = UdpStreamRemote::new(local_socket, addr);
         Ok(Self { socket })
     }
 
     pub async fn receive(&mut self) -> Result<Option<Payload>> {
-        let mut buf = [0; PACKET_DATA_SIZE];
+        // one byte more than a packet, to tell the oversized datagrams apart
+        let mut buf = [0; PACKET_DATA_SIZE + 1];
 
-        let len = self.socket.read(&mut buf).await?;
+        let len = loop {
+            let len = self.socket.read(&mut buf).await?;
+            if len <= PACKET_DATA_SIZE {
+                break len;
+            }
+            warn!("datagram larger than {PACKET_DATA_SIZE} bytes dropped");
+        };
 
         if len > 0 {
-            Ok(Some(Payload {
+            let mut payload = Payload {
                 len,
-                buf,
-                addr: None,
-            }))
+                ..Payload::default()
+            };
+            payload.buf.copy_from_slice(&buf[..PACKET_DATA_SIZE]);
+            Ok(Some(payload))
         } else {
             Ok(None)
         }
@@ -0,0 +1,1218 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs, io,
+    path::Path,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    errors::InsertError,
+    json,
+    protocol::{
+        estimate_cluster_tip, CrdsData, CrdsFilter, CrdsValue, DuplicateShred, DuplicateShredIndex,
+        EpochSlotsIndex, IncrementalSnapshotHashes, NodeInstance, Slot, SnapshotHashes,
+        SnapshotHashesKind, VoteIndex,
+    },
+    utils::since_the_epoch_millis,
+};
+
+/// Key under which a `CrdsValue` is stored, one per variant kind and origin.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CrdsValueLabel {
+    LegacyContactInfo(Pubkey),
+    Vote(VoteIndex, Pubkey),
+    LowestSlot(Pubkey),
+    SnapshotHashes(Pubkey),
+    AccountsHashes(Pubkey),
+    EpochSlots(EpochSlotsIndex, Pubkey),
+    LegacyVersion(Pubkey),
+    Version(Pubkey),
+    NodeInstance(Pubkey),
+    DuplicateShred(DuplicateShredIndex, Pubkey),
+    IncrementalSnapshotHashes(Pubkey),
+    ContactInfo(Pubkey),
+}
+
+impl CrdsValueLabel {
+    pub fn new(data: &CrdsData) -> Self {
+        match data {
+            CrdsData::LegacyContactInfo(info) => CrdsValueLabel::LegacyContactInfo(info.id),
+            CrdsData::Vote(index, vote) => CrdsValueLabel::Vote(*index, vote.from),
+            CrdsData::LowestSlot(_, slots) => CrdsValueLabel::LowestSlot(slots.from),
+            CrdsData::SnapshotHashes(hashes) => CrdsValueLabel::SnapshotHashes(hashes.from),
+            CrdsData::AccountsHashes(hashes) => CrdsValueLabel::AccountsHashes(hashes.from),
+            CrdsData::EpochSlots(index, slots) => CrdsValueLabel::EpochSlots(*index, slots.from),
+            CrdsData::LegacyVersion(version) => CrdsValueLabel::LegacyVersion(version.from),
+            CrdsData::Version(version) => CrdsValueLabel::Version(version.from),
+            CrdsData::NodeInstance(node) => CrdsValueLabel::NodeInstance(node.from),
+            CrdsData::DuplicateShred(index, shred) => {
+                CrdsValueLabel::DuplicateShred(*index, shred.from)
+            }
+            CrdsData::IncrementalSnapshotHashes(hashes) => {
+                CrdsValueLabel::IncrementalSnapshotHashes(hashes.from)
+            }
+            CrdsData::ContactInfo(info) => CrdsValueLabel::ContactInfo(info.pubkey),
+        }
+    }
+}
+
+impl SnapshotHashesKind {
+    /// Label of the hashes of this kind, the two kinds are stored apart.
+    pub fn label(self, from: Pubkey) -> CrdsValueLabel {
+        match self {
+            SnapshotHashesKind::Snapshot => CrdsValueLabel::SnapshotHashes(from),
+            SnapshotHashesKind::Accounts => CrdsValueLabel::AccountsHashes(from),
+        }
+    }
+}
+
+/// Values dated further ahead of the local time are rejected, once stored
+/// they could not be replaced until the local time caught up.
+pub const CRDS_MAX_CLOCK_SKEW: Duration = Duration::from_secs(15);
+
+/// The votes only tell the recent slots of a node, they expire first.
+pub const CRDS_VOTE_TTL: Duration = Duration::from_mins(2);
+
+/// Age after which the values are purged, per variant kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrdsTtl {
+    default: Duration,
+    /// by the name of `CrdsData::kind`
+    kinds: HashMap<String, Duration>,
+}
+
+impl CrdsTtl {
+    /// The values expire after `default`, but the votes after `CRDS_VOTE_TTL`.
+    pub fn new(default: Duration) -> Self {
+        CrdsTtl {
+            default,
+            kinds: HashMap::from([("Vote".to_string(), CRDS_VOTE_TTL)]),
+        }
+    }
+
+    /// Override the age of a kind, unknown kinds are refused.
+    pub fn set(&mut self, kind: &str, ttl: Duration) -> Result<(), String> {
+        if !CrdsData::KINDS.contains(&kind) {
+            return Err(format!(
+                "unknown CRDS kind {kind}, expected one of {}",
+                CrdsData::KINDS.join(", ")
+            ));
+        }
+        self.kinds.insert(kind.to_string(), ttl);
+        Ok(())
+    }
+
+    pub fn ttl_for_kind(&self, kind: &str) -> Duration {
+        self.kinds.get(kind).copied().unwrap_or(self.default)
+    }
+}
+
+/// Latest `CrdsValue` received for each label.
+#[derive(Debug)]
+pub struct CrdsTable {
+    table: HashMap<CrdsValueLabel, CrdsValue>,
+    /// local time, in milliseconds since the epoch, at which each value was inserted
+    inserted: HashMap<CrdsValueLabel, u64>,
+    max_clock_skew: Duration,
+}
+
+impl Default for CrdsTable {
+    fn default() -> Self {
+        CrdsTable::with_max_clock_skew(CRDS_MAX_CLOCK_SKEW)
+    }
+}
+
+impl CrdsTable {
+    /// Table rejecting the values whose wallclock is more than `max_clock_skew`
+    /// ahead of the local time.
+    pub fn with_max_clock_skew(max_clock_skew: Duration) -> Self {
+        CrdsTable {
+            table: HashMap::new(),
+            inserted: HashMap::new(),
+            max_clock_skew,
+        }
+    }
+
+    /// Insert the value if it is newer than the stored one and not dated in the future.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn insert(&mut self, value: CrdsValue) -> Result<(), InsertError> {
+        let now = since_the_epoch_millis();
+        if let Some(wallclock) = value.data.wallclock() {
+            if wallclock > now.saturating_add(self.max_clock_skew.as_millis() as u64) {
+                return Err(InsertError::FutureWallclock { wallclock, now });
+            }
+        }
+        let label = value.label();
+        match self.table.get(&label) {
+            Some(current) if current.data.wallclock() >= value.data.wallclock() => {
+                Err(InsertError::Outdated)
+            }
+            _ => {
+                self.table.insert(label, value);
+                self.inserted.insert(label, now);
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the values whose wallclock is older than the age of their kind, values
+    /// without a wallclock expire that age after they were inserted. Returns how many were removed.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn purge_stale(&mut self, ttl: &CrdsTtl, now_ms: u64) -> usize {
+        let inserted = &mut self.inserted;
+        let before = self.table.len();
+        self.table.retain(|label, value| {
+            let max_age = ttl.ttl_for_kind(value.data.kind());
+            let cutoff = now_ms.saturating_sub(max_age.as_millis() as u64);
+            let timestamp = value
+                .data
+                .wallclock()
+                .or_else(|| inserted.get(label).copied())
+                .unwrap_or(now_ms);
+            let keep = timestamp >= cutoff;
+            if !keep {
+                inserted.remove(label);
+            }
+            keep
+        });
+        before - self.table.len()
+    }
+
+    pub fn get(&self, label: &CrdsValueLabel) -> Option<&CrdsValue> {
+        self.table.get(label)
+    }
+
+    /// Latest hashes of the kind advertised by the node.
+    pub fn snapshot_hashes(
+        &self,
+        from: Pubkey,
+        kind: SnapshotHashesKind,
+    ) -> Option<&SnapshotHashes> {
+        self.get(&kind.label(from))
+            .and_then(|value| value.data.snapshot_hashes())
+            .map(|(_, hashes)| hashes)
+    }
+
+    /// Latest incremental snapshot hashes advertised by the node.
+    pub fn incremental_snapshot_hashes(&self, from: Pubkey) -> Option<&IncrementalSnapshotHashes> {
+        match &self
+            .get(&CrdsValueLabel::IncrementalSnapshotHashes(from))?
+            .data
+        {
+            CrdsData::IncrementalSnapshotHashes(hashes) => Some(hashes),
+            _ => None,
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &CrdsValue> {
+        self.table.values()
+    }
+
+    /// Number of values held of each kind, by variant name.
+    pub fn counts_by_kind(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for value in self.table.values() {
+            *counts.entry(value.data.kind()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Values originated by the node, ordered by label so that the order is stable.
+    pub fn values_from(&self, from: Pubkey) -> Vec<&CrdsValue> {
+        let mut values = self
+            .table
+            .values()
+            .filter(|value| value.data.pubkey() == Some(from))
+            .collect::<Vec<_>>();
+        values.sort_by_cached_key(|value| format!("{:?}", value.label()));
+        values
+    }
+
+    /// Values a peer requesting with the filter is missing, the ones in the
+    /// filter mask partition whose hash is not in the bloom filter.
+    pub fn find_missing(&self, filter: &CrdsFilter) -> Vec<CrdsValue> {
+        self.table
+            .values()
+            .filter(|value| {
+                let hash = value.hash();
+                filter.test_mask(&hash) && !filter.filter.contains(&hash)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Write all the stored values to a pretty printed JSON file.
+    pub fn export_json(&self, path: &Path) -> io::Result<()> {
+        let mut values = self
+            .table
+            .iter()
+            .map(|(label, value)| (format!("{label:?}"), value))
+            .collect::<Vec<_>>();
+        values.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let values = values
+            .into_iter()
+            .map(|(label, value)| {
+                let data = json::to_value(&value.data)?;
+                Ok(json!({
+                    "label": label,
+                    "pubkey": value.data.pubkey().map(|pubkey| pubkey.to_string()),
+                    "wallclock": value.data.wallclock(),
+                    "signature": value.signature.to_string(),
+                    "data": data,
+                }))
+            })
+            .collect::<serde_json::Result<Vec<Value>>>()?;
+
+        let export = json!({
+            "timestamp": since_the_epoch_millis(),
+            "values": values,
+        });
+
+        fs::write(path, serde_json::to_string_pretty(&export)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+/// Last values inserted for each label, with the local time they were received.
+/// Opt-in, it holds up to `depth` values per label on top of the `CrdsTable`.
+#[derive(Debug)]
+pub struct CrdsTableHistory {
+    depth: usize,
+    values: HashMap<CrdsValueLabel, VecDeque<(u64, CrdsValue)>>,
+}
+
+impl CrdsTableHistory {
+    /// Keep the last `depth` values of each label, at least one.
+    pub fn new(depth: usize) -> Self {
+        CrdsTableHistory {
+            depth: depth.max(1),
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Append the value to the history of its label, the oldest one is evicted
+    /// once `depth` values are held.
+    pub fn push(&mut self, value: CrdsValue, now_ms: u64) {
+        let history = self.values.entry(value.label()).or_default();
+        if history.len() == self.depth {
+            history.pop_front();
+        }
+        history.push_back((now_ms, value));
+        // kept in a single slice for `history`
+        history.make_contiguous();
+    }
+
+    /// Values of the label with their receive time, the oldest first.
+    pub fn history(&self, label: &CrdsValueLabel) -> &[(u64, CrdsValue)] {
+        self.values
+            .get(label)
+            .map_or(&[], |history| history.as_slices().0)
+    }
+
+    /// Forget the labels not received for `max_age`, returns how many.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn purge_stale(&mut self, max_age: Duration, now_ms: u64) -> usize {
+        let cutoff = now_ms.saturating_sub(max_age.as_millis() as u64);
+        let before = self.values.len();
+        self.values.retain(|_, history| {
+            history
+                .back()
+                .is_some_and(|(received_at, _)| *received_at >= cutoff)
+        });
+        before - self.values.len()
+    }
+
+    /// Every label with its values, the oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (&CrdsValueLabel, &[(u64, CrdsValue)])> {
+        self.values
+            .iter()
+            .map(|(label, history)| (label, history.as_slices().0))
+    }
+
+    /// Number of labels with a history.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Nodes whose contact info advertised a different shred version than the one
+/// before it, as `(pubkey, old, new)` in the order received for each node. A
+/// node moving to another cluster or restarted with a wrong configuration shows up here.
+pub fn detect_shred_version_changes(history: &CrdsTableHistory) -> Vec<(Pubkey, u16, u16)> {
+    let shred_version = |value: &CrdsValue| match &value.data {
+        CrdsData::LegacyContactInfo(info) => Some(info.shred_version),
+        CrdsData::ContactInfo(info) => Some(info.shred_version),
+        _ => None,
+    };
+    let mut changes = history
+        .iter()
+        .filter_map(|(label, values)| match label {
+            CrdsValueLabel::LegacyContactInfo(from) | CrdsValueLabel::ContactInfo(from) => {
+                Some((*from, values))
+            }
+            _ => None,
+        })
+        .flat_map(|(from, values)| {
+            values
+                .iter()
+                .filter_map(|(_, value)| shred_version(value))
+                .collect::<Vec<_>>()
+                .windows(2)
+                .filter(|pair| pair[0] != pair[1])
+                .map(|pair| (from, pair[0], pair[1]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // both kinds of contact info report the same change
+    changes.sort_by_key(|(from, _, _)| *from);
+    changes.dedup();
+    changes
+}
+
+/// Highest slot voted by each node, with the local time it was first seen.
+#[derive(Default, Debug)]
+pub struct VoteTracker {
+    votes: HashMap<Pubkey, (Slot, u64)>,
+}
+
+impl VoteTracker {
+    /// Track the latest slot of a `Vote` value, returns true if it is higher
+    /// than the known one. Votes are not received in order, lower slots are ignored.
+    pub fn insert(&mut self, value: &CrdsValue, now_ms: u64) -> bool {
+        match &value.data {
+            CrdsData::Vote(_, vote) => match (value.data.pubkey(), vote.slots().last()) {
+                (Some(from), Some(slot)) => self.update(from, *slot, now_ms),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    pub fn update(&mut self, from: Pubkey, slot: Slot, now_ms: u64) -> bool {
+        match self.votes.get(&from) {
+            Some((current, _)) if *current >= slot => false,
+            _ => {
+                self.votes.insert(from, (slot, now_ms));
+                true
+            }
+        }
+    }
+
+    /// Highest voted slot of the node and when it was seen.
+    pub fn last_vote(&self, pubkey: &Pubkey) -> Option<(Slot, u64)> {
+        self.votes.get(pubkey).copied()
+    }
+
+    /// Slots the node is behind the cluster tip, zero when it is ahead of it.
+    pub fn lag(&self, pubkey: &Pubkey, cluster_tip: Slot) -> Option<Slot> {
+        self.votes
+            .get(pubkey)
+            .map(|(slot, _)| cluster_tip.saturating_sub(*slot))
+    }
+
+    /// Highest slot voted by any node.
+    pub fn highest_slot(&self) -> Option<Slot> {
+        self.votes.values().map(|(slot, _)| *slot).max()
+    }
+
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+}
+
+/// Highest slot each node voted on or reported in its epoch slots, computed
+/// once when the value is received, the input of the cluster tip.
+#[derive(Default, Debug)]
+pub struct HighestSlots {
+    slots: HashMap<Pubkey, Slot>,
+}
+
+impl HighestSlots {
+    /// Track the highest slot of a `Vote` or `EpochSlots` value, lower slots
+    /// than the known one of the node are ignored.
+    pub fn insert(&mut self, value: &CrdsValue) {
+        let slot = match &value.data {
+            CrdsData::Vote(_, vote) => vote.slots().last().copied(),
+            CrdsData::EpochSlots(_, slots) => slots.highest_slot(),
+            _ => None,
+        };
+        if let (Some(from), Some(slot)) = (value.data.pubkey(), slot) {
+            let highest = self.slots.entry(from).or_default();
+            *highest = (*highest).max(slot);
+        }
+    }
+
+    /// Keep the nodes matching the predicate.
+    pub fn retain(&mut self, mut f: impl FnMut(&Pubkey) -> bool) {
+        self.slots.retain(|pubkey, _| f(pubkey));
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<Slot> {
+        self.slots.get(pubkey).copied()
+    }
+
+    pub fn cluster_tip(&self) -> Option<Slot> {
+        estimate_cluster_tip(self.slots.values().copied())
+    }
+}
+
+/// Incomplete duplicate shred proofs are dropped this long after their first chunk.
+pub const DUPLICATE_SHRED_PROOF_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// Duplicate slot proof of a node, reassembled from the `DuplicateShred` chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateShredProof {
+    pub from: Pubkey,
+    pub slot: Slot,
+    pub num_chunks: u8,
+    chunks: BTreeMap<u8, Vec<u8>>,
+    /// local time, in milliseconds since the epoch, of the first chunk
+    pub first_seen_ms: u64,
+}
+
+impl DuplicateShredProof {
+    pub fn received_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.chunks.len() == usize::from(self.num_chunks)
+    }
+
+    /// The serialized proof, once every chunk is received.
+    pub fn payload(&self) -> Option<Vec<u8>> {
+        self.is_complete()
+            .then(|| self.chunks.values().flatten().copied().collect())
+    }
+}
+
+/// Duplicate shred proofs gossiped by the nodes, keyed by reporter and accused slot.
+#[derive(Default, Debug)]
+pub struct DuplicateShredTracker {
+    proofs: HashMap<(Pubkey, Slot), DuplicateShredProof>,
+}
+
+impl DuplicateShredTracker {
+    /// Add the chunk to its proof, returns true if it was not known. Chunks
+    /// out of range or disagreeing on the number of chunks are ignored.
+    pub fn insert(&mut self, shred: &DuplicateShred, now_ms: u64) -> bool {
+        if shred.chunk_index >= shred.num_chunks {
+            return false;
+        }
+        let proof = self
+            .proofs
+            .entry((shred.from, shred.slot))
+            .or_insert_with(|| DuplicateShredProof {
+                from: shred.from,
+                slot: shred.slot,
+                num_chunks: shred.num_chunks,
+                chunks: BTreeMap::new(),
+                first_seen_ms: now_ms,
+            });
+        if proof.num_chunks != shred.num_chunks || proof.chunks.contains_key(&shred.chunk_index) {
+            return false;
+        }
+        proof.chunks.insert(shred.chunk_index, shred.chunk.clone());
+        true
+    }
+
+    /// Drop the proofs still incomplete `timeout` after their first chunk,
+    /// returns how many were dropped.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn purge_incomplete(&mut self, timeout: Duration, now_ms: u64) -> usize {
+        let cutoff = now_ms.saturating_sub(timeout.as_millis() as u64);
+        let before = self.proofs.len();
+        self.proofs
+            .retain(|_, proof| proof.is_complete() || proof.first_seen_ms >= cutoff);
+        before - self.proofs.len()
+    }
+
+    /// Proofs grouped by accused slot, the reporters in pubkey order.
+    pub fn by_slot(&self) -> BTreeMap<Slot, Vec<&DuplicateShredProof>> {
+        let mut slots: BTreeMap<Slot, Vec<&DuplicateShredProof>> = BTreeMap::new();
+        for proof in self.proofs.values() {
+            slots.entry(proof.slot).or_default().push(proof);
+        }
+        for proofs in slots.values_mut() {
+            proofs.sort_by_key(|proof| proof.from);
+        }
+        slots
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+}
+
+/// Values failing their signature verification, counted by origin. Many
+/// failures from one origin hint at a spoofing or corrupting peer.
+#[derive(Default, Debug)]
+pub struct VerificationTracker {
+    failures: HashMap<Pubkey, u64>,
+}
+
+impl VerificationTracker {
+    pub fn record_failure(&mut self, origin: Pubkey) {
+        *self.failures.entry(origin).or_default() += 1;
+    }
+
+    pub fn verification_failures(&self) -> &HashMap<Pubkey, u64> {
+        &self.failures
+    }
+
+    /// The origins with their failures, the most failing first.
+    pub fn bad_peers(&self) -> Vec<(Pubkey, u64)> {
+        let mut peers = self
+            .failures
+            .iter()
+            .map(|(origin, failures)| (*origin, *failures))
+            .collect::<Vec<_>>();
+        peers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        peers
+    }
+}
+
+/// Node instances are kept this long to look for conflicts.
+pub const NODE_INSTANCE_CONFLICT_WINDOW: Duration = Duration::from_mins(2);
+
+/// Pubkeys announced by more than one running process at the same time: the
+/// wallclocks of a token span past the first wallclock of another one. A
+/// restarted node switches token once and is not reported.
+pub fn detect_instance_conflicts(instances: &[NodeInstance]) -> Vec<Pubkey> {
+    // first and last wallclock of each token
+    let mut spans: HashMap<Pubkey, HashMap<u64, (u64, u64)>> = HashMap::new();
+    for instance in instances {
+        let span = spans
+            .entry(instance.from)
+            .or_default()
+            .entry(instance.token)
+            .or_insert((instance.wallclock, instance.wallclock));
+        span.0 = span.0.min(instance.wallclock);
+        span.1 = span.1.max(instance.wallclock);
+    }
+    let mut conflicts = spans
+        .into_iter()
+        .filter(|(_, tokens)| {
+            let mut spans = tokens.values().copied().collect::<Vec<_>>();
+            spans.sort_unstable();
+            spans.windows(2).any(|pair| pair[1].0 <= pair[0].1)
+        })
+        .map(|(from, _)| from)
+        .collect::<Vec<_>>();
+    conflicts.sort();
+    conflicts
+}
+
+/// Node instances received within `NODE_INSTANCE_CONFLICT_WINDOW`.
+#[derive(Default, Debug)]
+pub struct NodeInstanceTracker {
+    instances: Vec<NodeInstance>,
+}
+
+impl NodeInstanceTracker {
+    /// Keep the instance, returns false when it is already known.
+    pub fn insert(&mut self, instance: &NodeInstance) -> bool {
+        if self.instances.contains(instance) {
+            return false;
+        }
+        self.instances.push(instance.clone());
+        true
+    }
+
+    /// Drop the instances whose wallclock is out of the conflict window.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn purge_stale(&mut self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(NODE_INSTANCE_CONFLICT_WINDOW.as_millis() as u64);
+        self.instances
+            .retain(|instance| instance.wallclock >= cutoff);
+    }
+
+    pub fn conflicts(&self) -> Vec<Pubkey> {
+        detect_instance_conflicts(&self.instances)
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use solana_sdk::{
+        hash::Hash,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+        vote,
+    };
+
+    use super::*;
+    use crate::protocol::{EpochSlots, LegacyContactInfo, NodeInstance, ShredType, Vote};
+
+    fn create_node_instance(keypair: &Keypair, wallclock: u64, token: u64) -> CrdsValue {
+        let data = CrdsData::NodeInstance(NodeInstance {
+            from: keypair.pubkey(),
+            wallclock,
+            timestamp: wallclock,
+            token,
+        });
+        CrdsValue::new_signed(data, keypair)
+    }
+
+    #[test]
+    fn test_crds_table_export_json() {
+        let keypair = Keypair::new();
+        let mut table = CrdsTable::default();
+        table
+            .insert(create_node_instance(&keypair, 1_000, 1))
+            .unwrap();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            wallclock: 2_000,
+            ..LegacyContactInfo::default()
+        };
+        table
+            .insert(CrdsValue::new_signed(
+                CrdsData::LegacyContactInfo(Box::new(info)),
+                &keypair,
+            ))
+            .unwrap();
+        table.insert(create_vote(&keypair, 0, vec![42])).unwrap();
+
+        let path = std::env::temp_dir().join(format!("crds-{}.json", keypair.pubkey()));
+        table.export_json(&path).unwrap();
+        let export: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let pubkey = keypair.pubkey().to_string();
+        assert!(export["timestamp"].as_u64().unwrap() > 0);
+        let value = &export["values"][0];
+        assert_eq!(value["data"]["LegacyContactInfo"]["id"], pubkey.as_str());
+        let value = &export["values"][1];
+        assert_eq!(value["pubkey"], pubkey.as_str());
+        assert_eq!(value["wallclock"], 1_000);
+        assert_eq!(value["data"]["NodeInstance"]["from"], pubkey.as_str());
+        assert_eq!(value["data"]["NodeInstance"]["token"], 1);
+        // the pubkeys are base58 wherever they are nested
+        let vote = &export["values"][2]["data"]["Vote"][1];
+        assert_eq!(vote["from"], pubkey.as_str());
+        let account_keys = vote["transaction"]["message"]["accountKeys"]
+            .as_array()
+            .unwrap();
+        assert!(account_keys.contains(&Value::from(pubkey.as_str())));
+        assert!(account_keys.contains(&Value::from(vote::program::id().to_string())));
+    }
+
+    #[test]
+    fn test_crds_table_insert() {
+        let keypair = Keypair::new();
+        let label = CrdsValueLabel::NodeInstance(keypair.pubkey());
+        let mut table = CrdsTable::default();
+        assert!(table.is_empty());
+
+        let value = create_node_instance(&keypair, 1_000, 1);
+        assert_eq!(table.insert(value.clone()), Ok(()));
+        assert_eq!(table.get(&label), Some(&value));
+
+        // newer wallclock overrides the stored value
+        let newer = create_node_instance(&keypair, 2_000, 2);
+        assert_eq!(table.insert(newer.clone()), Ok(()));
+        assert_eq!(table.get(&label), Some(&newer));
+
+        // older or equal wallclock is discarded
+        assert_eq!(
+            table.insert(create_node_instance(&keypair, 1_500, 3)),
+            Err(InsertError::Outdated)
+        );
+        assert_eq!(
+            table.insert(create_node_instance(&keypair, 2_000, 4)),
+            Err(InsertError::Outdated)
+        );
+        assert_eq!(table.get(&label), Some(&newer));
+        assert_eq!(table.len(), 1);
+
+        // values from another node are stored separately
+        let other = Keypair::new();
+        assert_eq!(table.insert(create_node_instance(&other, 500, 5)), Ok(()));
+        assert_eq!(table.len(), 2);
+
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            ..LegacyContactInfo::default()
+        };
+        let info = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+        table.insert(info.clone()).unwrap();
+        assert_eq!(table.values_from(keypair.pubkey()), vec![&info, &newer]);
+        assert_eq!(table.values_from(other.pubkey()).len(), 1);
+        assert!(table.values_from(Pubkey::new_unique()).is_empty());
+    }
+
+    #[test]
+    fn test_crds_table_insert_future_wallclock() {
+        let keypair = Keypair::new();
+        let label = CrdsValueLabel::NodeInstance(keypair.pubkey());
+        let mut table = CrdsTable::default();
+        let now = since_the_epoch_millis();
+
+        // within the clock skew
+        let value = create_node_instance(&keypair, now + 5_000, 1);
+        assert_eq!(table.insert(value.clone()), Ok(()));
+        assert_eq!(table.get(&label), Some(&value));
+
+        // an hour ahead, it would never be replaced
+        let future = create_node_instance(&keypair, now + 3_600_000, 2);
+        assert!(matches!(
+            table.insert(future),
+            Err(InsertError::FutureWallclock { wallclock, .. }) if wallclock == now + 3_600_000
+        ));
+        assert_eq!(table.get(&label), Some(&value));
+
+        let mut strict = CrdsTable::with_max_clock_skew(Duration::ZERO);
+        assert!(strict.insert(value).is_err());
+        assert_eq!(
+            strict.insert(create_node_instance(&keypair, now, 3)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_crds_table_counts_by_kind() {
+        let mut table = CrdsTable::default();
+        assert!(table.counts_by_kind().is_empty());
+
+        for _ in 0..3 {
+            let keypair = Keypair::new();
+            table
+                .insert(create_node_instance(&keypair, 1_000, 1))
+                .unwrap();
+            let info = LegacyContactInfo {
+                id: keypair.pubkey(),
+                ..LegacyContactInfo::default()
+            };
+            table
+                .insert(CrdsValue::new_signed(
+                    CrdsData::LegacyContactInfo(Box::new(info)),
+                    &keypair,
+                ))
+                .unwrap();
+        }
+        let keypair = Keypair::new();
+        table.insert(create_vote(&keypair, 0, vec![100])).unwrap();
+        table.insert(create_vote(&keypair, 1, vec![101])).unwrap();
+
+        let counts = table.counts_by_kind();
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![("LegacyContactInfo", 3), ("NodeInstance", 3), ("Vote", 2)]
+        );
+    }
+
+    #[test]
+    fn test_crds_table_find_missing() {
+        let mut table = CrdsTable::default();
+        let values = (0..16)
+            .map(|token| create_node_instance(&Keypair::new(), 1_000, token))
+            .collect::<Vec<_>>();
+        for value in &values {
+            table.insert(value.clone()).unwrap();
+        }
+
+        // a single partition, the peer already has the first value
+        let mut filter = CrdsFilter::new(16, 0.1, 7424);
+        filter.filter.add(&values[0].hash());
+        let missing = table.find_missing(&filter);
+        assert_eq!(missing.len(), 15);
+        assert!(!missing.contains(&values[0]));
+
+        // each value falls in exactly one partition
+        let filters = CrdsFilter::filters(16, 2);
+        assert_eq!(filters.len(), 8);
+        let mut missing = filters
+            .iter()
+            .flat_map(|filter| table.find_missing(filter))
+            .map(|value| value.signature)
+            .collect::<Vec<_>>();
+        missing.sort();
+        let mut signatures = values
+            .iter()
+            .map(|value| value.signature)
+            .collect::<Vec<_>>();
+        signatures.sort();
+        assert_eq!(missing, signatures);
+    }
+
+    #[test]
+    fn test_crds_table_purge_stale() {
+        let now = 1_681_834_717_999;
+        let stale = Keypair::new();
+        let fresh = Keypair::new();
+
+        let mut table = CrdsTable::default();
+        table
+            .insert(create_node_instance(&stale, now - 120_000, 1))
+            .unwrap();
+        table
+            .insert(create_node_instance(&fresh, now - 30_000, 2))
+            .unwrap();
+
+        let ttl = CrdsTtl::new(Duration::from_mins(1));
+        assert_eq!(table.purge_stale(&ttl, now), 1);
+        assert_eq!(table.len(), 1);
+        assert!(table
+            .get(&CrdsValueLabel::NodeInstance(stale.pubkey()))
+            .is_none());
+        assert!(table
+            .get(&CrdsValueLabel::NodeInstance(fresh.pubkey()))
+            .is_some());
+
+        // nothing left to purge
+        assert_eq!(table.purge_stale(&ttl, now), 0);
+        // a stale value can be inserted again
+        assert_eq!(table.insert(create_node_instance(&stale, now, 3)), Ok(()));
+    }
+
+    #[test]
+    fn test_crds_table_history() {
+        let keypair = Keypair::new();
+        let label = CrdsValueLabel::NodeInstance(keypair.pubkey());
+        let mut history = CrdsTableHistory::new(3);
+        assert!(history.history(&label).is_empty());
+
+        for token in 0..4 {
+            history.push(create_node_instance(&keypair, 1_000 + token, token), token);
+        }
+        history.push(create_node_instance(&Keypair::new(), 1_000, 9), 3);
+        assert_eq!(history.len(), 2);
+
+        // the oldest of the N+1 values is evicted
+        let tokens = history
+            .history(&label)
+            .iter()
+            .map(|(received_at, value)| match &value.data {
+                CrdsData::NodeInstance(instance) => (*received_at, instance.token),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, vec![(1, 1), (2, 2), (3, 3)]);
+
+        assert_eq!(history.purge_stale(Duration::from_millis(10), 13), 0);
+        assert_eq!(history.purge_stale(Duration::from_millis(10), 14), 2);
+        assert!(history.is_empty());
+        assert_eq!(CrdsTableHistory::new(0).depth(), 1);
+    }
+
+    #[test]
+    fn test_detect_shred_version_changes() {
+        let keypair = Keypair::new();
+        let create_info = |shred_version, wallclock| {
+            let info = LegacyContactInfo {
+                id: keypair.pubkey(),
+                shred_version,
+                wallclock,
+                ..LegacyContactInfo::default()
+            };
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair)
+        };
+        let mut history = CrdsTableHistory::new(4);
+        history.push(create_info(50_093, 1_000), 1_000);
+        history.push(create_info(50_093, 2_000), 2_000);
+        assert!(detect_shred_version_changes(&history).is_empty());
+
+        history.push(create_info(4_711, 3_000), 3_000);
+        assert_eq!(
+            detect_shred_version_changes(&history),
+            vec![(keypair.pubkey(), 50_093, 4_711)]
+        );
+
+        // other nodes and values are not reported
+        history.push(create_node_instance(&keypair, 4_000, 1), 4_000);
+        history.push(create_info(4_711, 4_000), 4_000);
+        assert_eq!(detect_shred_version_changes(&history).len(), 1);
+    }
+
+    #[test]
+    fn test_detect_instance_conflicts() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instance = |from, token, wallclock| NodeInstance {
+            from,
+            wallclock,
+            timestamp: 0,
+            token,
+        };
+
+        // restarted, the old token is not seen after the new one
+        let mut tracker = NodeInstanceTracker::default();
+        for value in [
+            instance(first, 1, 1_000),
+            instance(first, 1, 2_000),
+            instance(first, 2, 3_000),
+            instance(first, 2, 4_000),
+            instance(second, 7, 1_500),
+        ] {
+            assert!(tracker.insert(&value));
+        }
+        assert!(!tracker.insert(&instance(first, 2, 4_000)));
+        assert!(tracker.conflicts().is_empty());
+
+        // both tokens keep being announced
+        tracker.insert(&instance(first, 1, 3_500));
+        assert_eq!(tracker.conflicts(), vec![first]);
+        assert_eq!(
+            detect_instance_conflicts(&[instance(second, 7, 5), instance(second, 8, 5)]),
+            vec![second]
+        );
+
+        // the conflict is forgotten once out of the window
+        tracker.purge_stale(4_000 + 120_000);
+        assert_eq!(tracker.len(), 1);
+        assert!(tracker.conflicts().is_empty());
+    }
+
+    fn create_vote(keypair: &Keypair, index: VoteIndex, slots: Vec<Slot>) -> CrdsValue {
+        let instruction = vote::instruction::vote(
+            &Pubkey::new_unique(),
+            &keypair.pubkey(),
+            vote::state::Vote::new(slots, Hash::default()),
+        );
+        let transaction = Transaction::new_with_payer(&[instruction], Some(&keypair.pubkey()));
+        let vote = Vote::new(keypair.pubkey(), transaction, 1_000);
+        CrdsValue::new_signed(CrdsData::Vote(index, vote), keypair)
+    }
+
+    #[test]
+    fn test_crds_table_purge_stale_by_kind() {
+        let keypair = Keypair::new();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            wallclock: 1_000,
+            ..LegacyContactInfo::default()
+        };
+        let info = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+        let vote = create_vote(&keypair, 0, vec![100]);
+        let mut table = CrdsTable::default();
+        table.insert(info).unwrap();
+        table.insert(vote.clone()).unwrap();
+
+        // both values are as old, only the vote is past its age
+        let now = 1_000 + 300_000;
+        let mut ttl = CrdsTtl::new(Duration::from_mins(15));
+        assert_eq!(ttl.ttl_for_kind("Vote"), CRDS_VOTE_TTL);
+        assert_eq!(table.purge_stale(&ttl, now), 1);
+        assert!(table.get(&vote.label()).is_none());
+        assert!(table
+            .get(&CrdsValueLabel::LegacyContactInfo(keypair.pubkey()))
+            .is_some());
+
+        // the age of the votes can be raised
+        ttl.set("Vote", Duration::from_mins(10)).unwrap();
+        table.insert(vote).unwrap();
+        assert_eq!(table.purge_stale(&ttl, now), 0);
+        assert!(ttl.set("Votes", Duration::from_mins(10)).is_err());
+    }
+
+    #[test]
+    fn test_verification_tracker() {
+        let origin = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut tracker = VerificationTracker::default();
+        assert!(tracker.bad_peers().is_empty());
+
+        tracker.record_failure(other);
+        tracker.record_failure(origin);
+        tracker.record_failure(origin);
+        assert_eq!(tracker.verification_failures().get(&origin), Some(&2));
+        assert_eq!(tracker.bad_peers(), vec![(origin, 2), (other, 1)]);
+    }
+
+    #[test]
+    fn test_vote_tracker() {
+        let keypair = Keypair::new();
+        let other = Keypair::new();
+        let mut tracker = VoteTracker::default();
+
+        assert!(tracker.insert(&create_vote(&keypair, 0, vec![99, 100]), 1_000));
+        assert_eq!(tracker.last_vote(&keypair.pubkey()), Some((100, 1_000)));
+        assert_eq!(tracker.lag(&keypair.pubkey(), 110), Some(10));
+
+        // the second vote moves the node forward, an older one is ignored
+        assert!(tracker.insert(&create_vote(&keypair, 1, vec![108]), 2_000));
+        assert!(!tracker.insert(&create_vote(&keypair, 2, vec![104]), 3_000));
+        assert_eq!(tracker.last_vote(&keypair.pubkey()), Some((108, 2_000)));
+        assert_eq!(tracker.lag(&keypair.pubkey(), 110), Some(2));
+        assert_eq!(tracker.lag(&keypair.pubkey(), 100), Some(0));
+
+        assert!(!tracker.insert(&create_node_instance(&other, 1_000, 1), 1_000));
+        assert!(!tracker.insert(&create_vote(&other, 0, vec![]), 1_000));
+        assert_eq!(tracker.lag(&other.pubkey(), 110), None);
+
+        assert!(tracker.insert(&create_vote(&other, 0, vec![112]), 1_000));
+        assert_eq!(tracker.highest_slot(), Some(112));
+        assert_eq!(tracker.len(), 2);
+    }
+
+    #[test]
+    fn test_highest_slots() {
+        let mut highest = HighestSlots::default();
+        let voters = (0..10).map(|_| Keypair::new()).collect::<Vec<_>>();
+        for (slot, keypair) in (100..).zip(&voters) {
+            highest.insert(&create_vote(keypair, 0, vec![slot - 1, slot]));
+        }
+        let ahead = Keypair::new();
+        highest.insert(&create_vote(&ahead, 0, vec![1_000_000]));
+        assert_eq!(highest.get(&voters[0].pubkey()), Some(100));
+        assert_eq!(highest.cluster_tip(), Some(109));
+
+        // the epoch slots of the slowest voter move it to the front, an older
+        // vote does not move it back
+        let slowest = &voters[0];
+        let epoch_slots = EpochSlots::from_slots(slowest.pubkey(), &[200, 207], 1_000);
+        highest.insert(&CrdsValue::new_signed(
+            CrdsData::EpochSlots(0, epoch_slots),
+            slowest,
+        ));
+        highest.insert(&create_vote(slowest, 1, vec![150]));
+        assert_eq!(highest.get(&slowest.pubkey()), Some(207));
+        assert_eq!(highest.cluster_tip(), Some(207));
+
+        highest.insert(&create_node_instance(&ahead, 1_000, 1));
+        assert_eq!(highest.get(&ahead.pubkey()), Some(1_000_000));
+
+        highest.retain(|pubkey| *pubkey == slowest.pubkey());
+        assert_eq!(highest.get(&ahead.pubkey()), None);
+        assert_eq!(highest.cluster_tip(), Some(207));
+    }
+
+    #[test]
+    fn test_duplicate_shred_tracker() {
+        let reporter = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let chunk = |from, slot, num_chunks, index: u8| {
+            DuplicateShred::new(
+                from,
+                1_000,
+                slot,
+                ShredType::Data,
+                num_chunks,
+                index,
+                vec![index; 2],
+            )
+        };
+        let mut tracker = DuplicateShredTracker::default();
+
+        assert!(tracker.insert(&chunk(reporter, 100, 3, 0), 1_000));
+        assert!(tracker.insert(&chunk(reporter, 100, 3, 2), 1_000));
+        assert!(!tracker.insert(&chunk(reporter, 100, 3, 2), 1_000));
+        // out of range and inconsistent chunks
+        assert!(!tracker.insert(&chunk(reporter, 100, 3, 3), 1_000));
+        assert!(!tracker.insert(&chunk(reporter, 100, 4, 1), 1_000));
+
+        let slots = tracker.by_slot();
+        assert_eq!(slots[&100][0].received_chunks(), 2);
+        assert!(!slots[&100][0].is_complete());
+        assert_eq!(slots[&100][0].payload(), None);
+
+        assert!(tracker.insert(&chunk(reporter, 100, 3, 1), 2_000));
+        let slots = tracker.by_slot();
+        assert!(slots[&100][0].is_complete());
+        assert_eq!(slots[&100][0].payload(), Some(vec![0, 0, 1, 1, 2, 2]));
+
+        // a second reporter of the same slot, then one never completed
+        assert!(tracker.insert(&chunk(other, 100, 1, 0), 1_000));
+        assert!(tracker.insert(&chunk(other, 200, 2, 0), 1_000));
+        assert_eq!(tracker.by_slot()[&100].len(), 2);
+        assert_eq!(tracker.len(), 3);
+
+        assert_eq!(tracker.purge_incomplete(Duration::from_mins(1), 30_000), 0);
+        assert_eq!(tracker.purge_incomplete(Duration::from_mins(1), 70_000), 1);
+        assert_eq!(
+            tracker.by_slot().keys().copied().collect::<Vec<_>>(),
+            vec![100]
+        );
+    }
+
+    #[test]
+    fn test_crds_table_snapshot_and_accounts_hashes() {
+        let keypair = Keypair::new();
+        let create_hashes = |slot| SnapshotHashes {
+            from: keypair.pubkey(),
+            hashes: vec![(slot, Hash::new_unique())],
+            wallclock: 1_000,
+        };
+        let snapshot = create_hashes(100);
+        let accounts = create_hashes(200);
+
+        // same pubkey and wallclock, neither replaces the other
+        let mut table = CrdsTable::default();
+        assert_eq!(
+            table.insert(CrdsValue::new_signed(
+                CrdsData::SnapshotHashes(snapshot.clone()),
+                &keypair
+            )),
+            Ok(())
+        );
+        assert_eq!(
+            table.insert(CrdsValue::new_signed(
+                CrdsData::AccountsHashes(accounts.clone()),
+                &keypair
+            )),
+            Ok(())
+        );
+        assert_eq!(table.len(), 2);
+
+        assert_eq!(
+            table.snapshot_hashes(keypair.pubkey(), SnapshotHashesKind::Snapshot),
+            Some(&snapshot)
+        );
+        assert_eq!(
+            table.snapshot_hashes(keypair.pubkey(), SnapshotHashesKind::Accounts),
+            Some(&accounts)
+        );
+        assert_eq!(
+            table.snapshot_hashes(Pubkey::new_unique(), SnapshotHashesKind::Snapshot),
+            None
+        );
+
+        let kinds = table
+            .values()
+            .filter_map(|value| value.data.snapshot_hashes())
+            .map(|(kind, _)| kind)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            kinds,
+            HashSet::from([SnapshotHashesKind::Snapshot, SnapshotHashesKind::Accounts])
+        );
+    }
+}
@@ -13,9 +13,31 @@ pub enum ErrorKind {
     #[error("Encode error")]
     EncodeError,
 
+    #[error("Slots length error num:{num} bits:{bits}")]
+    SlotsLengthError { num: usize, bits: u64 },
+
+    #[error("Too many slots num:{num} max:{max}")]
+    TooManySlots { num: usize, max: usize },
+
+    #[error("Slot overflow first_slot:{first_slot} index:{index}")]
+    SlotOverflow { first_slot: u64, index: u64 },
+
+    #[error("Packet too large size:{size} max:{max}")]
+    PacketTooLarge { size: u64, max: u64 },
+
     #[error(transparent)]
     IoError(#[from] std::io::Error),
 
     #[error(transparent)]
     BincodeError(#[from] bincode::Error),
 }
+
+/// Why a value was not inserted in the CRDS table.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum InsertError {
+    #[error("Outdated wallclock, the stored value is as recent")]
+    Outdated,
+
+    #[error("Future wallclock:{wallclock} now:{now}")]
+    FutureWallclock { wallclock: u64, now: u64 },
+}
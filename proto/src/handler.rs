@@ -0,0 +1,129 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::protocol::{CrdsFilter, CrdsValue, Ping, Pong, Protocol, PruneData};
+
+/// Callbacks invoked by `dispatch`, one per `Protocol` variant.
+/// Every callback does nothing by default.
+pub trait ProtocolHandler {
+    fn on_pull_request(&mut self, _filter: CrdsFilter, _caller: CrdsValue) {}
+
+    fn on_pull_response(&mut self, _from: Pubkey, _values: Vec<CrdsValue>) {}
+
+    fn on_push_message(&mut self, _from: Pubkey, _values: Vec<CrdsValue>) {}
+
+    fn on_prune(&mut self, _from: Pubkey, _data: PruneData) {}
+
+    fn on_ping(&mut self, _ping: Ping) {}
+
+    fn on_pong(&mut self, _pong: Pong) {}
+}
+
+/// Hand the message over to the handler callback of its variant.
+pub fn dispatch(protocol: Protocol, handler: &mut impl ProtocolHandler) {
+    match protocol {
+        Protocol::PullRequest(filter, caller) => handler.on_pull_request(filter, caller),
+        Protocol::PullResponse(from, values) => handler.on_pull_response(from, values),
+        Protocol::PushMessage(from, values) => handler.on_push_message(from, values),
+        Protocol::PruneMessage(from, data) => handler.on_prune(from, data),
+        Protocol::PingMessage(ping) => handler.on_ping(ping),
+        Protocol::PongMessage(pong) => handler.on_pong(pong),
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signature};
+
+    use super::*;
+    use crate::protocol::CrdsData;
+
+    fn prune_data(pubkey: Pubkey) -> PruneData {
+        PruneData {
+            pubkey,
+            prunes: vec![],
+            signature: Signature::default(),
+            destination: Pubkey::new_unique(),
+            wallclock: 0,
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingHandler {
+        pull_requests: usize,
+        pull_responses: usize,
+        push_messages: usize,
+        prunes: usize,
+        pings: usize,
+        pongs: usize,
+        values: usize,
+    }
+
+    impl ProtocolHandler for CountingHandler {
+        fn on_pull_request(&mut self, _filter: CrdsFilter, _caller: CrdsValue) {
+            self.pull_requests += 1;
+        }
+
+        fn on_pull_response(&mut self, _from: Pubkey, values: Vec<CrdsValue>) {
+            self.pull_responses += 1;
+            self.values += values.len();
+        }
+
+        fn on_push_message(&mut self, _from: Pubkey, values: Vec<CrdsValue>) {
+            self.push_messages += 1;
+            self.values += values.len();
+        }
+
+        fn on_prune(&mut self, _from: Pubkey, _data: PruneData) {
+            self.prunes += 1;
+        }
+
+        fn on_ping(&mut self, _ping: Ping) {
+            self.pings += 1;
+        }
+
+        fn on_pong(&mut self, _pong: Pong) {
+            self.pongs += 1;
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let keypair = Keypair::new();
+        let from = Pubkey::new_unique();
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::default()), &keypair);
+        let ping = Ping::new(&keypair);
+        let answer = Pong::new(&ping, &keypair).unwrap();
+
+        let messages = vec![
+            Protocol::PullRequest(CrdsFilter::default(), value.clone()),
+            Protocol::PullResponse(from, vec![value.clone(), value.clone()]),
+            Protocol::PushMessage(from, vec![value]),
+            Protocol::PruneMessage(from, prune_data(from)),
+            Protocol::PingMessage(ping.clone()),
+            Protocol::PingMessage(ping),
+            Protocol::PongMessage(answer),
+        ];
+
+        let mut handler = CountingHandler::default();
+        for message in messages {
+            dispatch(message, &mut handler);
+        }
+        assert_eq!(handler.pull_requests, 1);
+        assert_eq!(handler.pull_responses, 1);
+        assert_eq!(handler.push_messages, 1);
+        assert_eq!(handler.prunes, 1);
+        assert_eq!(handler.pings, 2);
+        assert_eq!(handler.pongs, 1);
+        assert_eq!(handler.values, 3);
+    }
+
+    #[test]
+    fn test_dispatch_default_callbacks() {
+        struct Ignore;
+        impl ProtocolHandler for Ignore {}
+
+        let from = Pubkey::new_unique();
+        dispatch(Protocol::PruneMessage(from, prune_data(from)), &mut Ignore);
+    }
+}
@@ -1,7 +1,11 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::must_use_candidate)]
+pub mod crds;
 pub mod errors;
+pub mod handler;
+pub mod json;
+pub mod net;
 pub mod protocol;
 pub mod utils;
 pub mod wire;
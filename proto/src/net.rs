@@ -0,0 +1,375 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    protocol::{CrdsFilter, CrdsValue, Protocol},
+    utils::{canonical_addr, dest_addr_for},
+    wire::PACKET_DATA_SIZE,
+};
+
+/// An entrypoint not answering for this long is skipped while the others answer.
+pub const ENTRYPOINT_SILENCE: Duration = Duration::from_secs(30);
+
+/// Entrypoints contacted in turn, with the time of their last answer.
+#[derive(Debug, Clone, Default)]
+pub struct Entrypoints {
+    addrs: Vec<SocketAddr>,
+    next: usize,
+    /// first request still without an answer, per entrypoint
+    unanswered_since: HashMap<SocketAddr, Instant>,
+    last_response: HashMap<SocketAddr, Instant>,
+}
+
+impl Entrypoints {
+    pub fn new(addrs: Vec<SocketAddr>) -> Self {
+        Entrypoints {
+            addrs,
+            ..Entrypoints::default()
+        }
+    }
+
+    pub fn addrs(&self) -> &[SocketAddr] {
+        &self.addrs
+    }
+
+    pub fn is_silent(&self, addr: &SocketAddr, now: Instant) -> bool {
+        self.unanswered_since
+            .get(addr)
+            .is_some_and(|since| now.saturating_duration_since(*since) >= ENTRYPOINT_SILENCE)
+    }
+
+    /// The entrypoint to contact next, the silent ones are skipped unless all are silent.
+    pub fn next_addr(&mut self, now: Instant) -> Option<SocketAddr> {
+        let len = self.addrs.len();
+        if len == 0 {
+            return None;
+        }
+        let start = self.next % len;
+        let index = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|index| !self.is_silent(&self.addrs[*index], now))
+            .unwrap_or(start);
+        self.next = (index + 1) % len;
+
+        let addr = self.addrs[index];
+        self.unanswered_since.entry(addr).or_insert(now);
+        Some(addr)
+    }
+
+    /// Record a message received from `addr`, false when it is not an entrypoint.
+    pub fn record_response(&mut self, addr: SocketAddr, now: Instant) -> bool {
+        if !self.addrs.contains(&addr) {
+            return false;
+        }
+        self.unanswered_since.remove(&addr);
+        self.last_response.insert(addr, now);
+        true
+    }
+
+    pub fn last_response(&self, addr: &SocketAddr) -> Option<Instant> {
+        self.last_response.get(addr).copied()
+    }
+
+    /// Every entrypoint with the time of its last answer, in rotation order.
+    pub fn responses(&self) -> Vec<(SocketAddr, Option<Instant>)> {
+        self.addrs
+            .iter()
+            .map(|addr| (*addr, self.last_response(addr)))
+            .collect()
+    }
+}
+
+pub struct GossipClient {
+    socket: UdpSocket,
+    entrypoints: Entrypoints,
+}
+
+impl GossipClient {
+    pub fn new(bind_addr: SocketAddr, entrypoint: SocketAddr) -> io::Result<Self> {
+        let socket = UdpSocket::bind(bind_addr)?;
+        Ok(Self {
+            socket,
+            entrypoints: Entrypoints::new(vec![entrypoint]),
+        })
+    }
+
+    /// Replace the entrypoints, the pull requests are sent to each of them in turn.
+    pub fn set_entrypoints(&mut self, addrs: Vec<SocketAddr>) {
+        self.entrypoints = Entrypoints::new(addrs);
+    }
+
+    pub fn send_pull_request(
+        &mut self,
+        filter: CrdsFilter,
+        self_info: CrdsValue,
+    ) -> io::Result<()> {
+        let Some(addr) = self.entrypoints.next_addr(Instant::now()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "no entrypoint configured",
+            ));
+        };
+        let protocol = Protocol::PullRequest(filter, self_info);
+        self.send_to(&protocol, addr)
+    }
+
+    /// Push the values of `origin` to the peer.
+    pub fn push_to(
+        &self,
+        peer: SocketAddr,
+        origin: Pubkey,
+        values: Vec<CrdsValue>,
+    ) -> io::Result<()> {
+        self.send_to(&Protocol::PushMessage(origin, values), peer)
+    }
+
+    /// Read one datagram and parse it, `buf` should be at least `PACKET_DATA_SIZE + 1`
+    /// long, so that a datagram larger than a packet is told apart from a full one.
+    pub fn recv(&mut self, buf: &mut [u8]) -> io::Result<(Protocol, SocketAddr)> {
+        let (len, addr) = self.socket.recv_from(buf)?;
+        let addr = canonical_addr(addr);
+        let oversized = len > PACKET_DATA_SIZE;
+        if oversized {
+            warn!("datagram from {addr} larger than {PACKET_DATA_SIZE} bytes");
+        }
+        let protocol = Protocol::from_bytes(&buf[..len]).map_err(|err| {
+            let message = if oversized {
+                format!("datagram larger than {PACKET_DATA_SIZE} bytes: {err}")
+            } else {
+                err.to_string()
+            };
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })?;
+        self.entrypoints.record_response(addr, Instant::now());
+        Ok((protocol, addr))
+    }
+
+    fn send_to(&self, protocol: &Protocol, addr: SocketAddr) -> io::Result<()> {
+        let bytes = protocol
+            .to_bytes()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let local = self.socket.local_addr()?;
+        self.socket.send_to(&bytes, dest_addr_for(&local, addr))?;
+        Ok(())
+    }
+
+    pub fn entrypoints(&self) -> &Entrypoints {
+        &self.entrypoints
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::{
+        protocol::{CrdsData, LegacyContactInfo, Ping},
+        utils::parse_addr,
+    };
+
+    #[test]
+    fn test_send_pull_request() {
+        let entrypoint = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+
+        let mut client = GossipClient::new(
+            parse_addr("127.0.0.1:0").unwrap(),
+            entrypoint.local_addr().unwrap(),
+        )
+        .unwrap();
+
+        let keypair = Keypair::new();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            gossip: client.local_addr().unwrap(),
+            ..LegacyContactInfo::default()
+        };
+        let self_info =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+
+        client
+            .send_pull_request(CrdsFilter::default(), self_info.clone())
+            .unwrap();
+
+        let mut buf = [0; PACKET_DATA_SIZE];
+        let (len, addr) = entrypoint.recv_from(&mut buf).unwrap();
+        assert_eq!(addr, client.local_addr().unwrap());
+
+        let protocol = Protocol::from_bytes(&buf[..len]).unwrap();
+        assert!(matches!(protocol, Protocol::PullRequest(_, value) if value == self_info));
+    }
+
+    #[test]
+    fn test_push_to() {
+        let entrypoint = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+        let peer = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+        let client = GossipClient::new(
+            parse_addr("127.0.0.1:0").unwrap(),
+            entrypoint.local_addr().unwrap(),
+        )
+        .unwrap();
+
+        let keypair = Keypair::new();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            gossip: client.local_addr().unwrap(),
+            ..LegacyContactInfo::default()
+        };
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+
+        client
+            .push_to(
+                peer.local_addr().unwrap(),
+                keypair.pubkey(),
+                vec![value.clone()],
+            )
+            .unwrap();
+
+        let mut buf = [0; PACKET_DATA_SIZE];
+        let (len, addr) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(addr, client.local_addr().unwrap());
+        assert!(matches!(
+            Protocol::from_bytes(&buf[..len]).unwrap(),
+            Protocol::PushMessage(origin, values) if origin == keypair.pubkey() && values == vec![value]
+        ));
+    }
+
+    #[test]
+    fn test_recv() {
+        let peer = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+
+        let mut client = GossipClient::new(
+            parse_addr("127.0.0.1:0").unwrap(),
+            peer.local_addr().unwrap(),
+        )
+        .unwrap();
+        let client_addr = client.local_addr().unwrap();
+
+        let ping = Protocol::PingMessage(Ping::new(&Keypair::new()));
+        peer.send_to(&ping.to_bytes().unwrap(), client_addr)
+            .unwrap();
+
+        let mut buf = [0; PACKET_DATA_SIZE + 1];
+        let (protocol, addr) = client.recv(&mut buf).unwrap();
+        assert_eq!(protocol, ping);
+        assert_eq!(addr, peer.local_addr().unwrap());
+        assert!(client.entrypoints().last_response(&addr).is_some());
+
+        peer.send_to(&[1, 2, 3], client_addr).unwrap();
+        let err = client.recv(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!err.to_string().contains("larger"));
+
+        // a full packet is not mistaken for a larger one
+        peer.send_to(&[1; PACKET_DATA_SIZE], client_addr).unwrap();
+        let err = client.recv(&mut buf).unwrap_err();
+        assert!(!err.to_string().contains("larger"));
+
+        peer.send_to(&[1; PACKET_DATA_SIZE + 100], client_addr)
+            .unwrap();
+        let err = client.recv(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err
+            .to_string()
+            .starts_with("datagram larger than 1232 bytes"));
+    }
+
+    #[test]
+    fn test_entrypoints_round_robin() {
+        let addrs = ["10.0.0.1:8001", "10.0.0.2:8001", "10.0.0.3:8001"]
+            .map(|addr| parse_addr(addr).unwrap())
+            .to_vec();
+        let mut entrypoints = Entrypoints::new(addrs.clone());
+        let start = Instant::now();
+
+        let sent = (0..4)
+            .map(|_| entrypoints.next_addr(start).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sent, [addrs[0], addrs[1], addrs[2], addrs[0]]);
+
+        // the second one stays silent, the others keep answering
+        let later = start + ENTRYPOINT_SILENCE;
+        assert!(entrypoints.record_response(addrs[0], later));
+        assert!(entrypoints.record_response(addrs[2], later));
+        assert!(!entrypoints.record_response(parse_addr("10.0.0.4:8001").unwrap(), later));
+        assert!(entrypoints.is_silent(&addrs[1], later));
+
+        let sent = (0..3)
+            .map(|_| entrypoints.next_addr(later).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sent, [addrs[2], addrs[0], addrs[2]]);
+
+        assert_eq!(
+            entrypoints.responses(),
+            vec![
+                (addrs[0], Some(later)),
+                (addrs[1], None),
+                (addrs[2], Some(later))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entrypoints_all_silent() {
+        let addr = parse_addr("10.0.0.1:8001").unwrap();
+        let mut entrypoints = Entrypoints::new(vec![addr]);
+        let start = Instant::now();
+        assert_eq!(entrypoints.next_addr(start), Some(addr));
+        assert!(entrypoints.is_silent(&addr, start + ENTRYPOINT_SILENCE));
+        assert_eq!(
+            entrypoints.next_addr(start + ENTRYPOINT_SILENCE),
+            Some(addr)
+        );
+
+        assert_eq!(Entrypoints::default().next_addr(start), None);
+    }
+
+    #[test]
+    fn test_set_entrypoints() {
+        let first = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+        let second = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+
+        let mut client = GossipClient::new(
+            parse_addr("127.0.0.1:0").unwrap(),
+            first.local_addr().unwrap(),
+        )
+        .unwrap();
+        client.set_entrypoints(vec![
+            first.local_addr().unwrap(),
+            second.local_addr().unwrap(),
+        ]);
+
+        let self_info =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::default()), &Keypair::new());
+        for _ in 0..2 {
+            client
+                .send_pull_request(CrdsFilter::default(), self_info.clone())
+                .unwrap();
+        }
+
+        let mut buf = [0; PACKET_DATA_SIZE];
+        for entrypoint in [first, second] {
+            let (len, _) = entrypoint.recv_from(&mut buf).unwrap();
+            let protocol = Protocol::from_bytes(&buf[..len]).unwrap();
+            assert!(matches!(protocol, Protocol::PullRequest(..)));
+        }
+
+        client.set_entrypoints(vec![]);
+        let err = client
+            .send_pull_request(CrdsFilter::default(), self_info)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+    }
+}
@@ -1,23 +1,35 @@
 use std::{
-    collections::BTreeSet,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
     fmt,
-    net::{Ipv4Addr, SocketAddr},
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
 };
 
 use bincode::serialize;
-use bv::BitVec;
+use bv::{BitVec, Bits};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use log::warn;
 use serde::Serialize as SerdeSerialize;
 use serde_derive::{Deserialize, Serialize};
 
 use solana_bloom::bloom::Bloom;
 use solana_sdk::{
     hash::{self, Hash},
+    program_utils::limited_deserialize,
     pubkey::Pubkey,
+    serde_varint, short_vec,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
+    vote::{self, instruction::VoteInstruction},
 };
 
-use crate::errors::Result;
+use crate::{
+    crds::CrdsValueLabel,
+    errors::{Error, Result},
+    utils::since_the_epoch_millis,
+    wire::{deserialize_bytes, PACKET_DATA_SIZE},
+};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct LegacyContactInfo {
@@ -72,6 +84,173 @@ impl Default for LegacyContactInfo {
     }
 }
 
+/// Whether the address is set, unused services are advertised as 0.0.0.0:0.
+pub fn is_valid_addr(addr: &SocketAddr) -> bool {
+    !addr.ip().is_unspecified() && addr.port() != 0
+}
+
+/// Whether the address can be reached over the internet, the loopback, private,
+/// link-local, unspecified and multicast ranges are not.
+pub fn is_routable(addr: &SocketAddr) -> bool {
+    match addr.ip().to_canonical() {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            // fe80::/10 link-local and fc00::/7 unique local
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || first & 0xffc0 == 0xfe80
+                || first & 0xfe00 == 0xfc00)
+        }
+    }
+}
+
+fn valid_addr(addr: SocketAddr) -> Option<SocketAddr> {
+    is_valid_addr(&addr).then_some(addr)
+}
+
+impl LegacyContactInfo {
+    /// Contact info of a node taking part only in gossip, the other services are unset.
+    pub fn new_spy(keypair: &Keypair, gossip_bind: SocketAddr, shred_version: u16) -> Self {
+        LegacyContactInfo {
+            id: keypair.pubkey(),
+            gossip: gossip_bind,
+            wallclock: since_the_epoch_millis(),
+            shred_version,
+            ..LegacyContactInfo::default()
+        }
+    }
+
+    /// Copy with the wallclock bumped to `now_ms`, so that peers take it as newer.
+    #[must_use]
+    pub fn refreshed(&self, now_ms: u64) -> Self {
+        LegacyContactInfo {
+            wallclock: now_ms.max(self.wallclock + 1),
+            ..self.clone()
+        }
+    }
+
+    /// Whether the node advertises a reachable gossip address.
+    pub fn is_valid_gossip(&self) -> bool {
+        is_valid_addr(&self.gossip)
+    }
+
+    /// Address receiving the transactions, none when unset.
+    pub fn tpu_addr(&self) -> Option<SocketAddr> {
+        valid_addr(self.tpu)
+    }
+
+    /// Address receiving the transactions forwarded by the other nodes, none when unset.
+    pub fn tpu_forwards_addr(&self) -> Option<SocketAddr> {
+        valid_addr(self.tpu_forwards)
+    }
+
+    /// Address receiving the vote transactions, none when unset.
+    pub fn tpu_vote_addr(&self) -> Option<SocketAddr> {
+        valid_addr(self.tpu_vote)
+    }
+
+    /// The `serve_repair` address the shreds are requested from and the `tvu`
+    /// one they are sent to, none unless both are set.
+    pub fn repair_addrs(&self) -> Option<(SocketAddr, SocketAddr)> {
+        Some((valid_addr(self.serve_repair)?, valid_addr(self.tvu)?))
+    }
+
+    /// Whether the node advertises a JSON-RPC endpoint.
+    pub fn has_rpc(&self) -> bool {
+        is_valid_addr(&self.rpc)
+    }
+
+    pub fn matches_shred_version(&self, expected: u16) -> bool {
+        self.shred_version == expected
+    }
+
+    /// All the service addresses, labelled by service name.
+    pub fn addrs(&self) -> [(&'static str, SocketAddr); 10] {
+        [
+            ("gossip", self.gossip),
+            ("tvu", self.tvu),
+            ("tvu_forwards", self.tvu_forwards),
+            ("repair", self.repair),
+            ("tpu", self.tpu),
+            ("tpu_forwards", self.tpu_forwards),
+            ("tpu_vote", self.tpu_vote),
+            ("rpc", self.rpc),
+            ("rpc_pubsub", self.rpc_pubsub),
+            ("serve_repair", self.serve_repair),
+        ]
+    }
+
+    /// Service addresses the node actually advertises, labelled by service name.
+    pub fn valid_addrs(&self) -> Vec<(&'static str, SocketAddr)> {
+        self.addrs()
+            .into_iter()
+            .filter(|(_, addr)| is_valid_addr(addr))
+            .collect()
+    }
+
+    /// Number of services advertised, 1 for a spy which gossips only while a
+    /// voting validator sets most of them.
+    pub fn advertised_service_count(&self) -> usize {
+        self.addrs()
+            .iter()
+            .filter(|(_, addr)| is_valid_addr(addr))
+            .count()
+    }
+}
+
+/// Contact infos of the nodes belonging to the cluster with the expected shred version.
+pub fn filter_by_shred_version(
+    infos: &[LegacyContactInfo],
+    expected: u16,
+) -> Vec<&LegacyContactInfo> {
+    infos
+        .iter()
+        .filter(|info| info.matches_shred_version(expected))
+        .collect()
+}
+
+/// Contact infos of the nodes exposing a JSON-RPC endpoint.
+pub fn filter_with_rpc(infos: &[LegacyContactInfo]) -> Vec<&LegacyContactInfo> {
+    infos.iter().filter(|info| info.has_rpc()).collect()
+}
+
+/// TPU address of every node advertising one, to send the transactions to the leaders.
+pub fn collect_tpu_endpoints(infos: &[LegacyContactInfo]) -> Vec<(Pubkey, SocketAddr)> {
+    infos
+        .iter()
+        .filter_map(|info| Some((info.id, info.tpu_addr()?)))
+        .collect()
+}
+
+/// Repair addresses of every node able to serve shreds, `(serve_repair, tvu)`.
+pub fn collect_repair_peers(infos: &[LegacyContactInfo]) -> Vec<(Pubkey, SocketAddr, SocketAddr)> {
+    infos
+        .iter()
+        .filter_map(|info| {
+            let (serve_repair, tvu) = info.repair_addrs()?;
+            Some((info.id, serve_repair, tvu))
+        })
+        .collect()
+}
+
+/// Number of nodes advertising each shred version.
+pub fn shred_version_counts(infos: &[LegacyContactInfo]) -> BTreeMap<u16, usize> {
+    let mut counts = BTreeMap::new();
+    for info in infos {
+        *counts.entry(info.shred_version).or_default() += 1;
+    }
+    counts
+}
+
 pub type VoteIndex = u8;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -83,6 +262,59 @@ pub struct Vote {
 
 pub type Slot = u64;
 
+impl Vote {
+    pub fn new(from: Pubkey, transaction: Transaction, wallclock: u64) -> Self {
+        Vote {
+            from,
+            transaction,
+            wallclock,
+        }
+    }
+
+    /// The vote program instruction carried by the transaction, `None` when the
+    /// transaction holds no recognizable vote.
+    fn vote_instruction(&self) -> Option<VoteInstruction> {
+        let message = &self.transaction.message;
+        message.instructions.iter().find_map(|instruction| {
+            let program_id = message
+                .account_keys
+                .get(usize::from(instruction.program_id_index))?;
+            if !vote::program::check_id(program_id) {
+                return None;
+            }
+            limited_deserialize::<VoteInstruction>(&instruction.data)
+                .ok()
+                .filter(VoteInstruction::is_simple_vote)
+        })
+    }
+
+    /// Voted slots, the oldest first.
+    pub fn slots(&self) -> Vec<Slot> {
+        match self.vote_instruction() {
+            Some(VoteInstruction::Vote(vote) | VoteInstruction::VoteSwitch(vote, _)) => vote.slots,
+            Some(
+                VoteInstruction::UpdateVoteState(update)
+                | VoteInstruction::UpdateVoteStateSwitch(update, _)
+                | VoteInstruction::CompactUpdateVoteState(update)
+                | VoteInstruction::CompactUpdateVoteStateSwitch(update, _),
+            ) => update.slots(),
+            _ => vec![],
+        }
+    }
+
+    /// Bank hash of the last voted slot.
+    pub fn hash(&self) -> Option<Hash> {
+        match self.vote_instruction()? {
+            VoteInstruction::Vote(vote) | VoteInstruction::VoteSwitch(vote, _) => Some(vote.hash),
+            VoteInstruction::UpdateVoteState(update)
+            | VoteInstruction::UpdateVoteStateSwitch(update, _)
+            | VoteInstruction::CompactUpdateVoteState(update)
+            | VoteInstruction::CompactUpdateVoteStateSwitch(update, _) => Some(update.hash),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct SnapshotHashes {
     pub from: Pubkey,
@@ -90,12 +322,57 @@ pub struct SnapshotHashes {
     pub wallclock: u64,
 }
 
+/// What a `SnapshotHashes` advertises, told apart by the `CrdsData` variant
+/// carrying it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SnapshotHashesKind {
+    /// full snapshots the node can serve
+    Snapshot,
+    /// accounts hashes computed by the node
+    Accounts,
+}
+
+impl fmt::Display for SnapshotHashesKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotHashesKind::Snapshot => write!(f, "Snapshot Hashes"),
+            SnapshotHashesKind::Accounts => write!(f, "Accounts Hashes"),
+        }
+    }
+}
+
+fn latest_snapshot(hashes: &[(Slot, Hash)]) -> Option<&(Slot, Hash)> {
+    hashes.iter().max_by_key(|(slot, _)| *slot)
+}
+
+impl SnapshotHashes {
+    pub fn highest_slot(&self) -> Option<Slot> {
+        latest_snapshot(&self.hashes).map(|(slot, _)| *slot)
+    }
+
+    /// Hash of the snapshot at the highest slot.
+    pub fn latest_hash(&self) -> Option<&Hash> {
+        latest_snapshot(&self.hashes).map(|(_, hash)| hash)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct LegacyVersion1 {
-    major: u16,
-    minor: u16,
-    patch: u16,
-    commit: Option<u32>, // first 4 bytes of the sha1 commit hash
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub commit: Option<u32>, // first 4 bytes of the sha1 commit hash
+}
+
+impl LegacyVersion1 {
+    pub fn to_semver_string(&self) -> String {
+        format!("{}.{}.{}", self.major, self.minor, self.patch)
+    }
+
+    /// Compare the major, minor and patch numbers against a newer version format.
+    pub fn cmp_version(&self, other: &LegacyVersion2) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -114,6 +391,29 @@ pub struct LegacyVersion2 {
     pub feature_set: u32,
 }
 
+impl LegacyVersion2 {
+    pub fn to_semver_string(&self) -> String {
+        format!("{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Versions are ordered by major, minor and patch, commit and feature set
+/// only break ties to stay consistent with `Eq`.
+impl Ord for LegacyVersion2 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| self.commit.cmp(&other.commit))
+            .then_with(|| self.feature_set.cmp(&other.feature_set))
+    }
+}
+
+impl PartialOrd for LegacyVersion2 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct Version {
     pub from: Pubkey,
@@ -131,6 +431,10 @@ pub struct NodeInstance {
 
 pub type EpochSlotsIndex = u8;
 
+/// Most slots a single epoch slots chunk can describe, as the validator bounds
+/// its chunks.
+pub const MAX_SLOTS_PER_ENTRY: usize = 2048 * 8;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct Flate2 {
     pub first_slot: Slot,
@@ -151,213 +455,2396 @@ pub enum CompressedSlots {
     Uncompressed(Uncompressed),
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct EpochSlots {
-    pub from: Pubkey,
-    pub slots: Vec<CompressedSlots>,
-    pub wallclock: u64,
-}
+impl Flate2 {
+    /// The `num` read from the wire is checked before inflating, and the
+    /// inflated bytes are capped to what `MAX_SLOTS_PER_ENTRY` slots take.
+    pub fn decompress(&self) -> Result<Uncompressed> {
+        if self.num > MAX_SLOTS_PER_ENTRY {
+            return Err(Error::TooManySlots {
+                num: self.num,
+                max: MAX_SLOTS_PER_ENTRY,
+            });
+        }
+        let mut decompressed = Vec::new();
+        DeflateDecoder::new(self.compressed.as_slice())
+            .take((MAX_SLOTS_PER_ENTRY / 8) as u64)
+            .read_to_end(&mut decompressed)?;
+
+        let uncompressed = Uncompressed {
+            first_slot: self.first_slot,
+            num: self.num,
+            slots: BitVec::from(decompressed),
+        };
+        uncompressed.validate(MAX_SLOTS_PER_ENTRY)?;
+
+        Ok(uncompressed)
+    }
+}
+
+impl Uncompressed {
+    /// Reject a `num` above `max_slots` or beyond the bits carried.
+    pub fn validate(&self, max_slots: usize) -> Result<()> {
+        if self.num > max_slots {
+            return Err(Error::TooManySlots {
+                num: self.num,
+                max: max_slots,
+            });
+        }
+        let bits = self.slots.len();
+        if u64::try_from(self.num).map_or(true, |num| num > bits) {
+            return Err(Error::SlotsLengthError {
+                num: self.num,
+                bits,
+            });
+        }
+        Ok(())
+    }
+
+    /// Absolute slot numbers which are set, an error when one is beyond the
+    /// largest slot.
+    pub fn to_slots(&self) -> Result<Vec<Slot>> {
+        self.validate(MAX_SLOTS_PER_ENTRY)?;
+
+        (0..self.num as u64)
+            .filter(|index| self.slots.get_bit(*index))
+            .map(|index| {
+                self.first_slot
+                    .checked_add(index)
+                    .ok_or(Error::SlotOverflow {
+                        first_slot: self.first_slot,
+                        index,
+                    })
+            })
+            .collect()
+    }
+
+    /// Highest slot which is set, none when none is or it is beyond the largest slot.
+    pub fn highest_slot(&self) -> Option<Slot> {
+        self.validate(MAX_SLOTS_PER_ENTRY).ok()?;
+        let index = (0..self.num as u64)
+            .rev()
+            .find(|index| self.slots.get_bit(*index))?;
+        self.first_slot.checked_add(index)
+    }
+}
+
+impl CompressedSlots {
+    /// Deflate the bits, they are kept uncompressed when deflating does not
+    /// make them smaller.
+    fn compress(first_slot: Slot, num: usize, slots: BitVec<u8>) -> Self {
+        let bytes = slots.clone().into_boxed_slice();
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        match encoder.write_all(&bytes).and_then(|()| encoder.finish()) {
+            Ok(compressed) if compressed.len() < bytes.len() => CompressedSlots::Flate2(Flate2 {
+                first_slot,
+                num,
+                compressed,
+            }),
+            _ => CompressedSlots::Uncompressed(Uncompressed {
+                first_slot,
+                num,
+                slots,
+            }),
+        }
+    }
+
+    /// Absolute slot numbers which are set.
+    pub fn to_slots(&self) -> Result<Vec<Slot>> {
+        match self {
+            CompressedSlots::Flate2(flate2) => flate2.decompress()?.to_slots(),
+            CompressedSlots::Uncompressed(uncompressed) => uncompressed.to_slots(),
+        }
+    }
+
+    /// Highest slot which is set, without listing the others.
+    pub fn highest_slot(&self) -> Option<Slot> {
+        match self {
+            CompressedSlots::Flate2(flate2) => flate2.decompress().ok()?.highest_slot(),
+            CompressedSlots::Uncompressed(uncompressed) => uncompressed.highest_slot(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EpochSlots {
+    pub from: Pubkey,
+    pub slots: Vec<CompressedSlots>,
+    pub wallclock: u64,
+}
+
+impl EpochSlots {
+    /// Epoch slots advertising the slots, in chunks of at most `MAX_SLOTS_PER_ENTRY`
+    /// slots each deflated, the inverse of `to_slots`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_slots(from: Pubkey, slots: &[Slot], wallclock: u64) -> Self {
+        let mut slots = slots.to_vec();
+        slots.sort_unstable();
+        slots.dedup();
+
+        let mut chunks = vec![];
+        let mut rest = slots.as_slice();
+        while let Some(&first_slot) = rest.first() {
+            let end = rest.partition_point(|slot| slot - first_slot < MAX_SLOTS_PER_ENTRY as u64);
+            let (chunk, tail) = rest.split_at(end);
+            // below MAX_SLOTS_PER_ENTRY, it fits
+            let num = (chunk[chunk.len() - 1] - first_slot) as usize + 1;
+            let mut bits = BitVec::new_fill(false, num as u64);
+            for slot in chunk {
+                bits.set(slot - first_slot, true);
+            }
+            chunks.push(CompressedSlots::compress(first_slot, num, bits));
+            rest = tail;
+        }
+
+        EpochSlots {
+            from,
+            slots: chunks,
+            wallclock,
+        }
+    }
+
+    /// Sorted absolute slot numbers set in all the chunks, the chunks which
+    /// cannot be decompressed are skipped.
+    pub fn to_slots(&self) -> Vec<Slot> {
+        let mut slots = self
+            .slots
+            .iter()
+            .filter_map(|compressed| compressed.to_slots().ok())
+            .flatten()
+            .collect::<Vec<_>>();
+        slots.sort_unstable();
+        slots.dedup();
+        slots
+    }
+
+    /// Highest slot reported, the chunks which cannot be decompressed are skipped.
+    pub fn highest_slot(&self) -> Option<Slot> {
+        self.slots
+            .iter()
+            .filter_map(CompressedSlots::highest_slot)
+            .max()
+    }
+
+    /// Lowest and highest slot reported.
+    pub fn slot_range(&self) -> Option<(Slot, Slot)> {
+        let slots = self.to_slots();
+        Some((*slots.first()?, *slots.last()?))
+    }
+}
+
+/// Inclusive ranges of the slots missing between the sorted `slots`. They are
+/// produced lazily, a pair of chunks far apart makes a single huge range.
+pub fn slot_gaps(slots: &[Slot]) -> impl Iterator<Item = (Slot, Slot)> + '_ {
+    slots
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] > 1)
+        .map(|pair| (pair[0] + 1, pair[1] - 1))
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+enum DeprecatedCompressionType {
+    Uncompressed,
+    GZip,
+    BZip2,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct DeprecatedEpochIncompleteSlots {
+    first: Slot,
+    compression: DeprecatedCompressionType,
+    compressed_list: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LowestSlot {
+    pub from: Pubkey,
+    root: Slot,
+    pub lowest: Slot,
+    slots: BTreeSet<Slot>,
+    stash: Vec<DeprecatedEpochIncompleteSlots>,
+    pub wallclock: u64,
+}
+
+impl LowestSlot {
+    pub fn new(from: Pubkey, lowest: Slot, wallclock: u64) -> Self {
+        LowestSlot {
+            from,
+            root: 0,
+            lowest,
+            slots: BTreeSet::new(),
+            stash: Vec::new(),
+            wallclock,
+        }
+    }
+}
+
+/// Nodes still serving the ledger from before the `below` slot, the
+/// candidates to fetch old ledger data from.
+pub fn filter_full_history(values: &[CrdsValue], below: Slot) -> Vec<Pubkey> {
+    let mut nodes = values
+        .iter()
+        .filter_map(|value| match &value.data {
+            CrdsData::LowestSlot(_, slots) if slots.lowest < below => Some(slots.from),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    nodes.sort();
+    nodes.dedup();
+    nodes
+}
+
+/// Percentile of the highest slots of the nodes taken as the cluster tip.
+pub const CLUSTER_TIP_PERCENTILE: usize = 90;
+
+/// Current slot of the cluster, the `CLUSTER_TIP_PERCENTILE` percentile of the
+/// highest slot each node voted on or reported in its epoch slots, so that a
+/// single node running ahead does not move it.
+pub fn estimate_cluster_tip(highest: impl IntoIterator<Item = Slot>) -> Option<Slot> {
+    let mut slots = highest.into_iter().collect::<Vec<_>>();
+    slots.sort_unstable();
+    // nearest rank
+    let rank = (slots.len() * CLUSTER_TIP_PERCENTILE).div_ceil(100);
+    slots.get(rank.checked_sub(1)?).copied()
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct IncrementalSnapshotHashes {
+    pub from: Pubkey,
+    pub base: (Slot, Hash),
+    pub hashes: Vec<(Slot, Hash)>,
+    pub wallclock: u64,
+}
+
+impl IncrementalSnapshotHashes {
+    pub fn highest_slot(&self) -> Option<Slot> {
+        latest_snapshot(&self.hashes).map(|(slot, _)| *slot)
+    }
+
+    /// Hash of the incremental snapshot at the highest slot.
+    pub fn latest_hash(&self) -> Option<&Hash> {
+        latest_snapshot(&self.hashes).map(|(_, hash)| hash)
+    }
+
+    /// The full snapshot followed by the incrementals built on top of it, by
+    /// slot. A repeated slot keeps its first hash and the incrementals not above
+    /// the base are left out, they cannot be applied to it.
+    pub fn chain(&self) -> Vec<(Slot, Hash)> {
+        let mut incrementals = self
+            .hashes
+            .iter()
+            .filter(|(slot, _)| *slot > self.base.0)
+            .copied()
+            .collect::<Vec<_>>();
+        incrementals.sort_by_key(|(slot, _)| *slot);
+        incrementals.dedup_by_key(|(slot, _)| *slot);
+
+        let mut chain = vec![self.base];
+        chain.extend(incrementals);
+        chain
+    }
+}
+
+pub type DuplicateShredIndex = u16;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum ShredType {
+    Data = 0b1010_0101,
+    Code = 0b0101_1010,
+}
+
+impl From<ShredType> for u8 {
+    fn from(shred_type: ShredType) -> Self {
+        shred_type as u8
+    }
+}
+
+impl TryFrom<u8> for ShredType {
+    type Error = String;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        match value {
+            0b1010_0101 => Ok(ShredType::Data),
+            0b0101_1010 => Ok(ShredType::Code),
+            _ => Err(format!("invalid shred type:{value}")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct DuplicateShred {
+    pub from: Pubkey,
+    pub wallclock: u64,
+    pub slot: Slot,
+    _unused: u32,
+    pub shred_type: ShredType,
+    /// serialized duplicate slot proof split into chunks
+    pub num_chunks: u8,
+    pub chunk_index: u8,
+    pub chunk: Vec<u8>,
+}
+
+impl DuplicateShred {
+    pub fn new(
+        from: Pubkey,
+        wallclock: u64,
+        slot: Slot,
+        shred_type: ShredType,
+        num_chunks: u8,
+        chunk_index: u8,
+        chunk: Vec<u8>,
+    ) -> Self {
+        DuplicateShred {
+            from,
+            wallclock,
+            slot,
+            _unused: 0,
+            shred_type,
+            num_chunks,
+            chunk_index,
+            chunk,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ContactInfoVersion {
+    #[serde(with = "serde_varint")]
+    pub major: u16,
+    #[serde(with = "serde_varint")]
+    pub minor: u16,
+    #[serde(with = "serde_varint")]
+    pub patch: u16,
+    pub commit: u32,      // first 4 bytes of the sha1 commit hash
+    pub feature_set: u32, // first 4 bytes of the FeatureSet identifier
+    #[serde(with = "serde_varint")]
+    pub client: u16,
+}
+
+pub const SOCKET_TAG_GOSSIP: u8 = 0;
+pub const SOCKET_TAG_REPAIR: u8 = 1;
+pub const SOCKET_TAG_RPC: u8 = 2;
+pub const SOCKET_TAG_RPC_PUBSUB: u8 = 3;
+pub const SOCKET_TAG_SERVE_REPAIR: u8 = 4;
+pub const SOCKET_TAG_TPU: u8 = 5;
+pub const SOCKET_TAG_TPU_FORWARDS: u8 = 6;
+pub const SOCKET_TAG_TPU_FORWARDS_QUIC: u8 = 7;
+pub const SOCKET_TAG_TPU_QUIC: u8 = 8;
+pub const SOCKET_TAG_TPU_VOTE: u8 = 9;
+pub const SOCKET_TAG_TVU: u8 = 10;
+pub const SOCKET_TAG_TVU_QUIC: u8 = 11;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SocketEntry {
+    pub key: u8,   // protocol identifier, e.g. SOCKET_TAG_GOSSIP
+    pub index: u8, // index of the IpAddr in the ContactInfo addrs vector
+    #[serde(with = "serde_varint")]
+    pub offset: u16, // port offset with respect to the previous entry
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Extension {}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ContactInfo {
+    pub pubkey: Pubkey,
+    #[serde(with = "serde_varint")]
+    pub wallclock: u64,
+    /// when the node instance was first created, identifies duplicate running instances
+    pub outset: u64,
+    pub shred_version: u16,
+    pub version: ContactInfoVersion,
+    /// all the unique IP addresses referenced by the sockets
+    #[serde(with = "short_vec")]
+    pub addrs: Vec<IpAddr>,
+    /// sockets sorted by port, each port is encoded as offset from the previous one
+    #[serde(with = "short_vec")]
+    pub sockets: Vec<SocketEntry>,
+    #[serde(with = "short_vec")]
+    pub extensions: Vec<Extension>,
+}
+
+impl ContactInfo {
+    /// Socket address advertised for the given protocol tag, e.g. `SOCKET_TAG_GOSSIP`.
+    pub fn socket(&self, key: u8) -> Option<SocketAddr> {
+        let mut port: u16 = 0;
+        for entry in &self.sockets {
+            port = port.checked_add(entry.offset)?;
+            if entry.key == key {
+                let addr = self.addrs.get(usize::from(entry.index))?;
+                return Some(SocketAddr::new(*addr, port));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum CrdsData {
+    LegacyContactInfo(Box<LegacyContactInfo>), // OK len:254
+    Vote(VoteIndex, Vote),                     // OK len:472
+    LowestSlot(u8, LowestSlot),                // OK len:185
+    SnapshotHashes(SnapshotHashes),            // OK len:240
+    AccountsHashes(SnapshotHashes),            // OK len:800
+    EpochSlots(EpochSlotsIndex, EpochSlots),   // OK len:1049
+    LegacyVersion(LegacyVersion),              // OK len:163
+    Version(Version),                          // OK len:167
+    NodeInstance(NodeInstance),                // OK len:168
+    DuplicateShred(DuplicateShredIndex, DuplicateShred), // OK
+    IncrementalSnapshotHashes(IncrementalSnapshotHashes), // OK len:360
+    ContactInfo(ContactInfo),                  // OK
+}
+
+impl CrdsData {
+    /// Names of the variants, as returned by `kind`.
+    pub const KINDS: [&'static str; 12] = [
+        "LegacyContactInfo",
+        "Vote",
+        "LowestSlot",
+        "SnapshotHashes",
+        "AccountsHashes",
+        "EpochSlots",
+        "LegacyVersion",
+        "Version",
+        "NodeInstance",
+        "DuplicateShred",
+        "IncrementalSnapshotHashes",
+        "ContactInfo",
+    ];
+
+    /// Name of the variant.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CrdsData::LegacyContactInfo(_) => "LegacyContactInfo",
+            CrdsData::Vote(_, _) => "Vote",
+            CrdsData::LowestSlot(_, _) => "LowestSlot",
+            CrdsData::SnapshotHashes(_) => "SnapshotHashes",
+            CrdsData::AccountsHashes(_) => "AccountsHashes",
+            CrdsData::EpochSlots(_, _) => "EpochSlots",
+            CrdsData::LegacyVersion(_) => "LegacyVersion",
+            CrdsData::Version(_) => "Version",
+            CrdsData::NodeInstance(_) => "NodeInstance",
+            CrdsData::DuplicateShred(_, _) => "DuplicateShred",
+            CrdsData::IncrementalSnapshotHashes(_) => "IncrementalSnapshotHashes",
+            CrdsData::ContactInfo(_) => "ContactInfo",
+        }
+    }
+
+    /// The hashes of the `SnapshotHashes` and `AccountsHashes` variants,
+    /// with the kind of the variant.
+    pub fn snapshot_hashes(&self) -> Option<(SnapshotHashesKind, &SnapshotHashes)> {
+        match self {
+            CrdsData::SnapshotHashes(hashes) => Some((SnapshotHashesKind::Snapshot, hashes)),
+            CrdsData::AccountsHashes(hashes) => Some((SnapshotHashesKind::Accounts, hashes)),
+            _ => None,
+        }
+    }
+
+    /// Pubkey of the node which originated the data.
+    pub fn pubkey(&self) -> Option<Pubkey> {
+        match &self {
+            CrdsData::LegacyContactInfo(info) => Some(info.id),
+            CrdsData::Vote(_, vote) => Some(vote.from),
+            CrdsData::LowestSlot(_, slots) => Some(slots.from),
+            CrdsData::SnapshotHashes(hashes) | CrdsData::AccountsHashes(hashes) => {
+                Some(hashes.from)
+            }
+            CrdsData::EpochSlots(_, slots) => Some(slots.from),
+            CrdsData::LegacyVersion(version) => Some(version.from),
+            CrdsData::Version(version) => Some(version.from),
+            CrdsData::NodeInstance(node) => Some(node.from),
+            CrdsData::IncrementalSnapshotHashes(hashes) => Some(hashes.from),
+            CrdsData::DuplicateShred(_, shred) => Some(shred.from),
+            CrdsData::ContactInfo(info) => Some(info.pubkey),
+        }
+    }
+
+    /// Wallclock, in milliseconds since the epoch, at which the data was generated.
+    pub fn wallclock(&self) -> Option<u64> {
+        match &self {
+            CrdsData::LegacyContactInfo(info) => Some(info.wallclock),
+            CrdsData::Vote(_, vote) => Some(vote.wallclock),
+            CrdsData::LowestSlot(_, slots) => Some(slots.wallclock),
+            CrdsData::SnapshotHashes(hashes) | CrdsData::AccountsHashes(hashes) => {
+                Some(hashes.wallclock)
+            }
+            CrdsData::EpochSlots(_, slots) => Some(slots.wallclock),
+            CrdsData::LegacyVersion(version) => Some(version.wallclock),
+            CrdsData::Version(version) => Some(version.wallclock),
+            CrdsData::NodeInstance(node) => Some(node.wallclock),
+            CrdsData::IncrementalSnapshotHashes(hashes) => Some(hashes.wallclock),
+            CrdsData::DuplicateShred(_, shred) => Some(shred.wallclock),
+            CrdsData::ContactInfo(info) => Some(info.wallclock),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CrdsValue {
+    pub signature: Signature,
+    pub data: CrdsData,
+}
+
+impl CrdsValue {
+    /// Sign the data with the keypair of its origin.
+    ///
+    /// # Panics
+    ///
+    /// If the data cannot be serialized.
+    pub fn new_signed(data: CrdsData, keypair: &Keypair) -> Self {
+        let signable_data = serialize(&data).expect("failed to serialize CrdsData");
+        let signature = keypair.sign_message(&signable_data);
+        Self { signature, data }
+    }
+
+    /// Label under which the value is stored, unique per kind, origin and index.
+    pub fn label(&self) -> CrdsValueLabel {
+        CrdsValueLabel::new(&self.data)
+    }
+
+    /// Hash of the full signed value, as inserted in the bloom filters of the pull requests.
+    ///
+    /// # Panics
+    ///
+    /// If the value cannot be serialized.
+    pub fn hash(&self) -> Hash {
+        let bytes = serialize(self).expect("failed to serialize CrdsValue");
+        hash::hashv(&[&bytes])
+    }
+
+    /// Bincode serialization of the signed value, as carried by the messages.
+    pub fn to_bytes(&self) -> bincode::Result<Vec<u8>> {
+        serialize(self)
+    }
+
+    /// Verify the signature against the pubkey of the node which originated the data.
+    pub fn verify(&self) -> bool {
+        let Some(pubkey) = self.data.pubkey() else {
+            return false;
+        };
+
+        match serialize(&self.data) {
+            Ok(signable_data) => self.signature.verify(pubkey.as_ref(), &signable_data),
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for CrdsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.data.kind())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CrdsFilter {
+    pub filter: Bloom<Hash>,
+    pub mask: u64,
+    pub mask_bits: u32,
+}
+
+/// Number of hash functions used by the bloom filter.
+const CRDS_FILTER_KEYS: f64 = 8f64;
+/// False positive rate used when the requested one is out of range.
+const CRDS_FILTER_FALSE_RATE: f64 = 0.1f64;
+/// Number of bloom bits fitting in a pull request packet.
+const CRDS_FILTER_MAX_BITS: usize = 7424;
+/// At most 1024 filters are built, over a million values at 1287 per filter,
+/// beyond it the filters hold more values than requested.
+const CRDS_FILTER_MAX_MASK_BITS: u32 = 10;
+
+impl Default for CrdsFilter {
+    fn default() -> Self {
+        CrdsFilter::new(512, CRDS_FILTER_FALSE_RATE, CRDS_FILTER_MAX_BITS)
+    }
+}
+
+impl CrdsFilter {
+    /// Create a filter sized for `num_items` values within `max_bits` bloom bits.
+    /// A `false_rate` outside (0,1) falls back to the default rate.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn new(num_items: usize, false_rate: f64, max_bits: usize) -> Self {
+        let false_rate = if false_rate > 0.0 && false_rate < 1.0 {
+            false_rate
+        } else {
+            CRDS_FILTER_FALSE_RATE
+        };
+        let max_items = Self::max_items(max_bits as f64, false_rate, CRDS_FILTER_KEYS);
+        let mask_bits = Self::mask_bits(num_items as f64, max_items);
+
+        let bloom: Bloom<Hash> = Bloom::random(max_items as usize, false_rate, max_bits);
+
+        CrdsFilter {
+            filter: bloom,
+            mask: Self::compute_mask(0_u64, mask_bits),
+            mask_bits,
+        }
+    }
+
+    /// Filter holding the values we already have, so that the peer answers
+    /// only with the missing ones. It is sized for the larger of `values.len()`
+    /// and `num_items_hint`; when that takes more than one partition, the
+    /// filter covers the first one and only its values are inserted.
+    pub fn from_values(values: &[CrdsValue], num_items_hint: usize) -> CrdsFilter {
+        let num_items = num_items_hint.max(values.len());
+        let mut filter = CrdsFilter::new(num_items, CRDS_FILTER_FALSE_RATE, CRDS_FILTER_MAX_BITS);
+        for hash in values.iter().map(CrdsValue::hash) {
+            if filter.test_mask(&hash) {
+                filter.filter.add(&hash);
+            }
+        }
+        filter
+    }
+
+    /// Split the hash space in `2^mask_bits` filters, one per mask prefix,
+    /// so that `num_items` values are covered with at most
+    /// `max_items_per_filter` values per filter. The mask bits are capped at
+    /// `CRDS_FILTER_MAX_MASK_BITS`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn filters(num_items: usize, max_items_per_filter: usize) -> Vec<CrdsFilter> {
+        let max_items = max_items_per_filter.max(1);
+        let mask_bits =
+            Self::mask_bits(num_items as f64, max_items as f64).min(CRDS_FILTER_MAX_MASK_BITS);
+        (0..2u64.pow(mask_bits))
+            .map(|seed| CrdsFilter {
+                filter: Bloom::random(max_items, CRDS_FILTER_FALSE_RATE, CRDS_FILTER_MAX_BITS),
+                mask: Self::compute_mask(seed, mask_bits),
+                mask_bits,
+            })
+            .collect()
+    }
+
+    fn max_items(max_bits: f64, false_rate: f64, num_keys: f64) -> f64 {
+        let m = max_bits;
+        let p = false_rate;
+        let k = num_keys;
+        (m / (-k / (1f64 - (p.ln() / k).exp()).ln())).ceil()
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    fn mask_bits(num_items: f64, max_items: f64) -> u32 {
+        // for small ratios this can result in a negative number, ensure it returns 0 instead
+        ((num_items / max_items).log2().ceil()).max(0.0) as u32
+    }
+
+    fn compute_mask(seed: u64, mask_bits: u32) -> u64 {
+        assert!(seed <= 2u64.pow(mask_bits));
+        let seed: u64 = seed.checked_shl(64 - mask_bits).unwrap_or(0x0);
+        seed | (!0u64).checked_shr(mask_bits).unwrap_or(!0x0)
+    }
+
+    /// False positive probability of the bloom filter once `num_inserted` values
+    /// are added, `(1 - e^(-k n / m))^k` for `m` bits and `k` hash keys.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn estimated_false_positive_rate(&self, num_inserted: usize) -> f64 {
+        let m = self.filter.bits.len() as f64;
+        let k = self.filter.keys.len() as f64;
+        let n = num_inserted as f64;
+        if m == 0.0 {
+            // every bit index is out of range, so everything matches
+            return 1.0;
+        }
+        (1f64 - (-k * n / m).exp()).powf(k)
+    }
+
+    /// Fraction of the bloom bits set, close to 1 the filter is saturated and
+    /// matches almost every hash.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fill_ratio(&self) -> f64 {
+        let bits = &self.filter.bits;
+        if bits.is_empty() {
+            return 0.0;
+        }
+        let ones = (0..bits.len()).filter(|i| bits.get(*i)).count();
+        ones as f64 / bits.len() as f64
+    }
+
+    /// Test whether the hash falls in the mask partition and in the bloom filter.
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.test_mask(hash) && self.filter.contains(hash)
+    }
+
+    /// Test whether the hash falls in the mask partition the filter covers.
+    pub fn test_mask(&self, hash: &Hash) -> bool {
+        let ones = (!0u64).checked_shr(self.mask_bits).unwrap_or(!0u64);
+        (hash_as_u64(hash) | ones) == self.mask
+    }
+}
+
+fn hash_as_u64(hash: &Hash) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&hash.as_ref()[..8]);
+    u64::from_le_bytes(buf)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PingGeneric<T> {
+    pub from: Pubkey,
+    token: T,
+    signature: Signature,
+}
+
+/// Number of bytes in the randomly generated token sent with ping messages.
+const GOSSIP_PING_TOKEN_SIZE: usize = 32;
+
+pub type Ping = PingGeneric<[u8; GOSSIP_PING_TOKEN_SIZE]>;
+
+impl Ping {
+    /// Create a ping with a fresh random token signed by the keypair.
+    ///
+    /// # Panics
+    ///
+    /// If the token cannot be serialized.
+    pub fn new(keypair: &Keypair) -> Self {
+        let token: [u8; GOSSIP_PING_TOKEN_SIZE] = rand::random();
+        let signable_data = serialize(&token).expect("failed to serialize ping token");
+        Ping {
+            from: keypair.pubkey(),
+            token,
+            signature: keypair.sign_message(&signable_data),
+        }
+    }
+
+    pub fn token(&self) -> &[u8; GOSSIP_PING_TOKEN_SIZE] {
+        &self.token
+    }
+
+    pub fn from(&self) -> Pubkey {
+        self.from
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Pong {
+    from: Pubkey,
+    hash: Hash, // Hash of received ping token.
+    signature: Signature,
+}
+
+const PING_PONG_HASH_PREFIX: &[u8] = "SOLANA_PING_PONG".as_bytes();
+
+fn hash_ping_token<T: SerdeSerialize>(token: &T) -> bincode::Result<Hash> {
+    let token = serialize(token)?;
+    Ok(hash::hashv(&[PING_PONG_HASH_PREFIX, &token]))
+}
+
+impl Pong {
+    pub fn new<T: SerdeSerialize>(ping: &PingGeneric<T>, keypair: &Keypair) -> Result<Self> {
+        let hash = hash_ping_token(&ping.token)?;
+        Ok(Pong::with_hash(hash, keypair))
+    }
+
+    pub fn from(&self) -> Pubkey {
+        self.from
+    }
+
+    fn with_hash(hash: Hash, keypair: &Keypair) -> Self {
+        Pong {
+            from: keypair.pubkey(),
+            hash,
+            signature: keypair.sign_message(hash.as_ref()),
+        }
+    }
+
+    /// Check that the pong answers the given ping and is signed by its sender.
+    pub fn verify_for_ping(&self, ping: &Ping) -> bool {
+        match hash_ping_token(&ping.token) {
+            Ok(hash) => {
+                hash == self.hash
+                    && self
+                        .signature
+                        .verify(self.from.as_ref(), self.hash.as_ref())
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Asks the `destination` to stop pushing the values originated by the
+/// `prunes` nodes to `pubkey`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PruneData {
+    /// node sending the prune
+    pub pubkey: Pubkey,
+    /// origins whose values are no longer wanted
+    pub prunes: Vec<Pubkey>,
+    pub signature: Signature,
+    /// node asked to stop pushing
+    pub destination: Pubkey,
+    pub wallclock: u64,
+}
+
+/// Fields of the `PruneData` covered by its signature.
+#[derive(Serialize)]
+struct PruneSignData<'a> {
+    pubkey: &'a Pubkey,
+    prunes: &'a [Pubkey],
+    destination: &'a Pubkey,
+    wallclock: u64,
+}
+
+/// Prepended to the signed bytes by the newer nodes.
+const PRUNE_DATA_PREFIX: &[u8] = b"\xffSOLANA_PRUNE_DATA";
+
+impl PruneData {
+    /// Prunes of the keypair node, signed by it.
+    ///
+    /// # Panics
+    ///
+    /// If the data cannot be serialized.
+    pub fn new_signed(
+        keypair: &Keypair,
+        prunes: Vec<Pubkey>,
+        destination: Pubkey,
+        wallclock: u64,
+    ) -> Self {
+        let mut prune_data = PruneData {
+            pubkey: keypair.pubkey(),
+            prunes,
+            signature: Signature::default(),
+            destination,
+            wallclock,
+        };
+        let signable_data = prune_data
+            .signable_data()
+            .expect("failed to serialize PruneData");
+        prune_data.signature = keypair.sign_message(&signable_data);
+        prune_data
+    }
+
+    /// Bincode of the pubkey, prunes, destination and wallclock.
+    fn signable_data(&self) -> bincode::Result<Vec<u8>> {
+        serialize(&PruneSignData {
+            pubkey: &self.pubkey,
+            prunes: &self.prunes,
+            destination: &self.destination,
+            wallclock: self.wallclock,
+        })
+    }
+
+    /// Whether the signature of `pubkey` covers the prune, with or without the
+    /// prefix prepended by the newer nodes.
+    pub fn verify(&self) -> bool {
+        match self.signable_data() {
+            Ok(signable_data) => {
+                self.signature.verify(self.pubkey.as_ref(), &signable_data)
+                    || self.signature.verify(
+                        self.pubkey.as_ref(),
+                        &[PRUNE_DATA_PREFIX, &signable_data].concat(),
+                    )
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the `destination` should stop pushing its own values to the sender.
+    pub fn prunes_origin(&self, destination: &Pubkey, origin: &Pubkey) -> bool {
+        &self.destination == destination && self.prunes.contains(origin)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Protocol {
+    PullRequest(CrdsFilter, CrdsValue),
+    PullResponse(Pubkey, Vec<CrdsValue>),
+    PushMessage(Pubkey, Vec<CrdsValue>),
+    PruneMessage(Pubkey, PruneData),
+    PingMessage(Ping),
+    PongMessage(Pong),
+}
+
+impl Protocol {
+    pub fn from_bytes(bytes: &[u8]) -> bincode::Result<Protocol> {
+        deserialize_bytes(bytes)
+    }
+
+    pub fn to_bytes(&self) -> bincode::Result<Vec<u8>> {
+        serialize(self)
+    }
+
+    pub fn serialized_size(&self) -> bincode::Result<u64> {
+        bincode::serialized_size(self)
+    }
+
+    /// Fail with `PacketTooLarge` when the message doesn't fit in a single packet.
+    pub fn check_packet_size(&self) -> Result<()> {
+        let size = self.serialized_size()?;
+        let max = PACKET_DATA_SIZE as u64;
+        if size > max {
+            return Err(Error::PacketTooLarge { size, max });
+        }
+        Ok(())
+    }
+
+    /// Build the pong message answering the given ping.
+    pub fn pong_response(ping: &Ping, keypair: &Keypair) -> bincode::Result<Protocol> {
+        let hash = hash_ping_token(&ping.token)?;
+        Ok(Protocol::PongMessage(Pong::with_hash(hash, keypair)))
+    }
+
+    /// Pack at most `max_values` values in pull responses fitting a packet each.
+    /// Values too large to fit a packet on their own are skipped.
+    pub fn pull_responses(
+        from: Pubkey,
+        values: Vec<CrdsValue>,
+        max_values: usize,
+    ) -> bincode::Result<Vec<Protocol>> {
+        let empty = bincode::serialized_size(&Protocol::PullResponse(from, vec![]))?;
+
+        let mut responses = vec![];
+        let mut chunk = vec![];
+        let mut chunk_size = empty;
+        for value in values.into_iter().take(max_values) {
+            let size = bincode::serialized_size(&value)?;
+            if empty + size > PACKET_DATA_SIZE as u64 {
+                continue;
+            }
+            if chunk_size + size > PACKET_DATA_SIZE as u64 {
+                responses.push(Protocol::PullResponse(from, std::mem::take(&mut chunk)));
+                chunk_size = empty;
+            }
+            chunk.push(value);
+            chunk_size += size;
+        }
+        if !chunk.is_empty() {
+            responses.push(Protocol::PullResponse(from, chunk));
+        }
+        Ok(responses)
+    }
+}
+
+/// Pack the values greedily in push messages of at most `max_bytes` each.
+/// A value too large to fit on its own is sent alone, with a warning.
+pub fn chunk_values(origin: Pubkey, values: Vec<CrdsValue>, max_bytes: usize) -> Vec<Protocol> {
+    let empty = bincode::serialized_size(&Protocol::PushMessage(origin, vec![])).unwrap_or(0);
+    let max_bytes = max_bytes as u64;
+
+    let mut messages = vec![];
+    let mut chunk = vec![];
+    let mut chunk_size = empty;
+    for value in values {
+        let size = bincode::serialized_size(&value).unwrap_or(u64::MAX);
+        if empty.saturating_add(size) > max_bytes {
+            warn!("{value} of {size} bytes exceeds the {max_bytes} bytes limit, pushed alone");
+            messages.push(Protocol::PushMessage(origin, vec![value]));
+            continue;
+        }
+        if chunk_size + size > max_bytes {
+            messages.push(Protocol::PushMessage(origin, std::mem::take(&mut chunk)));
+            chunk_size = empty;
+        }
+        chunk.push(value);
+        chunk_size += size;
+    }
+    if !chunk.is_empty() {
+        messages.push(Protocol::PushMessage(origin, chunk));
+    }
+    messages
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Protocol::PullRequest(_, _) => write!(f, "PullRequest"),
+            Protocol::PullResponse(_, _) => write!(f, "PullResponse"),
+            Protocol::PushMessage(_, _) => write!(f, "PushMessage"),
+            Protocol::PruneMessage(_, _) => write!(f, "PruneMessage"),
+            Protocol::PingMessage(_) => write!(f, "PingMessage"),
+            Protocol::PongMessage(_) => write!(f, "PongMessage"),
+        }
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        utils::parse_addr,
+        wire::{Payload, PACKET_DATA_SIZE},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_sigh_crds_data() {
+        let keypair = Keypair::new();
+
+        let crds_data = CrdsData::LegacyContactInfo(Box::default());
+        let crds_value = CrdsValue::new_signed(crds_data.clone(), &keypair);
+
+        let pubkey = keypair.pubkey();
+        let message_bytes = serialize(&crds_data).expect("failed to serialize CrdsData");
+        assert!(crds_value.signature.verify(pubkey.as_ref(), &message_bytes));
+    }
+
+    #[test]
+    fn test_verify_crds_value() {
+        let keypair = Keypair::new();
+
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            ..LegacyContactInfo::default()
+        };
+        let crds_value =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+        assert!(crds_value.verify());
+
+        // signed by a key which is not the origin of the data
+        let crds_value =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::default()), &Keypair::new());
+        assert!(!crds_value.verify());
+
+        // tampered data
+        let version = Version {
+            from: keypair.pubkey(),
+            wallclock: 1,
+            version: LegacyVersion2 {
+                major: 1,
+                minor: 14,
+                patch: 17,
+                commit: None,
+                feature_set: 0,
+            },
+        };
+        let mut crds_value = CrdsValue::new_signed(CrdsData::Version(version), &keypair);
+        assert!(crds_value.verify());
+        if let CrdsData::Version(version) = &mut crds_value.data {
+            version.wallclock = 2;
+        }
+        assert!(!crds_value.verify());
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn create_crds_data_variants(from: Pubkey, wallclock: u64) -> Vec<CrdsData> {
+        let hashes = SnapshotHashes {
+            from,
+            hashes: vec![(1, Hash::default())],
+            wallclock,
+        };
+
+        vec![
+            CrdsData::LegacyContactInfo(Box::new(LegacyContactInfo {
+                id: from,
+                wallclock,
+                ..LegacyContactInfo::default()
+            })),
+            CrdsData::Vote(
+                0,
+                Vote {
+                    from,
+                    transaction: Transaction::default(),
+                    wallclock,
+                },
+            ),
+            CrdsData::LowestSlot(
+                0,
+                LowestSlot {
+                    from,
+                    root: 0,
+                    lowest: 0,
+                    slots: BTreeSet::new(),
+                    stash: Vec::new(),
+                    wallclock,
+                },
+            ),
+            CrdsData::SnapshotHashes(hashes.clone()),
+            CrdsData::AccountsHashes(hashes),
+            CrdsData::EpochSlots(
+                0,
+                EpochSlots {
+                    from,
+                    slots: Vec::new(),
+                    wallclock,
+                },
+            ),
+            CrdsData::LegacyVersion(LegacyVersion {
+                from,
+                wallclock,
+                version: LegacyVersion1 {
+                    major: 1,
+                    minor: 0,
+                    patch: 0,
+                    commit: None,
+                },
+            }),
+            CrdsData::Version(Version {
+                from,
+                wallclock,
+                version: LegacyVersion2 {
+                    major: 1,
+                    minor: 14,
+                    patch: 17,
+                    commit: None,
+                    feature_set: 0,
+                },
+            }),
+            CrdsData::NodeInstance(NodeInstance {
+                from,
+                wallclock,
+                timestamp: 0,
+                token: 0,
+            }),
+            CrdsData::DuplicateShred(
+                0,
+                DuplicateShred {
+                    from,
+                    wallclock,
+                    slot: 0,
+                    _unused: 0,
+                    shred_type: ShredType::Code,
+                    num_chunks: 1,
+                    chunk_index: 0,
+                    chunk: Vec::new(),
+                },
+            ),
+            CrdsData::IncrementalSnapshotHashes(IncrementalSnapshotHashes {
+                from,
+                base: (1, Hash::default()),
+                hashes: Vec::new(),
+                wallclock,
+            }),
+            CrdsData::ContactInfo(ContactInfo {
+                pubkey: from,
+                wallclock,
+                outset: 0,
+                shred_version: 0,
+                version: ContactInfoVersion {
+                    major: 1,
+                    minor: 18,
+                    patch: 26,
+                    commit: 0,
+                    feature_set: 0,
+                    client: 0,
+                },
+                addrs: Vec::new(),
+                sockets: Vec::new(),
+                extensions: Vec::new(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_crds_data_pubkey() {
+        let pubkey = Pubkey::new_unique();
+
+        for crds_data in create_crds_data_variants(pubkey, 0) {
+            assert_eq!(crds_data.pubkey(), Some(pubkey), "{crds_data:?}");
+        }
+    }
+
+    #[test]
+    fn test_crds_data_kinds() {
+        let kinds = create_crds_data_variants(Pubkey::new_unique(), 0)
+            .iter()
+            .map(CrdsData::kind)
+            .collect::<Vec<_>>();
+        // every variant can be named, in the order of the enum
+        assert_eq!(kinds, CrdsData::KINDS);
+    }
+
+    #[test]
+    fn test_crds_data_wallclock() {
+        let wallclock = 1_681_747_487_616;
+
+        for crds_data in create_crds_data_variants(Pubkey::new_unique(), wallclock) {
+            assert_eq!(crds_data.wallclock(), Some(wallclock), "{crds_data:?}");
+        }
+
+        let version = CrdsData::Version(Version {
+            from: Pubkey::new_unique(),
+            wallclock: 42,
+            version: LegacyVersion2 {
+                major: 1,
+                minor: 14,
+                patch: 17,
+                commit: None,
+                feature_set: 0,
+            },
+        });
+        assert_eq!(version.wallclock(), Some(42));
+
+        let hashes = CrdsData::SnapshotHashes(SnapshotHashes {
+            from: Pubkey::new_unique(),
+            hashes: Vec::new(),
+            wallclock: 24,
+        });
+        assert_eq!(hashes.wallclock(), Some(24));
+    }
+
+    #[test]
+    fn test_duplicate_shred_round_trip() {
+        let keypair = Keypair::new();
+
+        let shred = DuplicateShred {
+            from: keypair.pubkey(),
+            wallclock: 1_681_747_487_616,
+            slot: 193_221_345,
+            _unused: 0,
+            shred_type: ShredType::Data,
+            num_chunks: 3,
+            chunk_index: 1,
+            chunk: vec![1, 2, 3, 4, 5],
+        };
+        let crds_value = CrdsValue::new_signed(CrdsData::DuplicateShred(7, shred), &keypair);
+        assert!(crds_value.verify());
+
+        let bytes = serialize(&crds_value).unwrap();
+        // enum tag of the variant in the reference implementation
+        assert_eq!(bytes[64..68], [9, 0, 0, 0]);
+        // duplicate shred index
+        assert_eq!(bytes[68..70], [7, 0]);
+
+        let value: CrdsValue = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(value, crds_value);
+
+        // the variants following DuplicateShred still decode with the right tag
+        let hashes = CrdsData::IncrementalSnapshotHashes(IncrementalSnapshotHashes {
+            from: keypair.pubkey(),
+            base: (1, Hash::default()),
+            hashes: Vec::new(),
+            wallclock: 0,
+        });
+        assert_eq!(serialize(&hashes).unwrap()[..4], [10, 0, 0, 0]);
+
+        // unknown shred type
+        let mut bytes = bytes;
+        bytes[70 + 32 + 8 + 8 + 4] = 0;
+        assert!(bincode::deserialize::<CrdsValue>(&bytes).is_err());
+    }
+
+    fn compress(bits: &BitVec<u8>) -> Vec<u8> {
+        use flate2::{write::DeflateEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&bits.clone().into_boxed_slice()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_compressed_slots_to_slots() {
+        let mut bits: BitVec<u8> = BitVec::new_fill(false, 64);
+        bits.set(0, true);
+        bits.set(3, true);
+        bits.set(63, true);
+
+        let uncompressed = CompressedSlots::Uncompressed(Uncompressed {
+            first_slot: 1000,
+            num: 64,
+            slots: bits.clone(),
+        });
+        assert_eq!(uncompressed.to_slots().unwrap(), vec![1000, 1003, 1063]);
+
+        let flate2 = Flate2 {
+            first_slot: 1000,
+            num: 10,
+            compressed: compress(&bits),
+        };
+        assert_eq!(flate2.decompress().unwrap().slots, bits);
+        assert_eq!(
+            CompressedSlots::Flate2(flate2).to_slots().unwrap(),
+            vec![1000, 1003]
+        );
+    }
+
+    #[test]
+    fn test_compressed_slots_invalid_num() {
+        let bits: BitVec<u8> = BitVec::new_fill(true, 16);
+
+        let flate2 = Flate2 {
+            first_slot: 0,
+            num: 17,
+            compressed: compress(&bits),
+        };
+        assert!(matches!(
+            flate2.decompress(),
+            Err(Error::SlotsLengthError { num: 17, bits: 16 })
+        ));
+
+        let flate2 = Flate2 {
+            first_slot: 0,
+            num: 16,
+            compressed: vec![0xff, 0xff, 0xff],
+        };
+        assert!(CompressedSlots::Flate2(flate2).to_slots().is_err());
+
+        let uncompressed = Uncompressed {
+            first_slot: 0,
+            num: 17,
+            slots: bits,
+        };
+        assert!(uncompressed.to_slots().is_err());
+    }
+
+    #[test]
+    fn test_compressed_slots_overflow() {
+        let mut bits: BitVec<u8> = BitVec::new_fill(false, 16);
+        bits.set(0, true);
+        let mut uncompressed = Uncompressed {
+            first_slot: u64::MAX,
+            num: 16,
+            slots: bits,
+        };
+        assert_eq!(uncompressed.to_slots().unwrap(), vec![u64::MAX]);
+
+        uncompressed.slots.set(1, true);
+        assert!(matches!(
+            uncompressed.to_slots(),
+            Err(Error::SlotOverflow {
+                first_slot: u64::MAX,
+                index: 1
+            })
+        ));
+
+        // the chunk is skipped, the others are kept
+        let epoch_slots = EpochSlots {
+            from: Pubkey::new_unique(),
+            slots: vec![
+                CompressedSlots::Uncompressed(uncompressed),
+                CompressedSlots::Uncompressed(Uncompressed {
+                    first_slot: 100,
+                    num: 16,
+                    slots: BitVec::new_fill(true, 16),
+                }),
+            ],
+            wallclock: 0,
+        };
+        assert_eq!(epoch_slots.to_slots(), (100..116).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_compressed_slots_oversized_num() {
+        let bits: BitVec<u8> = BitVec::new_fill(true, 16);
+        let uncompressed = Uncompressed {
+            first_slot: 0,
+            num: usize::MAX,
+            slots: bits.clone(),
+        };
+        assert!(matches!(
+            uncompressed.validate(MAX_SLOTS_PER_ENTRY),
+            Err(Error::TooManySlots {
+                num: usize::MAX,
+                max: MAX_SLOTS_PER_ENTRY
+            })
+        ));
+        assert!(uncompressed.to_slots().is_err());
+        assert!(Uncompressed {
+            num: 16,
+            ..uncompressed
+        }
+        .validate(16)
+        .is_ok());
+
+        let flate2 = Flate2 {
+            first_slot: 0,
+            num: MAX_SLOTS_PER_ENTRY + 1,
+            compressed: compress(&bits),
+        };
+        assert!(matches!(
+            flate2.decompress(),
+            Err(Error::TooManySlots { .. })
+        ));
+
+        // a small payload inflating to far more than a chunk is cut
+        let bomb: BitVec<u8> = BitVec::new_fill(false, 64 * MAX_SLOTS_PER_ENTRY as u64);
+        let flate2 = Flate2 {
+            first_slot: 0,
+            num: MAX_SLOTS_PER_ENTRY,
+            compressed: compress(&bomb),
+        };
+        assert!(flate2.compressed.len() < 1_000);
+        let uncompressed = flate2.decompress().unwrap();
+        assert_eq!(uncompressed.slots.len(), MAX_SLOTS_PER_ENTRY as u64);
+        assert!(uncompressed.to_slots().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_epoch_slots_coverage() {
+        let mut bits: BitVec<u8> = BitVec::new_fill(false, 16);
+        for index in [0, 1, 2, 5, 6] {
+            bits.set(index, true);
+        }
+
+        let mut epoch_slots = EpochSlots {
+            from: Pubkey::new_unique(),
+            slots: vec![],
+            wallclock: 0,
+        };
+        assert_eq!(epoch_slots.slot_range(), None);
+        assert_eq!(slot_gaps(&epoch_slots.to_slots()).next(), None);
+
+        epoch_slots.slots = vec![
+            CompressedSlots::Flate2(Flate2 {
+                first_slot: 100,
+                num: 8,
+                compressed: compress(&bits),
+            }),
+            // a corrupted chunk doesn't hide the others
+            CompressedSlots::Flate2(Flate2 {
+                first_slot: 108,
+                num: 8,
+                compressed: vec![0xff, 0xff, 0xff],
+            }),
+            CompressedSlots::Uncompressed(Uncompressed {
+                first_slot: 110,
+                num: 2,
+                slots: bits,
+            }),
+        ];
+        assert_eq!(
+            epoch_slots.to_slots(),
+            vec![100, 101, 102, 105, 106, 110, 111]
+        );
+        assert_eq!(epoch_slots.slot_range(), Some((100, 111)));
+        assert_eq!(
+            slot_gaps(&epoch_slots.to_slots()).collect::<Vec<_>>(),
+            vec![(103, 104), (107, 109)]
+        );
+
+        // chunks far apart are a single range
+        let far = [0, 1, 1_000_000_000_000, u64::MAX];
+        assert_eq!(
+            slot_gaps(&far).collect::<Vec<_>>(),
+            vec![(2, 999_999_999_999), (1_000_000_000_001, u64::MAX - 1)]
+        );
+    }
+
+    #[test]
+    fn test_epoch_slots_from_slots() {
+        let from = Pubkey::new_unique();
+        // a dense run over two chunks then a few sparse slots
+        let mut slots = (1_000..1_000 + MAX_SLOTS_PER_ENTRY as u64 + 100).collect::<Vec<_>>();
+        slots.extend([50_000, 50_009, 50_023, 50_000]);
+
+        let epoch_slots = EpochSlots::from_slots(from, &slots, 42);
+        assert_eq!(epoch_slots.from, from);
+        assert_eq!(epoch_slots.wallclock, 42);
+        assert_eq!(epoch_slots.slots.len(), 3);
+        let CompressedSlots::Flate2(flate2) = &epoch_slots.slots[0] else {
+            panic!("the dense run is not compressed");
+        };
+        assert_eq!(flate2.num, MAX_SLOTS_PER_ENTRY);
+        assert!(flate2.compressed.len() < MAX_SLOTS_PER_ENTRY / 8);
+        assert_eq!(
+            flate2.decompress().unwrap().to_slots().unwrap().len(),
+            MAX_SLOTS_PER_ENTRY
+        );
+        // 3 bytes of bits do not deflate
+        assert!(matches!(
+            &epoch_slots.slots[2],
+            CompressedSlots::Uncompressed(Uncompressed {
+                first_slot: 50_000,
+                num: 24,
+                ..
+            })
+        ));
+
+        slots.sort_unstable();
+        slots.dedup();
+        assert_eq!(epoch_slots.to_slots(), slots);
+
+        // the wire round trip
+        let bytes = serialize(&epoch_slots).unwrap();
+        let decoded: EpochSlots = deserialize_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_slots(), slots);
+
+        assert!(EpochSlots::from_slots(from, &[], 0).slots.is_empty());
+    }
+
+    #[test]
+    fn test_new_ping() {
+        let keypair = Keypair::new();
+
+        let ping = Ping::new(&keypair);
+        assert_eq!(ping.from(), keypair.pubkey());
+        assert!(ping
+            .signature
+            .verify(keypair.pubkey().as_ref(), ping.token()));
+
+        let other = Ping::new(&keypair);
+        assert_ne!(ping.token(), other.token());
+    }
+
+    #[test]
+    fn test_pong_verify_for_ping() {
+        let request = Ping::new(&Keypair::new());
+
+        let keypair = Keypair::new();
+        let response = Pong::new(&request, &keypair).unwrap();
+        assert!(response.verify_for_ping(&request));
+
+        let other_ping = Ping::new(&Keypair::new());
+        assert!(!response.verify_for_ping(&other_ping));
+
+        let mut forged_pong = response.clone();
+        forged_pong.from = Keypair::new().pubkey();
+        assert!(!forged_pong.verify_for_ping(&request));
+
+        let mut forged_pong = response;
+        forged_pong.signature = Signature::default();
+        assert!(!forged_pong.verify_for_ping(&request));
+    }
+
+    #[test]
+    fn test_protocol_bytes_round_trip() {
+        let protocol = Protocol::PingMessage(Ping::new(&Keypair::new()));
+
+        let bytes = protocol.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 132);
+        assert_eq!(Protocol::from_bytes(&bytes).unwrap(), protocol);
+
+        assert!(Protocol::from_bytes(&bytes[..100]).is_err());
+        assert!(Protocol::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_protocol_from_bytes_huge_vec_length() {
+        // a pull response claiming u64::MAX values
+        let mut bytes = 1u32.to_le_bytes().to_vec();
+        bytes.extend_from_slice(Pubkey::new_unique().as_ref());
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+
+        let start = std::time::Instant::now();
+        let truncated = [bytes.as_slice(), &[0; 16]].concat();
+        assert!(Protocol::from_bytes(&truncated).is_err());
+
+        // more than a datagram is refused before parsing the values
+        let crds_value =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::default()), &Keypair::new());
+        let value_bytes = serialize(&crds_value).unwrap();
+        let oversized = [bytes.as_slice(), &value_bytes.repeat(20)].concat();
+        assert!(oversized.len() > 2 * PACKET_DATA_SIZE);
+        let err = Protocol::from_bytes(&oversized).unwrap_err();
+        assert!(matches!(*err, bincode::ErrorKind::SizeLimit), "{err}");
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_protocol_prune_message() {
+        let from = Pubkey::new_unique();
+        let me = Pubkey::new_unique();
+        let data = PruneData {
+            pubkey: from,
+            prunes: vec![me, Pubkey::new_unique()],
+            signature: Signature::default(),
+            destination: me,
+            wallclock: 1_681_834_717_999,
+        };
+        assert!(data.prunes_origin(&me, &me));
+        assert!(!data.prunes_origin(&from, &me));
+        assert!(!data.prunes_origin(&me, &from));
+
+        let protocol = Protocol::PruneMessage(from, data);
+        let bytes = protocol.to_bytes().unwrap();
+        // tag, pubkey, then pubkey, prunes, signature, destination and wallclock
+        assert_eq!(bytes.len(), 4 + 32 + 32 + 8 + 2 * 32 + 64 + 32 + 8);
+        assert_eq!(bytes[..4], [3, 0, 0, 0]);
+        assert_eq!(Protocol::from_bytes(&bytes).unwrap(), protocol);
+    }
+
+    #[test]
+    fn test_prune_data_verify() {
+        let keypair = Keypair::new();
+        let destination = Pubkey::new_unique();
+        let prunes = vec![destination, Pubkey::new_unique()];
+        let data = PruneData::new_signed(&keypair, prunes.clone(), destination, 1_000);
+        assert_eq!(data.pubkey, keypair.pubkey());
+        assert!(data.verify());
+
+        // round trip through the wire format
+        let protocol = Protocol::PruneMessage(keypair.pubkey(), data.clone());
+        let bytes = protocol.to_bytes().unwrap();
+        let Protocol::PruneMessage(from, decoded) = Protocol::from_bytes(&bytes).unwrap() else {
+            panic!("not a prune message");
+        };
+        assert_eq!(from, keypair.pubkey());
+        assert_eq!(decoded, data);
+        assert!(decoded.verify());
+
+        // signed by the newer nodes
+        let mut prefixed = data.clone();
+        let signable_data = [PRUNE_DATA_PREFIX, &data.signable_data().unwrap()].concat();
+        prefixed.signature = keypair.sign_message(&signable_data);
+        assert!(prefixed.verify());
+
+        // tampered fields
+        let mut tampered = data.clone();
+        tampered.prunes.pop();
+        assert!(!tampered.verify());
+        let mut tampered = data.clone();
+        tampered.wallclock += 1;
+        assert!(!tampered.verify());
+        let mut tampered = data;
+        tampered.pubkey = Pubkey::new_unique();
+        assert!(!tampered.verify());
+    }
+
+    #[test]
+    fn test_protocol_pong_response() {
+        let request = Ping::new(&Keypair::new());
+
+        let keypair = Keypair::new();
+        let protocol = Protocol::pong_response(&request, &keypair).unwrap();
+
+        assert!(matches!(protocol, Protocol::PongMessage(_)));
+        if let Protocol::PongMessage(response) = protocol {
+            assert_eq!(response.from, keypair.pubkey());
+            assert!(response.verify_for_ping(&request));
+        }
+    }
+
+    #[test]
+    fn test_crds_value_hash() {
+        let keypair = Keypair::new();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            wallclock: 1_000,
+            ..LegacyContactInfo::default()
+        };
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+
+        // stable across a serialization round trip
+        let bytes = serialize(&value).unwrap();
+        let copy: CrdsValue = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(value.hash(), copy.hash());
+        assert_eq!(value.hash(), hash::hashv(&[&bytes]));
+        assert_eq!(value.to_bytes().unwrap(), bytes);
+
+        let mut mutated = value.clone();
+        if let CrdsData::LegacyContactInfo(info) = &mut mutated.data {
+            info.wallclock += 1;
+        }
+        assert_ne!(value.hash(), mutated.hash());
+
+        // the signature is part of the hash
+        let mut resigned = value.clone();
+        resigned.signature = Signature::default();
+        assert_ne!(value.hash(), resigned.hash());
+    }
+
+    #[test]
+    fn test_vote_slots_and_hash() {
+        let node = Keypair::new();
+        let vote_account = Pubkey::new_unique();
+        let hash = hash::hash(b"bank");
+        let create_vote = |instruction| Vote {
+            from: node.pubkey(),
+            transaction: Transaction::new_with_payer(&[instruction], Some(&node.pubkey())),
+            wallclock: 1_000,
+        };
+
+        let vote = create_vote(vote::instruction::vote(
+            &vote_account,
+            &node.pubkey(),
+            vote::state::Vote::new(vec![100, 101, 102], hash),
+        ));
+        assert_eq!(vote.slots(), vec![100, 101, 102]);
+        assert_eq!(vote.hash(), Some(hash));
+
+        let update = vote::state::VoteStateUpdate::from(vec![(200, 2), (201, 1)]);
+        let vote = create_vote(vote::instruction::compact_update_vote_state(
+            &vote_account,
+            &node.pubkey(),
+            vote::state::VoteStateUpdate { hash, ..update },
+        ));
+        assert_eq!(vote.slots(), vec![200, 201]);
+        assert_eq!(vote.hash(), Some(hash));
+
+        // not a vote
+        let vote = create_vote(solana_sdk::system_instruction::transfer(
+            &node.pubkey(),
+            &vote_account,
+            1,
+        ));
+        assert!(vote.slots().is_empty());
+        assert_eq!(vote.hash(), None);
+
+        let vote = Vote {
+            from: node.pubkey(),
+            transaction: Transaction::default(),
+            wallclock: 1_000,
+        };
+        assert!(vote.slots().is_empty());
+        assert_eq!(vote.hash(), None);
+    }
+
+    #[test]
+    fn test_crds_value_label() {
+        let keypair = Keypair::new();
+        let create_vote = |index: VoteIndex| {
+            let vote = Vote {
+                from: keypair.pubkey(),
+                transaction: Transaction::default(),
+                wallclock: 1_000,
+            };
+            CrdsValue::new_signed(CrdsData::Vote(index, vote), &keypair)
+        };
+
+        let first = create_vote(0);
+        let second = create_vote(1);
+        assert_eq!(first.label(), CrdsValueLabel::Vote(0, keypair.pubkey()));
+        assert_eq!(second.label(), CrdsValueLabel::Vote(1, keypair.pubkey()));
+        assert_ne!(first.label(), second.label());
+        assert_eq!(first.label(), create_vote(0).label());
+
+        let other = Keypair::new();
+        let variants = create_crds_data_variants(other.pubkey(), 1_000);
+        let labels: HashSet<CrdsValueLabel> = variants
+            .into_iter()
+            .map(|data| CrdsValue::new_signed(data, &other).label())
+            .collect();
+        assert_eq!(labels.len(), 12);
+    }
+
+    #[test]
+    fn test_crds_filter_contains() {
+        // partition covering the hashes with the top bit cleared
+        let mut filter = CrdsFilter {
+            filter: Bloom::random(100, 0.1, 7424),
+            mask: 0x7fff_ffff_ffff_ffff,
+            mask_bits: 1,
+        };
+
+        let mut bytes = [0x11u8; 32];
+        bytes[7] = 0x7f;
+        let inside = Hash::new_from_array(bytes);
+        bytes[7] = 0x80;
+        let outside = Hash::new_from_array(bytes);
+
+        assert!(!filter.contains(&inside));
+        filter.filter.add(&inside);
+        filter.filter.add(&outside);
+        assert!(filter.contains(&inside));
+        assert!(!filter.contains(&outside));
+
+        // no mask bits, every hash matches the mask
+        let mut filter = CrdsFilter::default();
+        filter.filter.add(&outside);
+        assert!(filter.contains(&outside));
+    }
+
+    #[test]
+    fn test_crds_filter_new() {
+        let filter = CrdsFilter::new(512, 0.1, 7424);
+        assert_eq!(filter.mask_bits, 0);
+        assert_eq!(filter.mask, !0u64);
+        assert!(filter.filter.bits.len() <= 7424);
+        let default_bits = filter.filter.bits.len();
+
+        // more items than a single filter can hold require mask bits
+        let filter = CrdsFilter::new(10_000, 0.1, 7424);
+        assert_eq!(filter.mask_bits, 3);
+        assert_eq!(filter.mask, 0x1fff_ffff_ffff_ffff);
+
+        // out of range false rates fall back to the default one
+        for false_rate in [0.0, 1.0, -0.5, f64::NAN] {
+            let filter = CrdsFilter::new(512, false_rate, 7424);
+            assert_eq!(filter.mask_bits, 0);
+            assert_eq!(filter.filter.bits.len(), default_bits);
+        }
+    }
+
+    #[test]
+    fn test_crds_filter_estimated_false_positive_rate() {
+        let filter = CrdsFilter {
+            filter: Bloom::new(1000, vec![1, 2, 3]),
+            mask: !0u64,
+            mask_bits: 0,
+        };
+        let rate = filter.estimated_false_positive_rate(100);
+        assert!((rate - 0.017_410_586_496_326_586).abs() < 1e-12, "{rate}");
+        assert!(filter.estimated_false_positive_rate(0).abs() < f64::EPSILON);
+        assert!(filter.estimated_false_positive_rate(1_000) > rate);
+
+        // the default filter stays below its false rate for the items it is sized for
+        let filter = CrdsFilter::default();
+        assert!(filter.estimated_false_positive_rate(512) < CRDS_FILTER_FALSE_RATE);
+    }
+
+    #[test]
+    fn test_crds_filter_fill_ratio() {
+        let mut filter = CrdsFilter::default();
+        assert!(filter.fill_ratio().abs() < f64::EPSILON);
+
+        for _ in 0..512 {
+            filter.filter.add(&Hash::new_unique());
+        }
+        let ratio = filter.fill_ratio();
+        assert!(ratio > 0.0 && ratio < 1.0, "{ratio}");
+
+        let mut saturated = CrdsFilter {
+            filter: Bloom::new(8, vec![1, 2, 3]),
+            mask: !0u64,
+            mask_bits: 0,
+        };
+        for _ in 0..100 {
+            saturated.filter.add(&Hash::new_unique());
+        }
+        assert!((saturated.fill_ratio() - 1.0).abs() < f64::EPSILON);
+
+        let empty = CrdsFilter {
+            filter: Bloom::new(0, vec![]),
+            mask: !0u64,
+            mask_bits: 0,
+        };
+        assert!(empty.fill_ratio().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_crds_filter_from_values() {
+        let values = (0..100)
+            .map(|_| {
+                let keypair = Keypair::new();
+                let info = LegacyContactInfo::new_spy(
+                    &keypair,
+                    parse_addr("141.98.219.218:8001").unwrap(),
+                    0,
+                );
+                CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair)
+            })
+            .collect::<Vec<_>>();
+
+        let filter = CrdsFilter::from_values(&values, 0);
+        assert_eq!(filter.mask_bits, 0);
+        assert!(values.iter().all(|value| filter.contains(&value.hash())));
+
+        let false_positives = (0..1_000)
+            .filter(|_| filter.contains(&Hash::new_unique()))
+            .count();
+        assert!(false_positives < 100, "{false_positives}");
+
+        // a large hint splits the hash space, only the covered values are inserted
+        let filter = CrdsFilter::from_values(&values, 10_000);
+        assert_eq!(filter.mask_bits, 3);
+        for value in &values {
+            let hash = value.hash();
+            assert_eq!(filter.contains(&hash), filter.test_mask(&hash));
+        }
+    }
+
+    #[test]
+    fn test_crds_filter_filters() {
+        let filters = CrdsFilter::filters(10_000, 1287);
+        assert_eq!(filters.len(), 8);
+        assert!(filters.iter().all(|filter| filter.mask_bits == 3));
+
+        // every hash prefix is covered by exactly one filter
+        for prefix in 0..=255u8 {
+            let mut bytes = [0x5au8; 32];
+            bytes[7] = prefix;
+            let hash = Hash::new_from_array(bytes);
+            let matching = filters
+                .iter()
+                .filter(|filter| filter.test_mask(&hash))
+                .count();
+            assert_eq!(matching, 1);
+        }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-enum DeprecatedCompressionType {
-    Uncompressed,
-    GZip,
-    BZip2,
-}
+        // a single filter when everything fits
+        let filters = CrdsFilter::filters(100, 1287);
+        assert_eq!(filters.len(), 1);
+        assert_eq!(filters[0].mask, !0u64);
+
+        // the number of filters is capped
+        let filters = CrdsFilter::filters(usize::MAX, 1);
+        assert_eq!(filters.len(), 1 << CRDS_FILTER_MAX_MASK_BITS);
+        assert!(filters
+            .iter()
+            .all(|filter| filter.mask_bits == CRDS_FILTER_MAX_MASK_BITS));
+    }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub(crate) struct DeprecatedEpochIncompleteSlots {
-    first: Slot,
-    compression: DeprecatedCompressionType,
-    compressed_list: Vec<u8>,
-}
+    #[test]
+    fn test_legacy_version_ordering() {
+        let create_version = |major, minor, patch| LegacyVersion2 {
+            major,
+            minor,
+            patch,
+            commit: None,
+            feature_set: 0,
+        };
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct LowestSlot {
-    pub from: Pubkey,
-    root: Slot,
-    pub lowest: Slot,
-    slots: BTreeSet<Slot>,
-    stash: Vec<DeprecatedEpochIncompleteSlots>,
-    pub wallclock: u64,
-}
+        let mut versions = [
+            create_version(1, 18, 23),
+            create_version(1, 9, 30),
+            create_version(2, 0, 1),
+            create_version(1, 18, 3),
+        ];
+        versions.sort();
+        let versions: Vec<String> = versions
+            .iter()
+            .map(LegacyVersion2::to_semver_string)
+            .collect();
+        assert_eq!(versions, vec!["1.9.30", "1.18.3", "1.18.23", "2.0.1"]);
+
+        let legacy = LegacyVersion1 {
+            major: 1,
+            minor: 18,
+            patch: 3,
+            commit: Some(1),
+        };
+        assert_eq!(legacy.to_semver_string(), "1.18.3");
+        assert_eq!(
+            legacy.cmp_version(&create_version(1, 18, 3)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            legacy.cmp_version(&create_version(1, 18, 23)),
+            Ordering::Less
+        );
+        assert_eq!(
+            legacy.cmp_version(&create_version(1, 9, 30)),
+            Ordering::Greater
+        );
+    }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct IncrementalSnapshotHashes {
-    pub from: Pubkey,
-    pub base: (Slot, Hash),
-    pub hashes: Vec<(Slot, Hash)>,
-    pub wallclock: u64,
-}
+    #[test]
+    fn test_legacy_contact_info_new_spy() {
+        let keypair = Keypair::new();
+        let gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218)), 8000);
+        let before = since_the_epoch_millis();
+
+        let info = LegacyContactInfo::new_spy(&keypair, gossip, 50_093);
+        assert_eq!(info.id, keypair.pubkey());
+        assert_eq!(info.gossip, gossip);
+        assert_eq!(info.shred_version, 50_093);
+        assert!(info.wallclock >= before);
+        assert_eq!(info.valid_addrs(), vec![("gossip", gossip)]);
+
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+        assert!(value.verify());
+    }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum CrdsData {
-    LegacyContactInfo(Box<LegacyContactInfo>), // OK len:254
-    Vote(VoteIndex, Vote),                     // OK len:472
-    LowestSlot(u8, LowestSlot),                // OK len:185
-    SnapshotHashes(SnapshotHashes),            // OK len:240
-    AccountsHashes(SnapshotHashes),            // OK len:800
-    EpochSlots(EpochSlotsIndex, EpochSlots),   // OK len:1049
-    LegacyVersion(LegacyVersion),              // OK len:163
-    Version(Version),                          // OK len:167
-    NodeInstance(NodeInstance),                // OK len:168
-    DuplicateShred(),                          // ??
-    IncrementalSnapshotHashes(IncrementalSnapshotHashes), // OK len:360
-    ContactInfo(),                             // ??
-}
+    #[test]
+    fn test_legacy_contact_info_refreshed() {
+        let keypair = Keypair::new();
+        let gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218)), 8000);
+        let info = LegacyContactInfo::new_spy(&keypair, gossip, 50_093);
+
+        let refreshed = info.refreshed(info.wallclock + 5_000);
+        assert_eq!(refreshed.wallclock, info.wallclock + 5_000);
+        assert_eq!(refreshed.gossip, info.gossip);
+        // always newer, even if the clock went backwards
+        assert_eq!(info.refreshed(0).wallclock, info.wallclock + 1);
+
+        let value =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(refreshed)), &keypair);
+        assert!(value.verify());
+    }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct CrdsValue {
-    pub signature: Signature,
-    pub data: CrdsData,
-}
+    #[test]
+    fn test_legacy_contact_info_valid_addrs() {
+        let mut info = LegacyContactInfo::default();
+        assert!(!info.is_valid_gossip());
+        assert!(info.valid_addrs().is_empty());
+
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        info.gossip = SocketAddr::new(ip, 0);
+        assert!(!info.is_valid_gossip());
+
+        info.gossip = SocketAddr::new(ip, 8001);
+        info.tpu = SocketAddr::new(ip, 8003);
+        info.rpc = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8899);
+        assert!(info.is_valid_gossip());
+        assert_eq!(
+            info.valid_addrs(),
+            vec![("gossip", info.gossip), ("tpu", info.tpu)]
+        );
+    }
 
-impl CrdsValue {
-    pub fn new_signed(data: CrdsData, keypair: &Keypair) -> Self {
-        let signable_data = serialize(&data).expect("failed to serialize CrdsData");
-        let signature = keypair.sign_message(&signable_data);
-        Self { signature, data }
+    #[test]
+    fn test_legacy_contact_info_advertised_service_count() {
+        let keypair = Keypair::new();
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let mut info = LegacyContactInfo::default();
+        assert_eq!(info.advertised_service_count(), 0);
+
+        info = LegacyContactInfo::new_spy(&keypair, SocketAddr::new(ip, 8001), 0);
+        assert_eq!(info.advertised_service_count(), 1);
+
+        info.tvu = SocketAddr::new(ip, 8002);
+        info.tpu = SocketAddr::new(ip, 8003);
+        info.tpu_vote = SocketAddr::new(ip, 8005);
+        // an unspecified ip or a zero port is not advertised
+        info.rpc = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8899);
+        info.repair = SocketAddr::new(ip, 0);
+        assert_eq!(info.advertised_service_count(), 4);
     }
-}
 
-impl fmt::Display for CrdsValue {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.data {
-            CrdsData::LegacyContactInfo(_) => write!(f, "LegacyContactInfo"),
-            CrdsData::Vote(_, _) => write!(f, "Vote"),
-            CrdsData::LowestSlot(_, _) => write!(f, "LowestSlot"),
-            CrdsData::SnapshotHashes(_) => write!(f, "SnapshotHashes"),
-            CrdsData::AccountsHashes(_) => write!(f, "AccountsHashes"),
-            CrdsData::EpochSlots(_, _) => write!(f, "EpochSlots"),
-            CrdsData::LegacyVersion(_) => write!(f, "LegacyVersion"),
-            CrdsData::Version(_) => write!(f, "Version"),
-            CrdsData::NodeInstance(_) => write!(f, "NodeInstance"),
-            CrdsData::DuplicateShred() => write!(f, "DuplicateShred"),
-            CrdsData::IncrementalSnapshotHashes(_) => write!(f, "IncrementalSnapshotHashes"),
-            CrdsData::ContactInfo() => write!(f, "ContactInfo"),
+    #[test]
+    fn test_is_routable() {
+        for addr in [
+            "127.0.0.1:8001",
+            "10.0.0.7:8001",
+            "172.16.4.2:8001",
+            "192.168.1.10:8001",
+            "169.254.0.1:8001",
+            "0.0.0.0:8001",
+            "224.0.0.1:8001",
+            "255.255.255.255:8001",
+            "[::1]:8001",
+            "[::]:8001",
+            "[fe80::1]:8001",
+            "[fd00::1]:8001",
+            "[ff02::1]:8001",
+            "[::ffff:192.168.1.10]:8001",
+        ] {
+            assert!(!is_routable(&parse_addr(addr).unwrap()), "{addr}");
         }
+        assert!(is_routable(&parse_addr("141.98.219.218:8001").unwrap()));
+        assert!(is_routable(&parse_addr("172.32.0.1:8001").unwrap()));
+        assert!(is_routable(&parse_addr("[2001:db8::1]:8001").unwrap()));
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct CrdsFilter {
-    pub filter: Bloom<Hash>,
-    pub mask: u64,
-    pub mask_bits: u32,
-}
+    #[test]
+    fn test_legacy_contact_info_ipv6() {
+        let keypair = Keypair::new();
+        let gossip = parse_addr("[2001:db8::1]:8001").unwrap();
+        let info = LegacyContactInfo::new_spy(&keypair, gossip, 50_093);
+        assert!(info.is_valid_gossip());
+        assert!(!is_valid_addr(&parse_addr("[::]:8001").unwrap()));
+
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair);
+        let bytes = bincode::serialize(&value).unwrap();
+        let decoded: CrdsValue = bincode::deserialize(&bytes).unwrap();
+        assert!(decoded.verify());
+        assert!(
+            matches!(&decoded.data, CrdsData::LegacyContactInfo(info) if info.gossip == gossip)
+        );
+    }
 
-impl Default for CrdsFilter {
-    fn default() -> Self {
-        fn compute_mask(seed: u64, mask_bits: u32) -> u64 {
-            assert!(seed <= 2u64.pow(mask_bits));
-            let seed: u64 = seed.checked_shl(64 - mask_bits).unwrap_or(0x0);
-            seed | (!0u64).checked_shr(mask_bits).unwrap_or(!0x0)
-        }
+    #[test]
+    fn test_snapshot_hashes_highest_slot() {
+        let from = Pubkey::new_unique();
+        let hash = |byte| Hash::new_from_array([byte; 32]);
 
-        #[allow(clippy::cast_possible_truncation)]
-        #[allow(clippy::cast_sign_loss)]
-        fn mask_bits(num_items: f64, max_items: f64) -> u32 {
-            // for small ratios this can result in a negative number, ensure it returns 0 instead
-            ((num_items / max_items).log2().ceil()).max(0.0) as u32
-        }
+        let mut hashes = SnapshotHashes {
+            from,
+            hashes: vec![],
+            wallclock: 0,
+        };
+        assert_eq!(hashes.highest_slot(), None);
+        assert_eq!(hashes.latest_hash(), None);
 
-        let max_items: u32 = 1287;
-        let num_items: u32 = 512;
-        let false_rate: f64 = 0.1f64;
-        let max_bits = 7424u32;
-        let mask_bits = mask_bits(f64::from(num_items), f64::from(max_items));
+        hashes.hashes = vec![(200, hash(2)), (300, hash(3)), (100, hash(1))];
+        assert_eq!(hashes.highest_slot(), Some(300));
+        assert_eq!(hashes.latest_hash(), Some(&hash(3)));
 
-        let bloom: Bloom<Hash> = Bloom::random(max_items as usize, false_rate, max_bits as usize);
+        let mut incremental = IncrementalSnapshotHashes {
+            from,
+            base: (100, hash(1)),
+            hashes: vec![],
+            wallclock: 0,
+        };
+        assert_eq!(incremental.highest_slot(), None);
+        assert_eq!(incremental.latest_hash(), None);
 
-        CrdsFilter {
-            filter: bloom,
-            mask: compute_mask(0_u64, mask_bits),
-            mask_bits,
-        }
+        incremental.hashes = vec![(150, hash(5)), (120, hash(4))];
+        assert_eq!(incremental.highest_slot(), Some(150));
+        assert_eq!(incremental.latest_hash(), Some(&hash(5)));
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct PingGeneric<T> {
-    pub from: Pubkey,
-    token: T,
-    signature: Signature,
-}
+    #[test]
+    fn test_incremental_snapshot_chain() {
+        let hash = |n: u8| Hash::new_from_array([n; 32]);
+        let mut incremental = IncrementalSnapshotHashes {
+            from: Pubkey::new_unique(),
+            base: (100, hash(1)),
+            hashes: vec![],
+            wallclock: 0,
+        };
+        assert_eq!(incremental.chain(), vec![(100, hash(1))]);
+
+        incremental.hashes = vec![
+            (150, hash(5)),
+            (120, hash(4)),
+            (150, hash(6)),
+            (100, hash(7)),
+            (90, hash(8)),
+        ];
+        assert_eq!(
+            incremental.chain(),
+            vec![(100, hash(1)), (120, hash(4)), (150, hash(5))]
+        );
+    }
 
-/// Number of bytes in the randomly generated token sent with ping messages.
-const GOSSIP_PING_TOKEN_SIZE: usize = 32;
+    #[test]
+    fn test_filter_by_shred_version() {
+        let create_info = |shred_version| LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            shred_version,
+            ..LegacyContactInfo::default()
+        };
+        let infos = vec![
+            create_info(50_093),
+            create_info(4_711),
+            create_info(50_093),
+            create_info(0),
+        ];
 
-pub type Ping = PingGeneric<[u8; GOSSIP_PING_TOKEN_SIZE]>;
+        assert!(infos[0].matches_shred_version(50_093));
+        assert!(!infos[1].matches_shred_version(50_093));
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub struct Pong {
-    from: Pubkey,
-    hash: Hash, // Hash of received ping token.
-    signature: Signature,
-}
+        let filtered = filter_by_shred_version(&infos, 50_093);
+        assert_eq!(filtered, vec![&infos[0], &infos[2]]);
+        assert_eq!(filter_by_shred_version(&infos, 4_711), vec![&infos[1]]);
+        assert!(filter_by_shred_version(&infos, 1).is_empty());
+    }
 
-const PING_PONG_HASH_PREFIX: &[u8] = "SOLANA_PING_PONG".as_bytes();
+    #[test]
+    fn test_filter_with_rpc() {
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let create_info = |rpc| LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            rpc,
+            ..LegacyContactInfo::default()
+        };
+        let infos = vec![
+            create_info(SocketAddr::new(ip, 8899)),
+            LegacyContactInfo::default(),
+            create_info(SocketAddr::new(ip, 0)),
+            create_info(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8899)),
+            create_info(parse_addr("[2001:db8::1]:8899").unwrap()),
+        ];
 
-impl Pong {
-    pub fn new<T: SerdeSerialize>(ping: &PingGeneric<T>, keypair: &Keypair) -> Result<Self> {
-        let token = serialize(&ping.token)?;
-        let hash = hash::hashv(&[PING_PONG_HASH_PREFIX, &token]);
-        let pong_response = Pong {
-            from: keypair.pubkey(),
-            hash,
-            signature: keypair.sign_message(hash.as_ref()),
+        assert!(infos[0].has_rpc());
+        assert!(!infos[1].has_rpc());
+        assert_eq!(filter_with_rpc(&infos), vec![&infos[0], &infos[4]]);
+        assert!(filter_with_rpc(&infos[1..4]).is_empty());
+    }
+
+    #[test]
+    fn test_tpu_addrs() {
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let mut info = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            tpu: SocketAddr::new(ip, 8003),
+            tpu_forwards: SocketAddr::new(ip, 0),
+            tpu_vote: SocketAddr::new(ip, 8005),
+            ..LegacyContactInfo::default()
         };
-        Ok(pong_response)
+        assert_eq!(info.tpu_addr(), Some(info.tpu));
+        assert_eq!(info.tpu_forwards_addr(), None);
+        assert_eq!(info.tpu_vote_addr(), Some(info.tpu_vote));
+        // same filtering as the other addresses
+        let valid = info.valid_addrs();
+        assert!(valid.contains(&("tpu", info.tpu)));
+        assert!(!valid.iter().any(|(name, _)| *name == "tpu_forwards"));
+
+        info.tpu_vote = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8005);
+        assert_eq!(info.tpu_vote_addr(), None);
+        assert_eq!(LegacyContactInfo::default().tpu_addr(), None);
     }
-}
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
-pub enum Protocol {
-    PullRequest(CrdsFilter, CrdsValue),
-    PullResponse(Pubkey, Vec<CrdsValue>),
-    PushMessage(Pubkey, Vec<CrdsValue>),
-    PruneMessage(Pubkey),
-    PingMessage(Ping),
-    PongMessage(Pong),
-}
+    #[test]
+    fn test_collect_tpu_endpoints() {
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let leader = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            tpu: SocketAddr::new(ip, 8003),
+            ..LegacyContactInfo::default()
+        };
+        let spy = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            ..LegacyContactInfo::default()
+        };
 
-impl fmt::Display for Protocol {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self {
-            Protocol::PullRequest(_, _) => write!(f, "PullRequest"),
-            Protocol::PullResponse(_, _) => write!(f, "PullResponse"),
-            Protocol::PushMessage(_, _) => write!(f, "PushMessage"),
-            Protocol::PruneMessage(_) => write!(f, "PruneMessage"),
-            Protocol::PingMessage(_) => write!(f, "PingMessage"),
-            Protocol::PongMessage(_) => write!(f, "PongMessage"),
-        }
+        assert_eq!(
+            collect_tpu_endpoints(&[spy.clone(), leader.clone()]),
+            vec![(leader.id, leader.tpu)]
+        );
+        assert!(collect_tpu_endpoints(&[spy]).is_empty());
     }
-}
 
-//tests
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn test_collect_repair_peers() {
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let validator = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            tvu: SocketAddr::new(ip, 8002),
+            serve_repair: SocketAddr::new(ip, 8008),
+            ..LegacyContactInfo::default()
+        };
+        assert_eq!(
+            validator.repair_addrs(),
+            Some((validator.serve_repair, validator.tvu))
+        );
 
-    use crate::{
-        utils::parse_addr,
-        wire::{Payload, PACKET_DATA_SIZE},
-    };
+        // both are needed, an unspecified ip or a zero port is unset
+        let no_tvu = LegacyContactInfo {
+            tvu: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8002),
+            ..validator.clone()
+        };
+        let no_repair = LegacyContactInfo {
+            serve_repair: SocketAddr::new(ip, 0),
+            ..validator.clone()
+        };
+        assert_eq!(no_tvu.repair_addrs(), None);
+        assert_eq!(no_repair.repair_addrs(), None);
 
-    use super::*;
+        assert_eq!(
+            collect_repair_peers(&[no_tvu, validator.clone(), no_repair]),
+            vec![(validator.id, validator.serve_repair, validator.tvu)]
+        );
+    }
 
     #[test]
-    fn test_sigh_crds_data() {
+    fn test_estimate_cluster_tip() {
+        assert_eq!(estimate_cluster_tip([]), None);
+
+        // ten nodes from slot 100 to 109 and one running far ahead
+        let mut highest = (100..110).collect::<Vec<_>>();
+        highest.push(1_000_000);
+        assert_eq!(estimate_cluster_tip(highest), Some(109));
+
+        // a single node is its own tip
+        assert_eq!(estimate_cluster_tip([100]), Some(100));
+    }
+
+    #[test]
+    fn test_epoch_slots_highest_slot() {
+        let epoch_slots = EpochSlots::from_slots(Pubkey::new_unique(), &[100, 20_000, 20_005], 0);
+        assert_eq!(epoch_slots.highest_slot(), Some(20_005));
+        assert_eq!(
+            EpochSlots::from_slots(Pubkey::new_unique(), &[], 0).highest_slot(),
+            None
+        );
+
+        // the set bit beyond the largest slot is not reported
+        let mut slots: BitVec<u8> = BitVec::new_fill(false, 8);
+        slots.set(0, true);
+        slots.set(7, true);
+        let overflowing = Uncompressed {
+            first_slot: u64::MAX - 1,
+            num: 8,
+            slots,
+        };
+        assert_eq!(overflowing.highest_slot(), None);
+    }
+
+    #[test]
+    fn test_filter_full_history() {
         let keypair = Keypair::new();
+        let archive = Pubkey::new_unique();
+        let pruned = Pubkey::new_unique();
+        let values = [
+            CrdsData::LowestSlot(0, LowestSlot::new(archive, 0, 1)),
+            CrdsData::LowestSlot(0, LowestSlot::new(pruned, 250_000_000, 1)),
+            CrdsData::LowestSlot(0, LowestSlot::new(archive, 10, 2)),
+            CrdsData::LegacyContactInfo(Box::default()),
+        ]
+        .into_iter()
+        .map(|data| CrdsValue::new_signed(data, &keypair))
+        .collect::<Vec<_>>();
+
+        assert_eq!(filter_full_history(&values, 100_000_000), vec![archive]);
+        let mut both = vec![archive, pruned];
+        both.sort();
+        assert_eq!(filter_full_history(&values, Slot::MAX), both);
+        assert!(filter_full_history(&values, 0).is_empty());
+    }
 
-        let crds_data = CrdsData::LegacyContactInfo(Box::new(LegacyContactInfo::default()));
-        let crds_value = CrdsValue::new_signed(crds_data.clone(), &keypair);
+    #[test]
+    fn test_shred_version_counts() {
+        let create_info = |shred_version| LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            shred_version,
+            ..LegacyContactInfo::default()
+        };
+        let infos = vec![
+            create_info(4_711),
+            create_info(50_093),
+            create_info(0),
+            create_info(50_093),
+            create_info(50_093),
+        ];
 
-        let pubkey = keypair.pubkey();
-        let message_bytes = serialize(&crds_data).expect("failed to serialize CrdsData");
+        let counts = shred_version_counts(&infos);
         assert_eq!(
-            crds_value.signature.verify(pubkey.as_ref(), &message_bytes),
-            true
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (4_711, 1), (50_093, 3)]
         );
+        assert!(shred_version_counts(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_pull_responses() {
+        let keypair = Keypair::new();
+        let values = (0..20)
+            .map(|wallclock| {
+                let info = LegacyContactInfo {
+                    id: keypair.pubkey(),
+                    wallclock,
+                    ..LegacyContactInfo::default()
+                };
+                CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair)
+            })
+            .collect::<Vec<_>>();
+
+        let responses = Protocol::pull_responses(keypair.pubkey(), values.clone(), 20).unwrap();
+        assert!(responses.len() > 1);
+        let mut sent = vec![];
+        for response in responses {
+            assert!(bincode::serialized_size(&response).unwrap() <= PACKET_DATA_SIZE as u64);
+            let Protocol::PullResponse(from, chunk) = response else {
+                panic!("not a pull response");
+            };
+            assert_eq!(from, keypair.pubkey());
+            sent.extend(chunk);
+        }
+        assert_eq!(sent, values);
+
+        // the number of values is capped
+        let responses = Protocol::pull_responses(keypair.pubkey(), values, 3).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert!(matches!(&responses[0], Protocol::PullResponse(_, chunk) if chunk.len() == 3));
+    }
+
+    #[test]
+    fn test_chunk_values() {
+        let keypair = Keypair::new();
+        let origin = keypair.pubkey();
+        let values = (0..8)
+            .map(|token| {
+                let data = CrdsData::NodeInstance(NodeInstance {
+                    from: origin,
+                    wallclock: token,
+                    timestamp: 0,
+                    token,
+                });
+                CrdsValue::new_signed(data, &keypair)
+            })
+            .collect::<Vec<_>>();
+        assert!(chunk_values(origin, vec![], PACKET_DATA_SIZE).is_empty());
+
+        let messages = chunk_values(origin, values.clone(), PACKET_DATA_SIZE);
+        assert_eq!(messages.len(), 1);
+
+        // each packet stays under the budget, no value is lost
+        let max_bytes = 400;
+        let messages = chunk_values(origin, values.clone(), max_bytes);
+        assert!(messages.len() > 1);
+        let mut packed = vec![];
+        for message in messages {
+            assert!(message.serialized_size().unwrap() <= max_bytes as u64);
+            let Protocol::PushMessage(from, chunk) = message else {
+                panic!("not a push message");
+            };
+            assert_eq!(from, origin);
+            packed.extend(chunk);
+        }
+        assert_eq!(packed, values);
+
+        // a value larger than the budget is pushed alone
+        let messages = chunk_values(origin, values[..3].to_vec(), 100);
+        assert_eq!(messages.len(), 3);
+        assert!(messages
+            .iter()
+            .all(|message| matches!(message, Protocol::PushMessage(_, chunk) if chunk.len() == 1)));
     }
 
     #[test]
@@ -370,13 +2857,11 @@ mod tests {
     fn create_payload(message: &[u8]) -> Payload {
         let mut buf = [0; PACKET_DATA_SIZE];
 
-        for i in 0..message.len() {
-            buf[i] = message[i];
-        }
+        buf[..message.len()].copy_from_slice(message);
 
         Payload {
             len: message.len(),
-            buf: buf,
+            buf,
             addr: None,
         }
     }
@@ -400,7 +2885,6 @@ mod tests {
         assert!(matches!(protocol, Protocol::PingMessage(_)));
 
         if let Protocol::PingMessage(ping) = protocol {
-
             assert_eq!(
                 ping.from.to_string(),
                 "44fNPdtMtRDhRcfsNqxa5d5ZjifbM1WRjUxszxwFuY2W"
@@ -440,7 +2924,6 @@ mod tests {
         assert!(matches!(protocol, Protocol::PongMessage(_)));
 
         if let Protocol::PongMessage(pong) = protocol {
-
             assert_eq!(
                 pong.from.to_string(),
                 "5kqgfKSazLt43S4n7rXUh61gn53iphQEam6bPaC5sFSs"
@@ -517,7 +3000,6 @@ mod tests {
             assert!(matches!(crds_data, CrdsData::LegacyContactInfo(_,)));
 
             if let CrdsData::LegacyContactInfo(info) = crds_data {
-
                 assert_eq!(
                     info.id.to_string(),
                     "BVvsUC7bcugkAE71bpDpDNpZuwsqY35syesvPtjShPDs"
@@ -533,7 +3015,7 @@ mod tests {
                 assert_eq!(info.rpc, parse_addr("0.0.0.0:9007").unwrap());
                 assert_eq!(info.rpc_pubsub, parse_addr("0.0.0.0:9008").unwrap());
                 assert_eq!(info.serve_repair, parse_addr("0.0.0.0:9009").unwrap());
-                assert_eq!(info.wallclock, 1681747000803);
+                assert_eq!(info.wallclock, 1_681_747_000_803);
                 assert_eq!(info.shred_version, 0);
             }
         }
@@ -572,7 +3054,6 @@ mod tests {
             assert!(matches!(crds_data, CrdsData::LegacyContactInfo(_,)));
 
             if let CrdsData::LegacyContactInfo(info) = crds_data {
-
                 assert_eq!(
                     info.id.to_string(),
                     "8YcR2zEgUXYkKBtnWCSWM3Hbycu6RMqNvi9sGJmvezQE"
@@ -588,9 +3069,71 @@ mod tests {
                 assert_eq!(info.rpc, parse_addr("0.0.0.0:9007").unwrap());
                 assert_eq!(info.rpc_pubsub, parse_addr("0.0.0.0:9008").unwrap());
                 assert_eq!(info.serve_repair, parse_addr("0.0.0.0:9009").unwrap());
-                assert_eq!(info.wallclock, 1681747487616);
+                assert_eq!(info.wallclock, 1_681_747_487_616);
                 assert_eq!(info.shred_version, 0);
             }
         }
     }
+
+    #[test]
+    fn test_parse_push_message_contact_info_message() {
+        let data: [u8; 194] = [
+            2, 0, 0, 0, 112, 26, 219, 83, 31, 191, 215, 27, 61, 28, 154, 238, 134, 84, 53, 138,
+            195, 64, 71, 69, 95, 125, 193, 73, 179, 255, 150, 187, 36, 104, 203, 159, 1, 0, 0, 0,
+            0, 0, 0, 0, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 11, 0, 0, 0, 112, 26, 219, 83, 31, 191, 215, 27, 61, 28,
+            154, 238, 134, 84, 53, 138, 195, 64, 71, 69, 95, 125, 193, 73, 179, 255, 150, 187, 36,
+            104, 203, 159, 128, 215, 216, 255, 248, 48, 123, 248, 77, 155, 136, 249, 5, 0, 173,
+            195, 2, 1, 13, 77, 60, 43, 26, 69, 240, 209, 206, 3, 1, 0, 0, 0, 0, 10, 20, 30, 40, 3,
+            0, 0, 193, 62, 2, 0, 130, 7, 10, 0, 100, 0,
+        ];
+
+        let info_payload = create_payload(&data);
+
+        let protocol: Protocol = info_payload.deserialize_slice(..).unwrap();
+
+        assert!(matches!(protocol, Protocol::PushMessage(_, _)));
+
+        if let Protocol::PushMessage(pubkey, crds_values) = &protocol {
+            assert_eq!(
+                pubkey.to_string(),
+                "8YcR2zEgUXYkKBtnWCSWM3Hbycu6RMqNvi9sGJmvezQE"
+            );
+
+            let crds_data = &crds_values[0].data;
+            assert!(matches!(crds_data, CrdsData::ContactInfo(_)));
+
+            if let CrdsData::ContactInfo(info) = crds_data {
+                assert_eq!(
+                    info.pubkey.to_string(),
+                    "8YcR2zEgUXYkKBtnWCSWM3Hbycu6RMqNvi9sGJmvezQE"
+                );
+                assert_eq!(info.wallclock, 1_681_747_487_616);
+                assert_eq!(info.outset, 1_681_740_000_000_123);
+                assert_eq!(info.shred_version, 50093);
+                assert_eq!(
+                    info.version,
+                    ContactInfoVersion {
+                        major: 2,
+                        minor: 1,
+                        patch: 13,
+                        commit: 0x1a2b_3c4d,
+                        feature_set: 3_469_865_029,
+                        client: 3,
+                    }
+                );
+                assert_eq!(
+                    info.socket(SOCKET_TAG_GOSSIP),
+                    parse_addr("10.20.30.40:8001")
+                );
+                assert_eq!(info.socket(SOCKET_TAG_RPC), parse_addr("10.20.30.40:8899"));
+                assert_eq!(info.socket(SOCKET_TAG_TVU), parse_addr("10.20.30.40:8999"));
+                assert_eq!(info.socket(SOCKET_TAG_TPU), None);
+            }
+        }
+
+        // round-trip
+        assert_eq!(serialize(&protocol).unwrap(), data);
+    }
 }
@@ -1,14 +1,24 @@
 use std::{
-    net::{SocketAddr, ToSocketAddrs},
-    time::{SystemTime, UNIX_EPOCH},
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use solana_sdk::signature::Keypair;
+use rand::Rng;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
 
 use crate::errors::Result;
-use crate::protocol::{CrdsData, CrdsFilter, CrdsValue, LegacyContactInfo, Ping, Pong, Protocol};
+use crate::protocol::{CrdsData, CrdsFilter, CrdsValue, LegacyContactInfo, Ping, Protocol};
 use crate::wire::Payload;
 
+/// Milliseconds since the epoch, the wallclock of the gossip values.
+///
+/// # Panics
+///
+/// If the system clock is set before the epoch.
 #[allow(clippy::cast_possible_truncation)]
 pub fn since_the_epoch_millis() -> u64 {
     let start = SystemTime::now();
@@ -19,6 +29,21 @@ pub fn since_the_epoch_millis() -> u64 {
     since_the_epoch.as_millis() as u64
 }
 
+/// Convert a wallclock, in milliseconds since the epoch, to a `SystemTime`.
+/// Values not representable by the platform fall back to the current time.
+pub fn wallclock_to_systemtime(wallclock: u64) -> SystemTime {
+    UNIX_EPOCH
+        .checked_add(Duration::from_millis(wallclock))
+        .unwrap_or_else(SystemTime::now)
+}
+
+/// Time elapsed since the wallclock, zero for timestamps in the future.
+pub fn wallclock_age(wallclock: u64) -> Duration {
+    SystemTime::now()
+        .duration_since(wallclock_to_systemtime(wallclock))
+        .unwrap_or(Duration::ZERO)
+}
+
 pub fn parse_addr(addr: &str) -> Option<SocketAddr> {
     let addrs = addr
         .to_socket_addrs()
@@ -27,6 +52,71 @@ pub fn parse_addr(addr: &str) -> Option<SocketAddr> {
     addrs.first().copied()
 }
 
+/// Unspecified address of the same family as `addr`, to bind a socket able to reach it.
+pub fn unspecified_ip_for(addr: &SocketAddr) -> IpAddr {
+    match addr {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    }
+}
+
+/// Bind the gossip socket on every interface. The dual stack socket reaches
+/// both the IPv4 and the IPv6 peers, hosts without IPv6 fall back to IPv4 only.
+pub fn bind_gossip_socket(port: u16) -> io::Result<UdpSocket> {
+    UdpSocket::bind(SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port))
+        .or_else(|_| UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port)))
+}
+
+/// Destination address as expected by a socket bound on `local`,
+/// an IPv6 socket reaches the IPv4 peers through their IPv4-mapped address.
+pub fn dest_addr_for(local: &SocketAddr, dest: SocketAddr) -> SocketAddr {
+    match (local, dest) {
+        (SocketAddr::V6(_), SocketAddr::V4(dest)) => {
+            SocketAddr::new(IpAddr::V6(dest.ip().to_ipv6_mapped()), dest.port())
+        }
+        _ => dest,
+    }
+}
+
+/// Source address of a datagram, the IPv4-mapped ones received by a
+/// dual stack socket are turned back into IPv4.
+pub fn canonical_addr(addr: SocketAddr) -> SocketAddr {
+    SocketAddr::new(addr.ip().to_canonical(), addr.port())
+}
+
+/// Order the peers by stake, each pick weighted by its share of the remaining
+/// stake as the validators do. The peers without stake come last in a uniform
+/// order, so with no stake information at all the result is a plain shuffle.
+#[allow(clippy::cast_precision_loss)]
+pub fn weighted_shuffle<R: Rng>(peers: &[(Pubkey, u64)], rng: &mut R) -> Vec<Pubkey> {
+    // Efraimidis-Spirakis: sort by u^(1/weight), compared through ln(u)/weight
+    let mut keyed = peers
+        .iter()
+        .map(|(pubkey, stake)| {
+            let u: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+            let key = if *stake == 0 {
+                f64::NEG_INFINITY
+            } else {
+                u.ln() / *stake as f64
+            };
+            (key, u, *pubkey)
+        })
+        .collect::<Vec<_>>();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1)));
+    keyed.into_iter().map(|(_, _, pubkey)| pubkey).collect()
+}
+
+/// Serialize the message in a packet for the destination, messages which
+/// don't fit a single packet are rejected.
+pub fn create_payload(protocol: &Protocol, dest: SocketAddr) -> Result<Payload> {
+    protocol.check_packet_size()?;
+
+    let mut payload = Payload::default();
+    payload.populate_packet(Some(dest), protocol)?;
+
+    Ok(payload)
+}
+
 pub fn create_pull_request(
     contact_info: LegacyContactInfo,
     keypair: &Keypair,
@@ -38,10 +128,25 @@ pub fn create_pull_request(
 
     let protocol = Protocol::PullRequest(crds_filter, crds_value);
 
-    let mut payload = Payload::default();
-    payload.populate_packet(Some(entrypoint_addr), &protocol)?;
+    create_payload(&protocol, entrypoint_addr)
+}
 
-    Ok(payload)
+/// Push our contact info to the peer, signed with the node keypair.
+pub fn create_push_message(
+    contact_info: LegacyContactInfo,
+    keypair: &Keypair,
+    peer_addr: SocketAddr,
+) -> Result<Payload> {
+    let crds_data = CrdsData::LegacyContactInfo(Box::new(contact_info));
+    let crds_value = CrdsValue::new_signed(crds_data, keypair);
+
+    let protocol = Protocol::PushMessage(keypair.pubkey(), vec![crds_value]);
+
+    create_payload(&protocol, peer_addr)
+}
+
+pub fn create_ping(ping: Ping, peer_addr: SocketAddr) -> Result<Payload> {
+    create_payload(&Protocol::PingMessage(ping), peer_addr)
 }
 
 pub fn create_pong_response(
@@ -49,21 +154,149 @@ pub fn create_pong_response(
     from_addr: SocketAddr,
     keypair: &Keypair,
 ) -> Result<Payload> {
-    let value = Pong::new(ping, keypair)?;
-    let protocol = Protocol::PongMessage(value);
-
-    let mut payload = Payload::default();
-    payload.populate_packet(Some(from_addr), &protocol)?;
+    let protocol = Protocol::pong_response(ping, keypair)?;
 
-    Ok(payload)
+    create_payload(&protocol, from_addr)
 }
 
 //tests
 #[cfg(test)]
 mod tests {
-    use std::net::{IpAddr, Ipv4Addr};
-
     use super::*;
+    use crate::{errors::Error, wire::PACKET_DATA_SIZE};
+
+    #[test]
+    fn test_wallclock_to_systemtime() {
+        assert_eq!(wallclock_to_systemtime(0), UNIX_EPOCH);
+        assert_eq!(
+            wallclock_to_systemtime(1_681_834_717_999),
+            UNIX_EPOCH + Duration::from_millis(1_681_834_717_999)
+        );
+        // must not panic on overflow
+        let _ = wallclock_to_systemtime(u64::MAX);
+    }
+
+    #[test]
+    fn test_weighted_shuffle() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let peers = (0..20u64)
+            .map(|i| (Pubkey::new_unique(), i * 1_000))
+            .collect::<Vec<_>>();
+
+        let first = weighted_shuffle(&peers, &mut StdRng::seed_from_u64(42));
+        let second = weighted_shuffle(&peers, &mut StdRng::seed_from_u64(42));
+        assert_eq!(first, second);
+        assert_eq!(first.len(), peers.len());
+        // the unstaked peer is always last
+        assert_eq!(first.last(), Some(&peers[0].0));
+
+        // the heaviest peer leads far more often than a uniform pick would
+        let mut rng = StdRng::seed_from_u64(7);
+        let heaviest = peers[19].0;
+        let leads = (0..1_000)
+            .filter(|_| weighted_shuffle(&peers, &mut rng)[0] == heaviest)
+            .count();
+        assert!(leads > 50, "leads: {leads}");
+
+        // without stake it is still a permutation of the peers
+        let unstaked = peers.iter().map(|(p, _)| (*p, 0)).collect::<Vec<_>>();
+        let mut shuffled = weighted_shuffle(&unstaked, &mut rng);
+        shuffled.sort();
+        let mut expected = peers.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(shuffled, expected);
+        assert!(weighted_shuffle(&[], &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_wallclock_age() {
+        let now = since_the_epoch_millis();
+
+        let age = wallclock_age(now - 3_000);
+        assert!(age >= Duration::from_secs(3));
+        assert!(age < Duration::from_secs(30));
+
+        assert_eq!(wallclock_age(now + 60_000), Duration::ZERO);
+        assert_eq!(wallclock_age(u64::MAX), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_dest_addr_for() {
+        let v4_local = parse_addr("0.0.0.0:8001").unwrap();
+        let v6_local = parse_addr("[::]:8001").unwrap();
+        let v4_peer = parse_addr("141.98.219.218:8000").unwrap();
+        let v6_peer = parse_addr("[2001:db8::1]:8000").unwrap();
+
+        assert_eq!(dest_addr_for(&v4_local, v4_peer), v4_peer);
+        assert_eq!(dest_addr_for(&v6_local, v6_peer), v6_peer);
+        let mapped = dest_addr_for(&v6_local, v4_peer);
+        assert_eq!(mapped, parse_addr("[::ffff:141.98.219.218]:8000").unwrap());
+        assert_eq!(canonical_addr(mapped), v4_peer);
+        assert_eq!(canonical_addr(v6_peer), v6_peer);
+
+        assert_eq!(
+            unspecified_ip_for(&v4_peer),
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        );
+        assert_eq!(
+            unspecified_ip_for(&v6_peer),
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        );
+    }
+
+    #[test]
+    fn test_bind_gossip_socket() {
+        let socket = bind_gossip_socket(0).unwrap();
+        let local = socket.local_addr().unwrap();
+        let peer = UdpSocket::bind(parse_addr("127.0.0.1:0").unwrap()).unwrap();
+        let peer_addr = peer.local_addr().unwrap();
+
+        socket
+            .send_to(b"gossip", dest_addr_for(&local, peer_addr))
+            .unwrap();
+        let mut buf = [0; 16];
+        let (len, _) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"gossip");
+
+        peer.send_to(b"answer", SocketAddr::new(peer_addr.ip(), local.port()))
+            .unwrap();
+        let (len, from) = socket.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"answer");
+        assert_eq!(canonical_addr(from), peer_addr);
+    }
+
+    #[test]
+    fn test_create_payload_oversized_push() {
+        let keypair = Keypair::new();
+        let dest = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218)), 8001);
+        let create_push = |count| {
+            let values = (0..count)
+                .map(|_| {
+                    let info = LegacyContactInfo::new_spy(&keypair, dest, 0);
+                    CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &keypair)
+                })
+                .collect();
+            Protocol::PushMessage(keypair.pubkey(), values)
+        };
+
+        let push = create_push(1);
+        let payload = create_payload(&push, dest).unwrap();
+        assert_eq!(payload.len as u64, push.serialized_size().unwrap());
+        assert_eq!(payload.addr, Some(dest));
+
+        let push = create_push(10);
+        let size = push.serialized_size().unwrap();
+        assert!(size > PACKET_DATA_SIZE as u64);
+        assert!(matches!(
+            push.check_packet_size(),
+            Err(Error::PacketTooLarge { max, .. }) if max == PACKET_DATA_SIZE as u64
+        ));
+        assert!(matches!(
+            create_payload(&push, dest),
+            Err(Error::PacketTooLarge { size: rejected, .. }) if rejected == size
+        ));
+    }
 
     #[test]
     fn test_parse_addr() {
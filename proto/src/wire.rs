@@ -11,6 +11,23 @@ use crate::errors::Result;
 ///   8 bytes is the size of the fragment header
 pub const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
 
+/// Deserialize with the same bincode options used by the Solana validators
+/// to decode incoming packets. Bincode does not apply the size limit when
+/// reading from a slice, so the bytes beyond a datagram are refused upfront.
+pub fn deserialize_bytes<T>(bytes: &[u8]) -> bincode::Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    if bytes.len() > PACKET_DATA_SIZE {
+        return Err(Box::new(bincode::ErrorKind::SizeLimit));
+    }
+    bincode::options()
+        .with_limit(PACKET_DATA_SIZE as u64)
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(bytes)
+}
+
 #[derive(Debug)]
 pub struct Payload {
     pub len: usize,
@@ -38,7 +55,7 @@ impl Payload {
         let mut wr = io::Cursor::new(self.buffer_mut());
         let r = bincode::serialize_into(&mut wr, data);
         match r {
-            Ok(_) => {
+            Ok(()) => {
                 self.len = wr.position() as usize;
                 self.addr = dest;
             }
@@ -58,12 +75,7 @@ impl Payload {
         let bytes = self
             .data(index)
             .ok_or(bincode::Error::from(bincode::ErrorKind::SizeLimit))?;
-        bincode::options()
-            .with_limit(PACKET_DATA_SIZE as u64)
-            .with_fixint_encoding()
-            .reject_trailing_bytes()
-            .deserialize(bytes)
-            .map_err(std::convert::Into::into)
+        deserialize_bytes(bytes).map_err(std::convert::Into::into)
     }
 
     #[inline]
@@ -1,20 +1,110 @@
-use std::sync::mpsc::Sender;
+use std::{collections::HashMap, io::Stdout, net::SocketAddr, sync::mpsc::Sender, time::Instant};
 
+use arboard::Clipboard;
+use solana_gossip_proto::{
+    crds::{
+        CrdsTable, CrdsTableHistory, DuplicateShredTracker, HighestSlots, NodeInstanceTracker,
+        VerificationTracker, VoteTracker,
+    },
+    protocol::{CrdsFilter, Slot},
+};
+use solana_sdk::pubkey::Pubkey;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::SharedMetrics;
 use crate::{
+    common::PausedQueue,
+    config::AppConfig,
+    history::NodeCountHistory,
+    lookup::IpLookup,
+    rate::StatsSnapshot,
+    stream::JsonStream,
     transport::CtrlCmd,
-    ui::{list_stateful_widget::StatefulList, table_stateful_widget::StatefulTable},
+    ui::{
+        epoch_slots_widget::SlotsCoverage, list_stateful_widget::StatefulList,
+        node_table_widget::StatefulNodeTable, quit_summary_widget::RunSummary,
+    },
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    /// keys are captured to edit the node table filter
+    Editing,
+    /// keys are captured to type the pubkey of the node to follow
+    Following,
+}
+
 #[derive(Debug)]
 pub struct Model {
+    pub started_at: Instant,
+    /// shown while the quit is waiting to be confirmed
+    pub quit_summary: Option<RunSummary>,
+    pub input_mode: InputMode,
+    /// base58 pubkey typed while following a node
+    pub follow_input: String,
+    /// why the last typed pubkey could not be followed
+    pub follow_error: Option<String>,
+    /// while paused the received values are queued instead of shown
+    pub paused: bool,
+    pub paused_queue: PausedQueue,
+    pub crds: CrdsTable,
+    /// last values of each label, when enabled
+    pub crds_history: Option<CrdsTableHistory>,
+    /// highest slot voted by each node
+    pub votes: VoteTracker,
+    /// highest slot of each node, from its votes and epoch slots
+    pub highest_slots: HighestSlots,
+    /// estimated from the highest slots, refreshed every second
+    pub cluster_tip: Option<Slot>,
+    /// duplicate slot proofs gossiped by the nodes
+    pub duplicate_shreds: DuplicateShredTracker,
+    /// recent node instances, to spot identities running twice
+    pub node_instances: NodeInstanceTracker,
+    /// pubkeys announced by two running instances, refreshed every second
+    pub instance_conflicts: Vec<Pubkey>,
+    /// origins of the values failing their signature
+    pub verification: VerificationTracker,
+    /// country lookups, enabled when a geolocation database is configured
+    pub geo: Option<IpLookup>,
+    /// reverse DNS lookups of the nodes shown in the detail view
+    pub dns: Option<IpLookup>,
+    /// the integrated values written as JSON lines, headless only
+    pub stream: Option<JsonStream<Stdout>>,
+    /// refreshed for the metrics server, when enabled
+    #[cfg(feature = "metrics")]
+    pub metrics: Option<SharedMetrics>,
+
     pub debug_messages_stateful: StatefulList<String>,
-    pub home_stateful_table: StatefulTable<String>,
+    pub home_node_table: StatefulNodeTable,
     pub home_stats_stateful_list: StatefulList<String>,
+    pub rates: StatsSnapshot,
+    pub node_count_history: NodeCountHistory,
+    pub detail_node: Option<Pubkey>,
+    pub detail_field: usize,
+    /// index of the node value shown as a hex dump, none while hidden
+    pub detail_hex: Option<usize>,
+    pub detail_hex_scroll: u16,
+    /// epoch slots coverage of the nodes shown in the detail view, dropped
+    /// when their epoch slots change
+    pub slots_coverage: HashMap<Pubkey, Option<SlotsCoverage>>,
+    pub status_message: Option<String>,
+    /// peers which pruned our values, with the prune wallclock
+    pub pruned_by: HashMap<Pubkey, u64>,
+    /// last pull request filter received, shown in the debug view
+    pub pull_filter: Option<(SocketAddr, CrdsFilter)>,
 
     pub entrypoints_stateful: StatefulList<String>,
     pub entrypoints: Vec<String>,
     pub entrypoint: Option<String>,
     pub listern_port: u16,
+    /// when the gossip socket was bound, none while disconnected
+    pub connected_at: Option<Instant>,
+    /// when the last pull response was received
+    pub last_response_at: Option<Instant>,
+    /// entrypoints the pull requests rotate across, with their last answer
+    pub entrypoint_responses: Vec<(SocketAddr, Option<Instant>)>,
 }
 
 impl Default for Model {
@@ -31,29 +121,67 @@ impl Default for Model {
         .map(String::from)
         .collect::<Vec<String>>();
         Model {
+            started_at: Instant::now(),
+            quit_summary: None,
+            input_mode: InputMode::Normal,
+            follow_input: String::new(),
+            follow_error: None,
+            paused: false,
+            paused_queue: PausedQueue::default(),
+            crds: CrdsTable::default(),
+            crds_history: None,
+            votes: VoteTracker::default(),
+            highest_slots: HighestSlots::default(),
+            cluster_tip: None,
+            duplicate_shreds: DuplicateShredTracker::default(),
+            node_instances: NodeInstanceTracker::default(),
+            instance_conflicts: vec![],
+            verification: VerificationTracker::default(),
+            geo: None,
+            dns: None,
+            stream: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
             debug_messages_stateful: StatefulList::default(),
-            home_stateful_table: StatefulTable::default(),
+            home_node_table: StatefulNodeTable::default(),
             home_stats_stateful_list: StatefulList::with_items(vec![
                 "[Receiver] processed msgs #:0".to_string(),
                 "[Sender] processed msgs #:0".to_string(),
                 "[Logic] processed msgs #:0".to_string(),
             ]),
+            rates: StatsSnapshot::default(),
+            node_count_history: NodeCountHistory::default(),
+            detail_node: None,
+            detail_field: 0,
+            detail_hex: None,
+            detail_hex_scroll: 0,
+            slots_coverage: HashMap::new(),
+            status_message: None,
+            pruned_by: HashMap::new(),
+            pull_filter: None,
             entrypoints_stateful: StatefulList::default(),
             entrypoints,
             entrypoint: None,
             listern_port: 8001,
+            connected_at: None,
+            last_response_at: None,
+            entrypoint_responses: vec![],
         }
     }
 }
 
+pub type ClipboardError = arboard::Error;
+
 pub struct Context {
     app_id: String,
     app_version: String,
 
     pub model: Model,
-    pub trace: bool,
+    pub config: AppConfig,
 
     pub ctrl_txs: Vec<Sender<CtrlCmd>>,
+
+    clipboard: Option<Clipboard>,
 }
 
 impl Context {
@@ -62,11 +190,22 @@ impl Context {
             app_id,
             app_version,
             model: Model::default(),
-            trace: false,
+            config: AppConfig::default(),
             ctrl_txs: Vec::new(),
+            clipboard: None,
         }
     }
 
+    /// Copy the text to the system clipboard. The clipboard is kept open with the
+    /// context, on some platforms its content is lost when it is closed.
+    pub fn copy_to_clipboard(&mut self, text: &str) -> Result<(), ClipboardError> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(Clipboard::new()?),
+        };
+        clipboard.set_text(text)
+    }
+
     pub fn info(&self) -> String {
         format!("AppId:{}, AppVersion:{}\n", self.app_id, self.app_version)
     }
@@ -87,8 +226,9 @@ mod mock_test {
                 app_id: String::from("_app_id_"),
                 app_version: String::from("_app_version_"),
                 model: Model::default(),
-                trace: false,
+                config: AppConfig::default(),
                 ctrl_txs: Vec::new(),
+                clipboard: None,
             }
         }
     }
@@ -0,0 +1,101 @@
+use std::{
+    fmt::{self, Write as _},
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+/// Directory the packets failing to deserialize are written to.
+pub const BAD_PACKETS_DIR: &str = "bad-packets";
+
+/// Lowercase hex of the bytes, without separators.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// Writes each packet failing to deserialize to its own file, the samples
+/// to fix the parsing of the unsupported variants.
+#[derive(Debug)]
+pub struct BadPacketDump {
+    dir: PathBuf,
+    written: u64,
+}
+
+impl BadPacketDump {
+    /// Create the directory when missing.
+    pub fn new(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(BadPacketDump {
+            dir: dir.to_path_buf(),
+            written: 0,
+        })
+    }
+
+    /// Write the sender, the receive time, the error and the hex encoded bytes,
+    /// returns the path of the file.
+    pub fn write(
+        &mut self,
+        from: SocketAddr,
+        bytes: &[u8],
+        err: &dyn fmt::Display,
+        now_ms: u64,
+    ) -> io::Result<PathBuf> {
+        let name = format!(
+            "{now_ms}-{}-{}.txt",
+            from.to_string().replace([':', '[', ']'], "_"),
+            self.written
+        );
+        let path = self.dir.join(name);
+        let content = format!(
+            "from: {from}\ntimestamp_ms: {now_ms}\nlen: {}\nerror: {err}\n{}\n",
+            bytes.len(),
+            to_hex(bytes)
+        );
+        fs::write(&path, content)?;
+        self.written += 1;
+        Ok(path)
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(&[]), "");
+        assert_eq!(to_hex(&[0x00, 0x0f, 0xab, 0xff]), "000fabff");
+    }
+
+    #[test]
+    fn test_bad_packet_dump() {
+        let dir = std::env::temp_dir().join(format!("bad-packets-{}", Pubkey::new_unique()));
+        let mut dump = BadPacketDump::new(&dir).unwrap();
+        let from = "[2001:db8::1]:8001".parse().unwrap();
+
+        let first = dump
+            .write(from, &[1, 2, 0xfe], &"invalid tag", 1_681_834_717_999)
+            .unwrap();
+        let second = dump
+            .write(from, &[1, 2, 0xfe], &"invalid tag", 1_681_834_717_999)
+            .unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            first.file_name().unwrap(),
+            "1681834717999-_2001_db8__1__8001-0.txt"
+        );
+
+        let content = fs::read_to_string(&first).unwrap();
+        assert_eq!(
+            content,
+            "from: [2001:db8::1]:8001\ntimestamp_ms: 1681834717999\nlen: 3\nerror: invalid tag\n0102fe\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,27 +1,127 @@
 use std::{
+    collections::VecDeque,
     io,
-    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
     sync::{mpsc, Arc},
     thread::JoinHandle,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use log::trace;
 
 use solana_gossip_proto::{
-    protocol::{LegacyContactInfo, Version},
-    utils::parse_addr,
+    net::Entrypoints,
+    protocol::{CrdsFilter, CrdsValue},
+    utils::{bind_gossip_socket, parse_addr},
     wire::Payload,
 };
+use solana_sdk::pubkey::Pubkey;
 
 use crate::app::Context;
+use crate::bad_packets::{BadPacketDump, BAD_PACKETS_DIR};
 use crate::logic::spawn_logic;
-use crate::transport::{receiver::spawn_receiver, sender::spawn_sender, CtrlCmd, Stats};
+use crate::ping::PeerStatus;
+use crate::rate::StatsSnapshot;
+use crate::stakes;
+use crate::transport::{
+    capture::{CaptureReader, CaptureWriter},
+    receiver::spawn_receiver,
+    replay::spawn_replay,
+    sender::spawn_sender,
+    CtrlCmd, Stats,
+};
 
 #[derive(Debug)]
 pub enum Data {
-    LegacyContactInfo(Box<LegacyContactInfo>),
-    Version(Version),
+    /// value received from the peer address
+    CrdsValue(SocketAddr, Box<CrdsValue>),
+    Rates(StatsSnapshot),
+    PeerStatus(Pubkey, PeerStatus),
+    /// the peer asked us to stop pushing our values, with the prune wallclock
+    PrunedBy(Pubkey, u64),
+    /// the entrypoints with the time of their last answer
+    Entrypoints(Vec<(SocketAddr, Option<Instant>)>),
+    /// filter of a pull request received from the peer
+    PullFilter(SocketAddr, Box<CrdsFilter>),
+    /// value received from the peer address failing its signature, with its origin
+    InvalidSignature(SocketAddr, Pubkey),
+}
+
+/// Data waiting for the main loop at most, the logic drops the newest beyond
+/// it rather than letting a stalled or paused UI grow the channel.
+pub const DATA_CHANNEL_CAPACITY: usize = 100_000;
+
+/// Values received while the feed is paused, in arrival order.
+/// When full the oldest values are dropped.
+#[derive(Debug)]
+pub struct PausedQueue {
+    queue: VecDeque<(SocketAddr, CrdsValue)>,
+    capacity: usize,
+    dropped: usize,
+}
+
+/// Values buffered at most while paused.
+pub const PAUSED_QUEUE_CAPACITY: usize = 100_000;
+
+impl Default for PausedQueue {
+    fn default() -> Self {
+        PausedQueue::with_capacity(PAUSED_QUEUE_CAPACITY)
+    }
+}
+
+impl PausedQueue {
+    pub fn with_capacity(capacity: usize) -> Self {
+        PausedQueue {
+            queue: VecDeque::new(),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, from: SocketAddr, value: CrdsValue) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.queue.len() == self.capacity {
+            self.queue.pop_front();
+            self.dropped += 1;
+        }
+        self.queue.push_back((from, value));
+    }
+
+    /// Take the buffered values, resetting the dropped count.
+    pub fn drain(&mut self) -> Vec<(SocketAddr, CrdsValue)> {
+        self.dropped = 0;
+        self.queue.drain(..).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+/// Entrypoints the pull requests rotate across, starting from the selected one.
+/// The other entrypoints given on the command line are only added when the
+/// selected one is among them, the default ones belong to different clusters.
+pub fn bootstrap_entrypoints(selected: SocketAddr, configured: &[SocketAddr]) -> Vec<SocketAddr> {
+    match configured.iter().position(|addr| *addr == selected) {
+        Some(index) => configured[index..]
+            .iter()
+            .chain(&configured[..index])
+            .copied()
+            .collect(),
+        None => vec![selected],
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -35,38 +135,24 @@ pub fn init_threads(
     let entrypoint_str = if let Some(entrypoint) = &ctx.model.entrypoint {
         entrypoint.as_str()
     } else {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "entrypoint address not selected",
-        ));
+        return Err(io::Error::other("entrypoint address not selected"));
     };
 
     let Some(entrypoint_addr) = parse_addr(entrypoint_str) else {
-          return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "invalid entrypoint address",
-          ));
-        };
+        return Err(io::Error::other("invalid entrypoint address"));
+    };
 
     let gossip_local_ip_addr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
     let gossip_local_listener_addr = SocketAddr::new(gossip_local_ip_addr, ctx.model.listern_port);
 
-    let socket = UdpSocket::bind(gossip_local_listener_addr)?;
-    socket.set_read_timeout(Some(Duration::from_millis(1000)))?;
-
-    let socket = Arc::new(socket);
-    trace!("[main] gossip_addr:{gossip_local_listener_addr:?}");
-
     // receiver
-    let (ctrl_sender_tx, ctrl_sender_rx) = mpsc::channel::<CtrlCmd>();
-    ctx.ctrl_txs.push(ctrl_sender_tx);
-    let (sender_tx, sender_rx) = mpsc::channel::<Payload>();
-
-    // sender
     let (ctrl_receiver_tx, ctrl_receiver_rx) = mpsc::channel::<CtrlCmd>();
     ctx.ctrl_txs.push(ctrl_receiver_tx);
     let (receiver_tx, receiver_rx) = mpsc::channel::<Payload>();
 
+    // sender
+    let (sender_tx, sender_rx) = mpsc::channel::<Payload>();
+
     // logic
     let (ctrl_logic_tx, ctrl_logic_rx) = mpsc::channel::<CtrlCmd>();
     ctx.ctrl_txs.push(ctrl_logic_tx);
@@ -74,24 +160,135 @@ pub fn init_threads(
     // stats
     let (stats_tx, stats_rx) = mpsc::channel::<Stats>();
 
-    let (data_tx, data_rx) = mpsc::channel::<Data>();
+    let (data_tx, data_rx) = mpsc::sync_channel::<Data>(DATA_CHANNEL_CAPACITY);
+
+    let mut threads = vec![];
+    if let Some(path) = &ctx.config.replay {
+        // the captured datagrams replace the network, in both directions
+        let reader = CaptureReader::open(path)?;
+        trace!("[main] replaying:{}", path.display());
+        threads.push(spawn_replay(
+            reader,
+            ctx.config.replay_speed,
+            receiver_tx,
+            sender_rx,
+            ctrl_receiver_rx,
+            stats_tx.clone(),
+        )?);
+    } else {
+        // dual stack when available, so that the IPv6 peers are reachable too
+        let socket = bind_gossip_socket(ctx.model.listern_port)?;
+        socket.set_read_timeout(Some(Duration::from_secs(1)))?;
 
-    let receiver_t = spawn_receiver(
-        socket.clone(),
-        receiver_tx,
-        ctrl_receiver_rx,
-        stats_tx.clone(),
-    )?;
-    let sender_t = spawn_sender(socket, sender_rx, ctrl_sender_rx, stats_tx.clone())?;
-    let logic_t = spawn_logic(
+        let socket = Arc::new(socket);
+        trace!(
+            "[main] gossip_addr:{gossip_local_listener_addr:?} bound:{:?}",
+            socket.local_addr()
+        );
+
+        let capture = match &ctx.config.capture {
+            Some(path) => Some(CaptureWriter::create(path)?),
+            None => None,
+        };
+
+        let (ctrl_sender_tx, ctrl_sender_rx) = mpsc::channel::<CtrlCmd>();
+        ctx.ctrl_txs.push(ctrl_sender_tx);
+
+        threads.push(spawn_receiver(
+            socket.clone(),
+            receiver_tx,
+            ctrl_receiver_rx,
+            stats_tx.clone(),
+            capture,
+        )?);
+        threads.push(spawn_sender(
+            socket,
+            sender_rx,
+            ctrl_sender_rx,
+            stats_tx.clone(),
+        )?);
+    }
+    let bad_packets = if ctx.config.dump_bad_packets {
+        Some(BadPacketDump::new(Path::new(BAD_PACKETS_DIR))?)
+    } else {
+        None
+    };
+    let stakes = match &ctx.config.stakes {
+        Some(path) => stakes::load(path)?,
+        None => stakes::Stakes::new(),
+    };
+    threads.push(spawn_logic(
         gossip_local_listener_addr,
-        entrypoint_addr,
+        ctx.config.shred_version,
+        ctx.config.push_fanout,
+        stakes,
+        ctx.config.crds_ttl.clone(),
+        Entrypoints::new(bootstrap_entrypoints(
+            entrypoint_addr,
+            &ctx.config.entrypoints,
+        )),
+        bad_packets,
         sender_tx,
         receiver_rx,
         ctrl_logic_rx,
         stats_tx,
         data_tx,
-    )?;
+    )?);
+
+    Ok((data_rx, stats_rx, threads))
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_gossip_proto::protocol::{CrdsData, NodeInstance};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_entrypoints() {
+        let addrs = ["10.0.0.1:8001", "10.0.0.2:8001", "10.0.0.3:8001"]
+            .map(|addr| parse_addr(addr).unwrap())
+            .to_vec();
+
+        assert_eq!(
+            bootstrap_entrypoints(addrs[1], &addrs),
+            vec![addrs[1], addrs[2], addrs[0]]
+        );
+        assert_eq!(bootstrap_entrypoints(addrs[0], &[]), vec![addrs[0]]);
+        assert_eq!(bootstrap_entrypoints(addrs[2], &addrs[..2]), vec![addrs[2]]);
+    }
+
+    fn create_value(token: u64) -> CrdsValue {
+        let keypair = Keypair::new();
+        let data = CrdsData::NodeInstance(NodeInstance {
+            from: keypair.pubkey(),
+            wallclock: 0,
+            timestamp: 0,
+            token,
+        });
+        CrdsValue::new_signed(data, &keypair)
+    }
+
+    #[test]
+    fn test_paused_queue() {
+        let mut queue = PausedQueue::with_capacity(2);
+        assert!(queue.is_empty());
+
+        let from = parse_addr("10.0.0.1:8001").unwrap();
+        let values = (0..3)
+            .map(|token| (from, create_value(token)))
+            .collect::<Vec<_>>();
+        for (from, value) in &values {
+            queue.push(*from, value.clone());
+        }
 
-    Ok((data_rx, stats_rx, vec![receiver_t, sender_t, logic_t]))
+        // bounded, the oldest value is dropped
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(queue.drain(), values[1..].to_vec());
+        assert!(queue.is_empty());
+        assert_eq!(queue.dropped(), 0);
+    }
 }
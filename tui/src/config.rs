@@ -0,0 +1,845 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    net::{SocketAddr, ToSocketAddrs},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Deserializer};
+use solana_gossip_proto::crds::CrdsTtl;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::logic::CRDS_MAX_AGE;
+
+/// Lower bound of the refresh interval, shorter ones would busy loop.
+pub const MIN_REFRESH_MS: u64 = 10;
+pub const DEFAULT_REFRESH_MS: u64 = 200;
+pub const DEFAULT_FRESH_SECS: u64 = 10;
+pub const DEFAULT_STALE_SECS: u64 = 60;
+pub const DEFAULT_SNAPSHOT: &str = "crds.json";
+pub const DEFAULT_SNAPSHOT_SECS: u64 = 60;
+pub const DEFAULT_CHECKPOINT_DIR: &str = "checkpoints";
+pub const DEFAULT_CHECKPOINT_KEEP: usize = 24;
+/// Peers our contact info is pushed to, besides the entrypoint.
+pub const DEFAULT_PUSH_FANOUT: usize = 6;
+/// Configuration file read when `--config` is not given, it may be missing.
+pub const DEFAULT_CONFIG: &str = "gossip-tui.toml";
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// TOML configuration file, the command line flags override its values
+    #[arg(long, value_name = "FILE", default_value = DEFAULT_CONFIG)]
+    pub config: PathBuf,
+
+    /// Interval, in milliseconds, between two redraws of the UI [default: 200]
+    #[arg(long, value_parser = clap::value_parser!(u64).range(MIN_REFRESH_MS..))]
+    pub refresh_ms: Option<u64>,
+
+    /// Known entrypoints and shred version of the cluster, the other flags override them
+    #[arg(long, value_enum)]
+    pub cluster: Option<Cluster>,
+
+    /// Gossip entrypoint as host:port, can be repeated
+    #[arg(long = "entrypoint", value_name = "HOST:PORT", value_parser = resolve_entrypoint)]
+    pub entrypoints: Vec<SocketAddr>,
+
+    /// Shred version advertised in the contact info, 0 to discover any cluster
+    #[arg(long)]
+    pub shred_version: Option<u16>,
+
+    /// Nodes heard from within this many seconds are shown as fresh [default: 10]
+    #[arg(long)]
+    pub fresh_secs: Option<u64>,
+
+    /// Nodes not heard from for this many seconds are shown as stale [default: 60]
+    #[arg(long)]
+    pub stale_secs: Option<u64>,
+
+    /// Do not color the node table rows
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Select the nodes with the mouse, the text can no longer be selected
+    #[arg(long)]
+    pub mouse: bool,
+
+    /// Country database in the `MaxMind` format, `SOLANA_GOSSIP_TUI_GEOIP_DB` is used when not given
+    #[arg(long, value_name = "FILE")]
+    pub geoip_db: Option<PathBuf>,
+
+    /// Directory the nodes and CRDS exports are written to [default: .]
+    #[arg(long, value_name = "DIR")]
+    pub export_dir: Option<PathBuf>,
+
+    /// Peers our contact info is pushed to besides the entrypoint, 0 for the entrypoint only [default: 6]
+    #[arg(long, value_name = "N")]
+    pub push_fanout: Option<usize>,
+
+    /// Keep the last N values received for each CRDS label, disabled when not given
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub history: Option<u64>,
+
+    /// Pubkey of our own validator, shown in a panel above the nodes
+    #[arg(long, value_name = "PUBKEY", value_parser = parse_pubkey)]
+    pub identity: Option<Pubkey>,
+
+    /// Stake of the nodes, one `PUBKEY STAKE` line each, to push to the staked peers first
+    #[arg(long, value_name = "FILE")]
+    pub stakes: Option<PathBuf>,
+
+    /// Interval, in seconds, between two checkpoints of the CRDS table, disabled when not given
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub checkpoint_interval: Option<u64>,
+
+    /// Directory the checkpoints are written to [default: checkpoints]
+    #[arg(long, value_name = "DIR")]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// Checkpoints kept, the oldest ones are removed [default: 24]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub checkpoint_keep: Option<u64>,
+
+    /// Age, in seconds, after which the values of a CRDS kind are purged, can be repeated
+    #[arg(long = "crds-ttl", value_name = "KIND=SECS", value_parser = parse_crds_ttl)]
+    pub crds_ttl: Vec<(String, u64)>,
+
+    /// Write every received datagram to the file
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub capture: Option<PathBuf>,
+
+    /// Feed the datagrams captured in the file instead of the network ones
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Replay speed factor, 2 replays twice as fast as the capture
+    #[arg(long, default_value_t = 1.0, value_parser = parse_replay_speed)]
+    pub replay_speed: f64,
+
+    /// Write the packets failing to deserialize to the bad-packets directory
+    #[arg(long)]
+    pub dump_bad_packets: bool,
+
+    /// Serve the Prometheus metrics on `http://ADDR/metrics`
+    #[cfg(feature = "metrics")]
+    #[arg(long, value_name = "ADDR")]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Run without the terminal UI, writing JSON snapshots of the CRDS table
+    #[arg(long)]
+    pub headless: bool,
+
+    /// File the headless snapshots are written to
+    #[arg(long, value_name = "FILE", default_value = DEFAULT_SNAPSHOT)]
+    pub snapshot: PathBuf,
+
+    /// Write each new CRDS value to stdout as a JSON line, with --headless
+    #[arg(long, requires = "headless")]
+    pub stream_json: bool,
+
+    /// Interval, in seconds, between two headless snapshots
+    #[arg(long, default_value_t = DEFAULT_SNAPSHOT_SECS, value_parser = clap::value_parser!(u64).range(1..))]
+    pub snapshot_secs: u64,
+}
+
+pub fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
+    pubkey
+        .parse::<Pubkey>()
+        .map_err(|err| format!("invalid pubkey {pubkey}: {err}"))
+}
+
+pub fn parse_crds_ttl(ttl: &str) -> Result<(String, u64), String> {
+    let (kind, secs) = ttl
+        .split_once('=')
+        .ok_or_else(|| format!("invalid CRDS ttl {ttl}, KIND=SECS is expected"))?;
+    match secs.parse::<u64>() {
+        Ok(secs) if secs > 0 => {
+            CrdsTtl::new(CRDS_MAX_AGE).set(kind, Duration::from_secs(secs))?;
+            Ok((kind.to_string(), secs))
+        }
+        _ => Err(format!(
+            "invalid CRDS ttl {ttl}, a positive number of seconds is expected"
+        )),
+    }
+}
+
+pub fn parse_replay_speed(speed: &str) -> Result<f64, String> {
+    match speed.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!(
+            "invalid replay speed {speed}, a positive number is expected"
+        )),
+    }
+}
+
+/// Public Solana clusters.
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    #[value(alias = "mainnet-beta")]
+    #[serde(alias = "mainnet-beta")]
+    Mainnet,
+    Testnet,
+    Devnet,
+}
+
+impl Cluster {
+    pub fn entrypoints(self) -> &'static [&'static str] {
+        match self {
+            Cluster::Mainnet => &[
+                "entrypoint.mainnet-beta.solana.com:8001",
+                "entrypoint2.mainnet-beta.solana.com:8001",
+                "entrypoint3.mainnet-beta.solana.com:8001",
+                "entrypoint4.mainnet-beta.solana.com:8001",
+                "entrypoint5.mainnet-beta.solana.com:8001",
+            ],
+            Cluster::Testnet => &[
+                "entrypoint.testnet.solana.com:8001",
+                "entrypoint2.testnet.solana.com:8001",
+                "entrypoint3.testnet.solana.com:8001",
+            ],
+            Cluster::Devnet => &[
+                "entrypoint.devnet.solana.com:8001",
+                "entrypoint2.devnet.solana.com:8001",
+                "entrypoint3.devnet.solana.com:8001",
+                "entrypoint4.devnet.solana.com:8001",
+                "entrypoint5.devnet.solana.com:8001",
+            ],
+        }
+    }
+
+    /// Expected shred version, `None` for the clusters restarted too often
+    /// to keep one, their nodes are discovered with any shred version.
+    pub fn shred_version(self) -> Option<u16> {
+        match self {
+            Cluster::Mainnet => Some(50_093),
+            Cluster::Testnet | Cluster::Devnet => None,
+        }
+    }
+
+    /// The entrypoints resolving at the moment, the others are skipped.
+    pub fn resolve_entrypoints(self) -> Vec<SocketAddr> {
+        self.entrypoints()
+            .iter()
+            .filter_map(|entrypoint| resolve_entrypoint(entrypoint).ok())
+            .collect()
+    }
+}
+
+/// Resolve the host:port of an entrypoint, IPv4 addresses are preferred.
+pub fn resolve_entrypoint(entrypoint: &str) -> Result<SocketAddr, String> {
+    let addrs = entrypoint
+        .to_socket_addrs()
+        .map_err(|err| format!("cannot resolve entrypoint {entrypoint}: {err}"))?
+        .collect::<Vec<_>>();
+    addrs
+        .iter()
+        .find(|addr| addr.is_ipv4())
+        .or_else(|| addrs.first())
+        .copied()
+        .ok_or_else(|| format!("no address found for entrypoint {entrypoint}"))
+}
+
+/// Settings read from the configuration file, each one is optional.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub cluster: Option<Cluster>,
+    #[serde(deserialize_with = "deserialize_entrypoints")]
+    pub entrypoints: Vec<SocketAddr>,
+    pub shred_version: Option<u16>,
+    pub refresh_ms: Option<u64>,
+    pub fresh_secs: Option<u64>,
+    pub stale_secs: Option<u64>,
+    pub color: Option<bool>,
+    pub mouse: Option<bool>,
+    pub geoip_db: Option<PathBuf>,
+    pub export_dir: Option<PathBuf>,
+    pub history: Option<u64>,
+    pub push_fanout: Option<usize>,
+    pub stakes: Option<PathBuf>,
+    pub checkpoint_interval: Option<u64>,
+    pub checkpoint_dir: Option<PathBuf>,
+    pub checkpoint_keep: Option<u64>,
+    /// seconds by CRDS kind
+    pub crds_ttl: BTreeMap<String, u64>,
+}
+
+fn deserialize_entrypoints<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<SocketAddr>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|entrypoint| resolve_entrypoint(entrypoint).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+impl FileConfig {
+    /// Read the file, a missing one gives the default settings.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => FileConfig::parse(&text).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}: {err}", path.display()),
+                )
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(FileConfig::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        let config: FileConfig = toml::from_str(text)?;
+        let mut ttl = CrdsTtl::new(CRDS_MAX_AGE);
+        for (kind, secs) in &config.crds_ttl {
+            if *secs == 0 {
+                return Err(serde::de::Error::custom(format!(
+                    "crds_ttl of {kind} must be positive"
+                )));
+            }
+            ttl.set(kind, Duration::from_secs(*secs))
+                .map_err(serde::de::Error::custom)?;
+        }
+        match config.refresh_ms {
+            Some(refresh_ms) if refresh_ms < MIN_REFRESH_MS => Err(serde::de::Error::custom(
+                format!("refresh_ms must be at least {MIN_REFRESH_MS}"),
+            )),
+            _ => Ok(config),
+        }
+    }
+}
+
+/// Ages splitting the nodes into fresh, aging and stale ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Freshness {
+    pub fresh: Duration,
+    pub stale: Duration,
+}
+
+impl Default for Freshness {
+    fn default() -> Self {
+        Freshness {
+            fresh: Duration::from_secs(DEFAULT_FRESH_SECS),
+            stale: Duration::from_secs(DEFAULT_STALE_SECS),
+        }
+    }
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    /// timeout of the terminal events poll, the UI is redrawn at least this often
+    pub refresh: Duration,
+    /// preset the entrypoints are resolved from when none is given
+    pub cluster: Option<Cluster>,
+    /// entrypoints given on the command line, replace the default ones
+    pub entrypoints: Vec<SocketAddr>,
+    /// advertised in the contact info of the spy node
+    pub shred_version: u16,
+    pub freshness: Freshness,
+    /// whether the node table rows are colored by freshness
+    pub color: bool,
+    /// whether the mouse events are captured
+    pub mouse: bool,
+    pub geoip_db: Option<PathBuf>,
+    pub export_dir: PathBuf,
+    /// values kept per CRDS label, none keeps only the latest one
+    pub history: Option<usize>,
+    /// peers our contact info is pushed to, besides the entrypoint
+    pub push_fanout: usize,
+    /// node followed by the identity panel
+    pub identity: Option<Pubkey>,
+    /// file the node stakes are read from, the push peers are picked uniformly without it
+    pub stakes: Option<PathBuf>,
+    /// interval between two checkpoints of the CRDS table, none when disabled
+    pub checkpoint_interval: Option<Duration>,
+    pub checkpoint_dir: PathBuf,
+    /// checkpoints kept in the directory
+    pub checkpoint_keep: usize,
+    /// age after which the CRDS values are purged, by kind
+    pub crds_ttl: CrdsTtl,
+    /// file the received datagrams are written to
+    pub capture: Option<PathBuf>,
+    /// file the datagrams are replayed from, in place of the network
+    pub replay: Option<PathBuf>,
+    pub replay_speed: f64,
+    pub dump_bad_packets: bool,
+    #[cfg(feature = "metrics")]
+    pub metrics_addr: Option<SocketAddr>,
+    /// collect the values without the terminal UI
+    pub headless: bool,
+    pub snapshot: PathBuf,
+    pub snapshot_interval: Duration,
+    /// the new values are written to stdout as JSON lines
+    pub stream_json: bool,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            refresh: Duration::from_millis(DEFAULT_REFRESH_MS),
+            cluster: None,
+            entrypoints: vec![],
+            shred_version: 0,
+            freshness: Freshness::default(),
+            color: true,
+            mouse: false,
+            geoip_db: None,
+            export_dir: PathBuf::from("."),
+            history: None,
+            push_fanout: DEFAULT_PUSH_FANOUT,
+            identity: None,
+            stakes: None,
+            checkpoint_interval: None,
+            checkpoint_dir: PathBuf::from(DEFAULT_CHECKPOINT_DIR),
+            checkpoint_keep: DEFAULT_CHECKPOINT_KEEP,
+            crds_ttl: CrdsTtl::new(CRDS_MAX_AGE),
+            capture: None,
+            replay: None,
+            replay_speed: 1.0,
+            dump_bad_packets: false,
+            #[cfg(feature = "metrics")]
+            metrics_addr: None,
+            headless: false,
+            snapshot: PathBuf::from(DEFAULT_SNAPSHOT),
+            snapshot_interval: Duration::from_secs(DEFAULT_SNAPSHOT_SECS),
+            stream_json: false,
+        }
+    }
+}
+
+impl From<Cli> for AppConfig {
+    fn from(cli: Cli) -> Self {
+        AppConfig::new(cli, FileConfig::default())
+    }
+}
+
+/// The default ages overridden by the kinds given, the kinds were checked
+/// while parsing.
+fn crds_ttl(kinds: impl Iterator<Item = (String, u64)>) -> CrdsTtl {
+    let mut ttl = CrdsTtl::new(CRDS_MAX_AGE);
+    for (kind, secs) in kinds {
+        ttl.set(&kind, Duration::from_secs(secs)).unwrap_or(());
+    }
+    ttl
+}
+
+impl AppConfig {
+    /// The command line flags given override the file values.
+    pub fn new(cli: Cli, file: FileConfig) -> Self {
+        let fresh_secs = cli
+            .fresh_secs
+            .or(file.fresh_secs)
+            .unwrap_or(DEFAULT_FRESH_SECS);
+        let stale_secs = cli
+            .stale_secs
+            .or(file.stale_secs)
+            .unwrap_or(DEFAULT_STALE_SECS);
+        let cluster = cli.cluster.or(file.cluster);
+        AppConfig {
+            refresh: Duration::from_millis(
+                cli.refresh_ms
+                    .or(file.refresh_ms)
+                    .unwrap_or(DEFAULT_REFRESH_MS),
+            ),
+            cluster,
+            entrypoints: if cli.entrypoints.is_empty() {
+                file.entrypoints
+            } else {
+                cli.entrypoints
+            },
+            shred_version: cli
+                .shred_version
+                .or(file.shred_version)
+                .or_else(|| cluster.and_then(Cluster::shred_version))
+                .unwrap_or(0),
+            // a stale threshold below the fresh one would leave no aging nodes
+            freshness: Freshness {
+                fresh: Duration::from_secs(fresh_secs),
+                stale: Duration::from_secs(stale_secs.max(fresh_secs)),
+            },
+            color: !cli.no_color && file.color.unwrap_or(true),
+            mouse: cli.mouse || file.mouse.unwrap_or(false),
+            geoip_db: cli.geoip_db.or(file.geoip_db),
+            export_dir: cli
+                .export_dir
+                .or(file.export_dir)
+                .unwrap_or_else(|| PathBuf::from(".")),
+            history: cli
+                .history
+                .or(file.history)
+                .filter(|depth| *depth > 0)
+                .map(|depth| usize::try_from(depth).unwrap_or(usize::MAX)),
+            push_fanout: cli
+                .push_fanout
+                .or(file.push_fanout)
+                .unwrap_or(DEFAULT_PUSH_FANOUT),
+            identity: cli.identity,
+            stakes: cli.stakes.or(file.stakes),
+            checkpoint_interval: cli
+                .checkpoint_interval
+                .or(file.checkpoint_interval)
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            checkpoint_dir: cli
+                .checkpoint_dir
+                .or(file.checkpoint_dir)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CHECKPOINT_DIR)),
+            checkpoint_keep: cli
+                .checkpoint_keep
+                .or(file.checkpoint_keep)
+                .filter(|keep| *keep > 0)
+                .map_or(DEFAULT_CHECKPOINT_KEEP, |keep| {
+                    usize::try_from(keep).unwrap_or(usize::MAX)
+                }),
+            crds_ttl: crds_ttl(file.crds_ttl.into_iter().chain(cli.crds_ttl)),
+            capture: cli.capture,
+            replay: cli.replay,
+            replay_speed: cli.replay_speed,
+            dump_bad_packets: cli.dump_bad_packets,
+            #[cfg(feature = "metrics")]
+            metrics_addr: cli.metrics_addr,
+            headless: cli.headless,
+            snapshot: cli.snapshot,
+            snapshot_interval: Duration::from_secs(cli.snapshot_secs),
+            stream_json: cli.stream_json,
+        }
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_gossip_proto::crds::CRDS_VOTE_TTL;
+
+    #[test]
+    fn test_cli_refresh_ms() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert_eq!(config, AppConfig::default());
+
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--refresh-ms", "50"]).unwrap());
+        assert_eq!(config.refresh, Duration::from_millis(50));
+
+        assert!(Cli::try_parse_from(["app", "--refresh-ms", "1"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--refresh-ms", "fast"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_entrypoints() {
+        let cli = Cli::try_parse_from([
+            "app",
+            "--entrypoint",
+            "141.98.219.218:8000",
+            "--entrypoint",
+            "[::1]:8001",
+        ])
+        .unwrap();
+        let config = AppConfig::from(cli);
+        assert_eq!(
+            config.entrypoints,
+            vec![
+                "141.98.219.218:8000".parse().unwrap(),
+                "[::1]:8001".parse().unwrap()
+            ]
+        );
+
+        assert!(Cli::try_parse_from(["app", "--entrypoint", "141.98.219.218"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--entrypoint", "host.invalid:8001"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_freshness() {
+        let config = AppConfig::from(
+            Cli::try_parse_from([
+                "app",
+                "--fresh-secs",
+                "5",
+                "--stale-secs",
+                "30",
+                "--no-color",
+            ])
+            .unwrap(),
+        );
+        assert_eq!(
+            config.freshness,
+            Freshness {
+                fresh: Duration::from_secs(5),
+                stale: Duration::from_secs(30),
+            }
+        );
+        assert!(!config.color);
+
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--fresh-secs", "120"]).unwrap());
+        assert_eq!(config.freshness.stale, Duration::from_mins(2));
+        assert!(config.color);
+    }
+
+    #[test]
+    fn test_cli_mouse() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert!(!config.mouse);
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--mouse"]).unwrap());
+        assert!(config.mouse);
+
+        let file = FileConfig::parse("mouse = true").unwrap();
+        assert!(AppConfig::new(Cli::try_parse_from(["app"]).unwrap(), file).mouse);
+    }
+
+    #[test]
+    fn test_cli_capture_replay() {
+        let config = AppConfig::from(
+            Cli::try_parse_from(["app", "--replay", "gossip.dump", "--replay-speed", "4"]).unwrap(),
+        );
+        assert_eq!(config.replay, Some(PathBuf::from("gossip.dump")));
+        assert!((config.replay_speed - 4.0).abs() < f64::EPSILON);
+        assert_eq!(config.capture, None);
+
+        let config =
+            AppConfig::from(Cli::try_parse_from(["app", "--capture", "gossip.dump"]).unwrap());
+        assert_eq!(config.capture, Some(PathBuf::from("gossip.dump")));
+
+        assert!(Cli::try_parse_from(["app", "--capture", "a", "--replay", "b"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--replay-speed", "0"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--replay-speed", "fast"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_dump_bad_packets() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert!(!config.dump_bad_packets);
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--dump-bad-packets"]).unwrap());
+        assert!(config.dump_bad_packets);
+    }
+
+    #[test]
+    fn test_cli_headless() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert!(!config.headless);
+
+        let config = AppConfig::from(
+            Cli::try_parse_from([
+                "app",
+                "--headless",
+                "--snapshot",
+                "/var/lib/gossip/crds.json",
+                "--snapshot-secs",
+                "10",
+            ])
+            .unwrap(),
+        );
+        assert!(config.headless);
+        assert_eq!(config.snapshot, PathBuf::from("/var/lib/gossip/crds.json"));
+        assert_eq!(config.snapshot_interval, Duration::from_secs(10));
+
+        assert!(Cli::try_parse_from(["app", "--snapshot-secs", "0"]).is_err());
+
+        // the stream would garble the terminal UI
+        assert!(Cli::try_parse_from(["app", "--stream-json"]).is_err());
+        let cli = Cli::try_parse_from(["app", "--headless", "--stream-json"]).unwrap();
+        assert!(AppConfig::from(cli).stream_json);
+    }
+
+    #[test]
+    fn test_cli_identity() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert_eq!(config.identity, None);
+
+        let identity = Pubkey::new_unique();
+        let config = AppConfig::from(
+            Cli::try_parse_from(["app", "--identity", &identity.to_string()]).unwrap(),
+        );
+        assert_eq!(config.identity, Some(identity));
+        assert!(Cli::try_parse_from(["app", "--identity", "not-a-pubkey"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_checkpoint() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert_eq!(config.checkpoint_interval, None);
+
+        let config = AppConfig::from(
+            Cli::try_parse_from([
+                "app",
+                "--checkpoint-interval",
+                "600",
+                "--checkpoint-dir",
+                "/var/lib/gossip",
+                "--checkpoint-keep",
+                "6",
+            ])
+            .unwrap(),
+        );
+        assert_eq!(config.checkpoint_interval, Some(Duration::from_mins(10)));
+        assert_eq!(config.checkpoint_dir, PathBuf::from("/var/lib/gossip"));
+        assert_eq!(config.checkpoint_keep, 6);
+
+        assert!(Cli::try_parse_from(["app", "--checkpoint-interval", "0"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--checkpoint-keep", "0"]).is_err());
+
+        let file = FileConfig::parse("checkpoint_interval = 60\ncheckpoint_keep = 0").unwrap();
+        let config = AppConfig::new(Cli::try_parse_from(["app"]).unwrap(), file);
+        assert_eq!(config.checkpoint_interval, Some(Duration::from_mins(1)));
+        assert_eq!(config.checkpoint_keep, DEFAULT_CHECKPOINT_KEEP);
+    }
+
+    #[test]
+    fn test_cli_crds_ttl() {
+        let config = AppConfig::from(Cli::try_parse_from(["app"]).unwrap());
+        assert_eq!(config.crds_ttl.ttl_for_kind("Vote"), CRDS_VOTE_TTL);
+        assert_eq!(config.crds_ttl.ttl_for_kind("ContactInfo"), CRDS_MAX_AGE);
+
+        let cli = Cli::try_parse_from([
+            "app",
+            "--crds-ttl",
+            "Vote=30",
+            "--crds-ttl",
+            "EpochSlots=600",
+        ])
+        .unwrap();
+        let file = FileConfig::parse("[crds_ttl]\nVote = 60\nContactInfo = 1800").unwrap();
+        let config = AppConfig::new(cli, file);
+        assert_eq!(
+            config.crds_ttl.ttl_for_kind("Vote"),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            config.crds_ttl.ttl_for_kind("EpochSlots"),
+            Duration::from_mins(10)
+        );
+        assert_eq!(
+            config.crds_ttl.ttl_for_kind("ContactInfo"),
+            Duration::from_mins(30)
+        );
+
+        for ttl in ["Vote", "Votes=30", "Vote=0", "Vote=soon"] {
+            assert!(Cli::try_parse_from(["app", "--crds-ttl", ttl]).is_err());
+        }
+        assert!(FileConfig::parse("[crds_ttl]\nVotes = 60").is_err());
+        assert!(FileConfig::parse("[crds_ttl]\nVote = 0").is_err());
+    }
+
+    #[test]
+    fn test_file_config() {
+        let file = FileConfig::parse(
+            r#"
+            entrypoints = ["141.98.219.218:8001", "[::1]:8001"]
+            shred_version = 50093
+            refresh_ms = 500
+            fresh_secs = 5
+            stale_secs = 30
+            color = false
+            geoip_db = "/usr/share/GeoIP/GeoLite2-Country.mmdb"
+            export_dir = "exports"
+            history = 16
+            push_fanout = 2
+            stakes = "stakes.txt"
+            "#,
+        )
+        .unwrap();
+        let config = AppConfig::new(Cli::try_parse_from(["app"]).unwrap(), file.clone());
+        assert_eq!(
+            config.entrypoints,
+            vec![
+                "141.98.219.218:8001".parse().unwrap(),
+                "[::1]:8001".parse().unwrap()
+            ]
+        );
+        assert_eq!(config.shred_version, 50_093);
+        assert_eq!(config.refresh, Duration::from_millis(500));
+        assert_eq!(config.freshness.stale, Duration::from_secs(30));
+        assert!(!config.color);
+        assert_eq!(
+            config.geoip_db,
+            Some(PathBuf::from("/usr/share/GeoIP/GeoLite2-Country.mmdb"))
+        );
+        assert_eq!(config.export_dir, PathBuf::from("exports"));
+        assert_eq!(config.history, Some(16));
+        assert_eq!(config.push_fanout, 2);
+        assert_eq!(config.stakes, Some(PathBuf::from("stakes.txt")));
+
+        // the command line wins
+        let cli = Cli::try_parse_from([
+            "app",
+            "--entrypoint",
+            "127.0.0.1:8001",
+            "--shred-version",
+            "4",
+            "--refresh-ms",
+            "50",
+        ])
+        .unwrap();
+        let config = AppConfig::new(cli, file);
+        assert_eq!(config.entrypoints, vec!["127.0.0.1:8001".parse().unwrap()]);
+        assert_eq!(config.shred_version, 4);
+        assert_eq!(config.refresh, Duration::from_millis(50));
+        assert_eq!(config.freshness.fresh, Duration::from_secs(5));
+
+        assert_eq!(FileConfig::parse("").unwrap(), FileConfig::default());
+        assert!(FileConfig::parse("refresh_ms = 1").is_err());
+        assert!(FileConfig::parse("refresh = 100").is_err());
+        assert!(FileConfig::parse("entrypoints = [\"host.invalid:8001\"]").is_err());
+    }
+
+    #[test]
+    fn test_cli_cluster() {
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--cluster", "mainnet"]).unwrap());
+        assert_eq!(config.cluster, Some(Cluster::Mainnet));
+        assert_eq!(config.shred_version, 50_093);
+        assert!(config.entrypoints.is_empty());
+
+        let config = AppConfig::from(
+            Cli::try_parse_from(["app", "--cluster", "mainnet-beta", "--shred-version", "7"])
+                .unwrap(),
+        );
+        assert_eq!(config.cluster, Some(Cluster::Mainnet));
+        assert_eq!(config.shred_version, 7);
+
+        let config = AppConfig::from(Cli::try_parse_from(["app", "--cluster", "devnet"]).unwrap());
+        assert_eq!(config.shred_version, 0);
+        assert!(Cli::try_parse_from(["app", "--cluster", "localnet"]).is_err());
+
+        let file = FileConfig::parse("cluster = \"testnet\"").unwrap();
+        assert_eq!(file.cluster, Some(Cluster::Testnet));
+        let config = AppConfig::new(
+            Cli::try_parse_from(["app", "--cluster", "devnet"]).unwrap(),
+            file,
+        );
+        assert_eq!(config.cluster, Some(Cluster::Devnet));
+
+        for cluster in [Cluster::Mainnet, Cluster::Testnet, Cluster::Devnet] {
+            assert!(cluster
+                .entrypoints()
+                .iter()
+                .all(|entrypoint| entrypoint.ends_with(".solana.com:8001")));
+        }
+    }
+
+    #[test]
+    fn test_file_config_load() {
+        let missing = std::env::temp_dir().join("missing-gossip-tui.toml");
+        assert_eq!(FileConfig::load(&missing).unwrap(), FileConfig::default());
+
+        let path = std::env::temp_dir().join(format!("gossip-tui-{}.toml", std::process::id()));
+        fs::write(&path, "shred_version = 2\n").unwrap();
+        assert_eq!(FileConfig::load(&path).unwrap().shred_version, Some(2));
+        fs::write(&path, "shred_version = \"two\"\n").unwrap();
+        let err = FileConfig::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_cli_metrics_addr() {
+        let config = AppConfig::from(
+            Cli::try_parse_from(["app", "--metrics-addr", "127.0.0.1:9100"]).unwrap(),
+        );
+        assert_eq!(config.metrics_addr, Some("127.0.0.1:9100".parse().unwrap()));
+        assert!(Cli::try_parse_from(["app", "--metrics-addr", "9100"]).is_err());
+    }
+}
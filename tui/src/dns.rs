@@ -0,0 +1,41 @@
+use std::{net::IpAddr, sync::mpsc, thread::Builder, time::Duration};
+
+use dns_lookup::lookup_addr;
+
+/// Upper bound on a single reverse lookup, the resolver is left to finish on its own.
+pub const PTR_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Reverse lookup of the hostname the address points to, `None` when there is
+/// no PTR record or the resolver does not answer within `PTR_TIMEOUT`.
+pub fn resolve_ptr(ip: IpAddr) -> Option<String> {
+    let (tx, rx) = mpsc::channel();
+    Builder::new()
+        .name(String::from("dns_ptr_t"))
+        .spawn(move || {
+            let _ = tx.send(lookup_addr(&ip).ok());
+        })
+        .ok()?;
+
+    rx.recv_timeout(PTR_TIMEOUT)
+        .ok()
+        .flatten()
+        .filter(|hostname| *hostname != ip.to_string())
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, time::Instant};
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_ptr_timeout() {
+        // TEST-NET-1 has no PTR records, the answer depends on the resolver
+        // but must never take much longer than the timeout
+        let start = Instant::now();
+        let hostname = resolve_ptr(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+        assert!(start.elapsed() < PTR_TIMEOUT + Duration::from_millis(500));
+        assert_ne!(hostname.as_deref(), Some("192.0.2.1"));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use solana_gossip_proto::{crds::CrdsTable, protocol::is_valid_addr};
+
+use crate::ui::node_table_widget::NodeRow;
+
+const NODES_CSV_HEADER: &str =
+    "pubkey,gossip_ip,gossip_port,tpu,rpc,shred_version,version,last_seen_ms";
+
+/// Unset addresses are left empty.
+fn addr_cell(addr: &SocketAddr) -> String {
+    if is_valid_addr(addr) {
+        addr.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Write one line per node, preceded by the header row.
+pub fn export_nodes_csv(nodes: &[NodeRow], path: &Path) -> io::Result<()> {
+    let mut csv = String::from(NODES_CSV_HEADER);
+    csv.push('\n');
+
+    for node in nodes {
+        let info = &node.info;
+        let (gossip_ip, gossip_port) = if is_valid_addr(&info.gossip) {
+            (info.gossip.ip().to_string(), info.gossip.port().to_string())
+        } else {
+            (String::new(), String::new())
+        };
+        let version = node
+            .version
+            .as_ref()
+            .map(|version| version.version.to_semver_string())
+            .unwrap_or_default();
+
+        let line = [
+            info.id.to_string(),
+            gossip_ip,
+            gossip_port,
+            addr_cell(&info.tpu),
+            addr_cell(&info.rpc),
+            info.shred_version.to_string(),
+            version,
+            info.wallclock.to_string(),
+        ]
+        .join(",");
+        csv.push_str(&line);
+        csv.push('\n');
+    }
+
+    fs::write(path, csv)
+}
+
+/// Export the table next to the snapshot and rename it over, a reader never
+/// sees a partially written snapshot.
+pub fn write_crds_snapshot(crds: &CrdsTable, path: &Path) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    crds.export_json(&tmp)?;
+    fs::rename(&tmp, path)
+}
+
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+
+/// Write the table to a new `checkpoint-<millis>.json` of the directory, created
+/// when missing, then prune the oldest checkpoints beyond `keep`.
+pub fn write_crds_checkpoint(
+    crds: &CrdsTable,
+    dir: &Path,
+    now_ms: u64,
+    keep: usize,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{CHECKPOINT_PREFIX}{now_ms}.json"));
+    write_crds_snapshot(crds, &path)?;
+    prune_checkpoints(dir, keep)?;
+    Ok(path)
+}
+
+/// Remove the oldest checkpoints of the directory keeping the last `keep`, the
+/// other files are left alone. Returns how many were removed.
+pub fn prune_checkpoints(dir: &Path, keep: usize) -> io::Result<usize> {
+    let mut checkpoints = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let millis = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(CHECKPOINT_PREFIX)?
+                .strip_suffix(".json")?
+                .parse::<u64>()
+                .ok()?;
+            Some((millis, path))
+        })
+        .collect::<Vec<_>>();
+    checkpoints.sort_unstable();
+    let excess = checkpoints.len().saturating_sub(keep);
+    for (_, path) in &checkpoints[..excess] {
+        fs::remove_file(path)?;
+    }
+    Ok(excess)
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use solana_gossip_proto::protocol::{
+        CrdsData, CrdsValue, LegacyContactInfo, LegacyVersion2, Version,
+    };
+    use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+
+    use super::*;
+
+    #[test]
+    fn test_export_nodes_csv() {
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let id = Pubkey::new_unique();
+        let info = LegacyContactInfo {
+            id,
+            gossip: SocketAddr::new(ip, 8001),
+            rpc: SocketAddr::new(ip, 8899),
+            wallclock: 1_681_834_717_999,
+            shred_version: 50_093,
+            ..LegacyContactInfo::default()
+        };
+        let version = Version {
+            from: id,
+            wallclock: 0,
+            version: LegacyVersion2 {
+                major: 1,
+                minor: 18,
+                patch: 23,
+                commit: None,
+                feature_set: 0,
+            },
+        };
+        let nodes = vec![
+            NodeRow::new(info, Some(&version)),
+            NodeRow::new(LegacyContactInfo::default(), None),
+        ];
+
+        let path = std::env::temp_dir().join(format!("nodes-{id}.csv"));
+        export_nodes_csv(&nodes, &path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], NODES_CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!("{id},141.98.219.218,8001,,141.98.219.218:8899,50093,1.18.23,1681834717999")
+        );
+        assert_eq!(lines[2], format!("{},,,,,0,,0", Pubkey::default()));
+    }
+
+    #[test]
+    fn test_write_crds_snapshot() {
+        let mut crds = CrdsTable::default();
+        let id = Pubkey::new_unique();
+        let info = LegacyContactInfo {
+            id,
+            ..LegacyContactInfo::default()
+        };
+        crds.insert(CrdsValue::new_signed(
+            CrdsData::LegacyContactInfo(Box::new(info)),
+            &Keypair::new(),
+        ))
+        .unwrap();
+
+        let path = std::env::temp_dir().join(format!("crds-{id}.json"));
+        write_crds_snapshot(&CrdsTable::default(), &path).unwrap();
+        write_crds_snapshot(&crds, &path).unwrap();
+        let json = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(json.contains(&id.to_string()));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_write_crds_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("checkpoints-{}", Pubkey::new_unique()));
+        let crds = CrdsTable::default();
+
+        let first = write_crds_checkpoint(&crds, &dir, 1_000, 2).unwrap();
+        assert_eq!(first, dir.join("checkpoint-1000.json"));
+        fs::write(dir.join("notes.txt"), "kept").unwrap();
+        // the timestamps are compared as numbers, not as names
+        write_crds_checkpoint(&crds, &dir, 999, 2).unwrap();
+        write_crds_checkpoint(&crds, &dir, 10_000, 2).unwrap();
+
+        let mut names = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            names,
+            vec!["checkpoint-1000.json", "checkpoint-10000.json", "notes.txt"]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::{net::IpAddr, path::Path};
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+
+/// Environment variable with the path of a `MaxMind` `GeoLite2` Country or City database.
+pub const GEOIP_DB_ENV: &str = "SOLANA_GOSSIP_TUI_GEOIP_DB";
+
+pub struct GeoIp {
+    reader: Reader<Vec<u8>>,
+}
+
+impl GeoIp {
+    pub fn open(path: &Path) -> Result<Self, MaxMindDBError> {
+        let reader = Reader::open_readfile(path)?;
+        Ok(GeoIp { reader })
+    }
+
+    /// English name of the country the address is located in, falls back to the ISO code.
+    pub fn lookup_country(&self, ip: IpAddr) -> Option<String> {
+        let record: geoip2::Country = self.reader.lookup(ip).ok()?;
+        let country = record.country?;
+        country
+            .names
+            .and_then(|names| names.get("en").map(|name| (*name).to_string()))
+            .or_else(|| country.iso_code.map(String::from))
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geo_ip_open_invalid() {
+        assert!(GeoIp::open(Path::new("/nonexistent/GeoLite2-Country.mmdb")).is_err());
+
+        let path = std::env::temp_dir().join("invalid-GeoLite2-Country.mmdb");
+        std::fs::write(&path, b"not a maxmind database").unwrap();
+        assert!(GeoIp::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
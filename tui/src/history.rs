@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+/// Samples, one per second, kept by the node count history.
+pub const NODE_COUNT_SAMPLES: usize = 120;
+
+/// Ring buffer of the last discovered node counts.
+#[derive(Debug)]
+pub struct NodeCountHistory {
+    samples: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl Default for NodeCountHistory {
+    fn default() -> Self {
+        NodeCountHistory::with_capacity(NODE_COUNT_SAMPLES)
+    }
+}
+
+impl NodeCountHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        NodeCountHistory {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append the sample, dropping the oldest one when full.
+    pub fn push(&mut self, count: usize) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(count as u64);
+    }
+
+    /// Samples from the oldest to the newest.
+    pub fn samples(&self) -> Vec<u64> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_count_history() {
+        let mut history = NodeCountHistory::with_capacity(3);
+        assert!(history.samples().is_empty());
+
+        history.push(1);
+        history.push(5);
+        assert_eq!(history.samples(), vec![1, 5]);
+
+        // wraps around keeping the newest samples
+        history.push(8);
+        history.push(13);
+        history.push(21);
+        assert_eq!(history.samples(), vec![8, 13, 21]);
+
+        let mut history = NodeCountHistory::with_capacity(0);
+        history.push(1);
+        assert!(history.samples().is_empty());
+    }
+}
@@ -1,10 +1,24 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::must_use_candidate)]
+#![cfg_attr(test, allow(clippy::unnecessary_wraps))]
 pub mod app;
+pub mod bad_packets;
 pub mod common;
+pub mod config;
+pub mod dns;
+pub mod export;
+pub mod geo;
+pub mod history;
 pub mod logic;
+pub mod lookup;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod ping;
+pub mod rate;
+pub mod stakes;
 pub mod stm;
+pub mod stream;
 pub mod terminal;
 pub mod transport;
 pub mod ui;
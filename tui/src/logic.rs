@@ -1,52 +1,226 @@
 use std::{
+    collections::{HashMap, HashSet},
     io,
     net::SocketAddr,
     sync::{
-        mpsc::{Receiver, Sender},
+        mpsc::{Receiver, Sender, SyncSender},
         Arc,
     },
     thread::{Builder, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use log::trace;
+use rand::{thread_rng, Rng};
 use solana_gossip_proto::{
-    protocol::{CrdsData, LegacyContactInfo, Ping, Protocol},
-    utils::{create_pong_response, create_pull_request, since_the_epoch_millis},
+    crds::{CrdsTable, CrdsTtl},
+    handler::{dispatch, ProtocolHandler},
+    net::Entrypoints,
+    protocol::{
+        is_valid_addr, CrdsData, CrdsFilter, CrdsValue, LegacyContactInfo, Ping, Pong, Protocol,
+        PruneData, SOCKET_TAG_GOSSIP,
+    },
+    utils::{
+        canonical_addr, create_payload, create_ping, create_pong_response, create_pull_request,
+        create_push_message, since_the_epoch_millis, weighted_shuffle,
+    },
     wire::Payload,
 };
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 
 use crate::{
+    bad_packets::BadPacketDump,
     common::Data,
+    ping::{PeerStatus, PingTracker},
+    rate,
+    stakes::Stakes,
     transport::{CtrlCmd, Stats, StatsId},
 };
 
 pub const RECV_TIMEOUT: Duration = Duration::from_millis(30);
+/// Values not refreshed for this long are dropped from the CRDS table.
+pub const CRDS_MAX_AGE: Duration = Duration::from_mins(15);
+/// Upper bound on the values sent back to a single pull request.
+pub const PULL_RESPONSE_MAX_VALUES: usize = 64;
+/// Interval at which our contact info is refreshed and pushed to the entrypoint.
+pub const CONTACT_INFO_REFRESH: Duration = Duration::from_secs(5);
+
+/// Reacts to the messages received from a peer.
+struct LogicHandler<'a> {
+    from_addr: SocketAddr,
+    len: usize,
+    counter: u32,
+    keypair: &'a Keypair,
+    tx: &'a Sender<Payload>,
+    data_tx: &'a SyncSender<Data>,
+    crds: &'a mut CrdsTable,
+    pings: &'a mut PingTracker,
+    entrypoint_addr: SocketAddr,
+    /// learned from the pull responses sent by the first entrypoint
+    entrypoint_id: &'a mut Option<Pubkey>,
+    /// peers which asked us to stop pushing our values
+    pruned_by: &'a mut HashSet<Pubkey>,
+}
+
+impl LogicHandler<'_> {
+    /// Keep the pulled or pushed values and pass them to the main loop, pinging
+    /// the nodes they advertise.
+    fn integrate_values(&mut self, crds_values: Vec<CrdsValue>) {
+        let from_addr = self.from_addr;
+        for value in crds_values {
+            trace!("# {value:?}");
+            // forged values are neither kept nor served to the peers
+            if !value.verify() {
+                if let Some(origin) = value.data.pubkey() {
+                    self.data_tx
+                        .try_send(Data::InvalidSignature(from_addr, origin))
+                        .unwrap_or(());
+                }
+                continue;
+            }
+            self.crds.insert(value.clone()).unwrap_or(());
+            if let CrdsData::LegacyContactInfo(info) = &value.data {
+                send_ping(
+                    self.pings,
+                    info.id,
+                    info.gossip,
+                    self.keypair,
+                    self.tx,
+                    self.counter,
+                );
+            }
+
+            self.data_tx
+                .try_send(Data::CrdsValue(from_addr, Box::new(value)))
+                .unwrap_or(());
+        }
+    }
+}
+
+impl ProtocolHandler for LogicHandler<'_> {
+    fn on_pull_request(&mut self, filter: CrdsFilter, caller: CrdsValue) {
+        self.data_tx
+            .try_send(Data::PullFilter(self.from_addr, Box::new(filter.clone())))
+            .unwrap_or(());
+        // answering a spoofed source would reflect the responses to a victim, the
+        // caller must be signed, gossip from the source and have answered our ping
+        let (id, gossip) = match &caller.data {
+            CrdsData::LegacyContactInfo(info) => (info.id, info.gossip),
+            CrdsData::ContactInfo(info) => match info.socket(SOCKET_TAG_GOSSIP) {
+                Some(gossip) => (info.pubkey, gossip),
+                None => return,
+            },
+            _ => return,
+        };
+        if !caller.verify() || canonical_addr(gossip) != self.from_addr {
+            trace!(
+                "# ??? PullRequest from_addr:{:?} fails verification",
+                self.from_addr
+            );
+            return;
+        }
+        if !self.pings.is_verified(&id, self.from_addr) {
+            send_ping(self.pings, id, gossip, self.keypair, self.tx, self.counter);
+            return;
+        }
+        send_pull_responses(
+            self.crds,
+            &filter,
+            self.from_addr,
+            self.keypair,
+            self.tx,
+            self.counter,
+        );
+    }
+
+    fn on_pull_response(&mut self, from_key: Pubkey, crds_values: Vec<CrdsValue>) {
+        let (len, from_addr) = (self.len, self.from_addr);
+        trace!("# len:{len} PullResponse from_addr:{from_addr:?} from_key:{from_key:?}");
+        if from_addr == self.entrypoint_addr {
+            *self.entrypoint_id = Some(from_key);
+        }
+        self.integrate_values(crds_values);
+    }
+
+    fn on_push_message(&mut self, from_key: Pubkey, crds_values: Vec<CrdsValue>) {
+        let (len, from_addr) = (self.len, self.from_addr);
+        trace!("# len:{len} PushMessage from_addr:{from_addr:?} from_key:{from_key:?}");
+        self.integrate_values(crds_values);
+    }
+
+    fn on_prune(&mut self, from_key: Pubkey, data: PruneData) {
+        trace!("# PruneMessage from_key:{from_key:?} data:{data:?}");
+        // spoofed prunes would partition our view of the cluster
+        if data.pubkey != from_key || !data.verify() {
+            trace!("# ??? PruneMessage from_key:{from_key:?} fails verification");
+            return;
+        }
+        let me = self.keypair.pubkey();
+        if data.prunes_origin(&me, &me) && self.pruned_by.insert(from_key) {
+            self.data_tx
+                .try_send(Data::PrunedBy(from_key, data.wallclock))
+                .unwrap_or(());
+        }
+    }
 
+    fn on_ping(&mut self, ping: Ping) {
+        send_pong_response(&ping, self.from_addr, self.keypair, self.tx, self.counter);
+    }
+
+    fn on_pong(&mut self, pong: Pong) {
+        let (len, from_addr) = (self.len, self.from_addr);
+        trace!("# len:{len} PongMessage from_addr:{from_addr:?} pong:{pong:?}");
+        if let Some((peer, rtt)) = self.pings.record_pong(&pong, Instant::now()) {
+            self.data_tx
+                .try_send(Data::PeerStatus(peer, PeerStatus::Alive(rtt)))
+                .unwrap_or(());
+        }
+    }
+}
+
+/// The pull requests are sent to the entrypoints in turn, the push messages
+/// to the first one and to `push_fanout` of the discovered peers, picked by
+/// `stakes` when known. The values served are purged by `crds_ttl`.
+/// The packets failing to deserialize are written to `bad_packets`, when set.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn_logic(
     gossip_local_listener_addr: SocketAddr,
-    entrypoint_addr: SocketAddr,
+    shred_version: u16,
+    push_fanout: usize,
+    stakes: Stakes,
+    crds_ttl: CrdsTtl,
+    mut entrypoints: Entrypoints,
+    mut bad_packets: Option<BadPacketDump>,
     tx: Sender<Payload>,
     rx: Receiver<Payload>,
     ctrl_rx: Receiver<CtrlCmd>,
     stats_tx: Sender<Stats>,
-    data_tx: Sender<Data>,
+    data_tx: SyncSender<Data>,
 ) -> io::Result<JoinHandle<()>> {
+    let Some(&entrypoint_addr) = entrypoints.addrs().first() else {
+        return Err(io::Error::other("no entrypoint address"));
+    };
+
     Builder::new().name("logic_t".to_string()).spawn(move || {
         let mut counter: u32 = 0;
 
-        let keypair = Keypair::new();
-        let keypair_arc = Arc::new(keypair);
-        let shred_version: u16 = 0;
+        let keypair_arc = Arc::new(Keypair::new());
 
-        let contact_info = LegacyContactInfo {
-            id: keypair_arc.pubkey(),
-            gossip: gossip_local_listener_addr,
-            wallclock: since_the_epoch_millis(),
+        let mut contact_info = LegacyContactInfo::new_spy(
+            keypair_arc.as_ref(),
+            gossip_local_listener_addr,
             shred_version,
-            ..LegacyContactInfo::default()
-        };
+        );
+
+        let mut refreshed_at = Instant::now();
+        let mut rates = rate::Stats::new(Instant::now());
+        // values served to the peers pull requests
+        let mut crds = CrdsTable::default();
+        // the discovered peers are pinged once to measure their round-trip time
+        let mut pings = PingTracker::default();
+        let mut entrypoint_id = None;
+        let mut pruned_by = HashSet::new();
+        let mut push_targets = PushTargets::new(push_fanout, stakes, thread_rng());
 
         'main_l: loop {
             if let Ok(ctrl_msg) = ctrl_rx.try_recv() {
@@ -70,49 +244,30 @@ pub(crate) fn spawn_logic(
                     trace!(
                       "######## c:{counter} #### addr:{from_addr:?} #### len:{len} ################ 1"
                     );
-                    let r = payload.deserialize_slice(..);
-                    match r {
-                        Ok(proto) => match proto {
-                            Protocol::PingMessage(ping) => send_pong_response(
-                                &ping,
+                    match payload.deserialize_slice(..) {
+                        Ok(proto) => {
+                            rates.record(&proto, Instant::now());
+                            entrypoints.record_response(from_addr, Instant::now());
+                            let mut handler = LogicHandler {
                                 from_addr,
-                                keypair_arc.as_ref(),
-                                &tx,
+                                len,
                                 counter,
-                            ),
-                            Protocol::PongMessage(pong) => {
-                                trace!(
-                                    "# len:{len} PongMessage from_addr:{from_addr:?} pong:{pong:?}",
-                                );
-                            }
-                            Protocol::PullResponse(from_key, crds_values) => {
-                                trace!(
-                                  "# len:{len} PullResponse from_addr:{from_addr:?} from_key:{from_key:?}"
-                                );
-                                for value in &crds_values {
-                                    trace!("# {value:?}");
-
-                                    match &value.data {
-                                        CrdsData::LegacyContactInfo(info) => {
-                                            data_tx
-                                                .send(Data::LegacyContactInfo(info.clone()))
-                                                .unwrap_or(());
-                                        }
-                                        CrdsData::Version(version) => {
-                                            data_tx
-                                                .send(Data::Version(version.clone()))
-                                                .unwrap_or(());
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            _ => {
-                                trace!("# ??? err protocol:{proto:?}");
-                            }
-                        },
+                                keypair: keypair_arc.as_ref(),
+                                tx: &tx,
+                                data_tx: &data_tx,
+                                crds: &mut crds,
+                                pings: &mut pings,
+                                entrypoint_addr,
+                                entrypoint_id: &mut entrypoint_id,
+                                pruned_by: &mut pruned_by,
+                            };
+                            dispatch(proto, &mut handler);
+                        }
                         Err(err) => {
                             trace!("# ??? err:{err:?}");
+                            if let Some(dump) = &mut bad_packets {
+                                dump_bad_packet(dump, from_addr, &payload, &err);
+                            }
                         }
                     }
                     trace!("#---------------------------------------- 2");
@@ -121,19 +276,148 @@ pub(crate) fn spawn_logic(
                 }
             }
 
-            send_pull_request(
-                contact_info.clone(),
-                keypair_arc.as_ref(),
-                entrypoint_addr,
-                &tx,
-                counter,
-            );
+            if rates.tick(Instant::now()) {
+                crds.purge_stale(&crds_ttl, since_the_epoch_millis());
+                on_tick(&rates, &mut pings, &entrypoints, &data_tx);
+            }
+
+            // a newer wallclock keeps our contact info in the peers tables
+            if refreshed_at.elapsed() >= CONTACT_INFO_REFRESH {
+                refreshed_at = Instant::now();
+                contact_info = contact_info.refreshed(since_the_epoch_millis());
+                let entrypoint = (entrypoint_addr, entrypoint_id);
+                let peers = push_targets.peers(&crds, &pings, &pruned_by, &contact_info, entrypoint);
+                for addr in peers {
+                    send_push_message(
+                        contact_info.clone(),
+                        keypair_arc.as_ref(),
+                        addr,
+                        &tx,
+                        counter,
+                    );
+                }
+            }
+
+            if let Some(addr) = entrypoints.next_addr(Instant::now()) {
+                send_pull_request(
+                    contact_info.clone(),
+                    keypair_arc.as_ref(),
+                    addr,
+                    &tx,
+                    counter,
+                );
+            }
         }
 
         trace!("counter:{counter} terminated");
     })
 }
 
+/// Picks the peers our contact info is pushed to.
+struct PushTargets<R> {
+    fanout: usize,
+    stakes: Stakes,
+    rng: R,
+}
+
+impl<R: Rng> PushTargets<R> {
+    fn new(fanout: usize, stakes: Stakes, rng: R) -> Self {
+        PushTargets {
+            fanout,
+            stakes,
+            rng,
+        }
+    }
+
+    /// The entrypoint then the gossip addresses of up to `fanout` known peers.
+    /// With the stakes known the peers are drawn weighted by stake, otherwise
+    /// the ones which answered the ping come first by round-trip time, then the
+    /// ones not answered yet. The unresponsive peers and the ones which pruned
+    /// us, the entrypoint too, are left out.
+    fn peers(
+        &mut self,
+        crds: &CrdsTable,
+        pings: &PingTracker,
+        pruned_by: &HashSet<Pubkey>,
+        contact_info: &LegacyContactInfo,
+        (entrypoint_addr, entrypoint_id): (SocketAddr, Option<Pubkey>),
+    ) -> Vec<SocketAddr> {
+        let mut peers = crds
+            .values()
+            .filter_map(|value| match &value.data {
+                CrdsData::LegacyContactInfo(info)
+                    if info.id != contact_info.id
+                        && info.gossip != entrypoint_addr
+                        && info.is_valid_gossip()
+                        && !pruned_by.contains(&info.id) =>
+                {
+                    let unresponsive = pings.status(&info.id) == Some(PeerStatus::Unresponsive);
+                    (!unresponsive).then(|| (pings.rtt(&info.id), info.gossip, info.id))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let peers = if self.stakes.is_empty() {
+            // the alive peers first, the fastest first
+            peers.sort_by_key(|(rtt, addr, _)| (rtt.is_none(), *rtt, *addr));
+            peers.into_iter().map(|(_, addr, _)| addr).collect()
+        } else {
+            let addrs = peers
+                .iter()
+                .map(|(_, addr, id)| (*id, *addr))
+                .collect::<HashMap<_, _>>();
+            let weighted = peers
+                .iter()
+                .map(|(_, _, id)| (*id, self.stakes.get(id).copied().unwrap_or(0)))
+                .collect::<Vec<_>>();
+            weighted_shuffle(&weighted, &mut self.rng)
+                .into_iter()
+                .filter_map(|id| addrs.get(&id).copied())
+                .collect::<Vec<_>>()
+        };
+        let entrypoint =
+            (!entrypoint_id.is_some_and(|id| pruned_by.contains(&id))).then_some(entrypoint_addr);
+        entrypoint
+            .into_iter()
+            .chain(peers.into_iter().take(self.fanout))
+            .collect()
+    }
+}
+
+fn dump_bad_packet(
+    dump: &mut BadPacketDump,
+    from_addr: SocketAddr,
+    payload: &Payload,
+    err: &dyn std::fmt::Display,
+) {
+    let Some(bytes) = payload.data(..) else {
+        return;
+    };
+    if let Err(err) = dump.write(from_addr, bytes, err, since_the_epoch_millis()) {
+        trace!("# bad packet dump err:{err:?}");
+    }
+}
+
+/// Report the rates and the entrypoints, and expire the pings.
+fn on_tick(
+    rates: &rate::Stats,
+    pings: &mut PingTracker,
+    entrypoints: &Entrypoints,
+    data_tx: &SyncSender<Data>,
+) {
+    data_tx
+        .try_send(Data::Rates(rates.snapshot()))
+        .unwrap_or(());
+    data_tx
+        .try_send(Data::Entrypoints(entrypoints.responses()))
+        .unwrap_or(());
+    for peer in pings.expire(Instant::now()) {
+        data_tx
+            .try_send(Data::PeerStatus(peer, PeerStatus::Unresponsive))
+            .unwrap_or(());
+    }
+}
+
 fn send_pull_request(
     contact_info: LegacyContactInfo,
     keypair: &Keypair,
@@ -153,6 +437,74 @@ fn send_pull_request(
     }
 }
 
+fn send_ping(
+    pings: &mut PingTracker,
+    peer: Pubkey,
+    gossip: SocketAddr,
+    keypair: &Keypair,
+    tx: &Sender<Payload>,
+    counter: u32,
+) {
+    let now = Instant::now();
+    if !pings.should_ping(&peer, now) || !is_valid_addr(&gossip) {
+        return;
+    }
+
+    let ping = Ping::new(keypair);
+    match create_ping(ping.clone(), gossip) {
+        Ok(data) => {
+            tx.send(data).unwrap_or(());
+            pings.record_ping(peer, canonical_addr(gossip), ping, now);
+        }
+        Err(err) => {
+            trace!("counter:{counter} err:{err:?}");
+        }
+    }
+}
+
+fn send_push_message(
+    contact_info: LegacyContactInfo,
+    keypair: &Keypair,
+    entrypoint_addr: SocketAddr,
+    tx: &Sender<Payload>,
+    counter: u32,
+) {
+    let r = create_push_message(contact_info, keypair, entrypoint_addr);
+
+    match r {
+        Ok(data) => {
+            tx.send(data).unwrap_or(());
+        }
+        Err(err) => {
+            trace!("counter:{counter} err:{err:?}");
+        }
+    }
+}
+
+fn send_pull_responses(
+    crds: &CrdsTable,
+    filter: &CrdsFilter,
+    from_addr: SocketAddr,
+    keypair: &Keypair,
+    tx: &Sender<Payload>,
+    counter: u32,
+) {
+    let missing = crds.find_missing(filter);
+    match Protocol::pull_responses(keypair.pubkey(), missing, PULL_RESPONSE_MAX_VALUES) {
+        Ok(responses) => {
+            for response in responses {
+                match create_payload(&response, from_addr) {
+                    Ok(payload) => tx.send(payload).unwrap_or(()),
+                    Err(err) => trace!("# counter:{counter} err:{err:?}"),
+                }
+            }
+        }
+        Err(err) => {
+            trace!("# counter:{counter} err:{err:?}");
+        }
+    }
+}
+
 fn send_pong_response(
     ping: &Ping,
     from_addr: SocketAddr,
@@ -171,3 +523,231 @@ fn send_pong_response(
         }
     }
 }
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+    use solana_gossip_proto::{protocol::NodeInstance, utils::parse_addr};
+    use solana_sdk::signature::Signature;
+
+    use super::*;
+    use crate::common::DATA_CHANNEL_CAPACITY;
+
+    #[test]
+    fn test_on_tick_full_channel() {
+        // the UI not keeping up drops the data instead of blocking the logic
+        let (data_tx, data_rx) = mpsc::sync_channel(1);
+        let mut pings = PingTracker::default();
+        on_tick(
+            &rate::Stats::new(Instant::now()),
+            &mut pings,
+            &Entrypoints::default(),
+            &data_tx,
+        );
+        assert!(matches!(data_rx.try_recv(), Ok(Data::Rates(_))));
+        assert!(data_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_pull_request_verified_caller() {
+        let (tx, rx) = mpsc::channel::<Payload>();
+        let (data_tx, _data_rx) = mpsc::sync_channel(DATA_CHANNEL_CAPACITY);
+        let keypair = Keypair::new();
+        let from_addr = parse_addr("10.0.0.1:8001").unwrap();
+        let mut crds = CrdsTable::default();
+        crds.insert(CrdsValue::new_signed(
+            CrdsData::LegacyContactInfo(Box::new(LegacyContactInfo::new_spy(
+                &keypair,
+                parse_addr("10.0.0.2:8001").unwrap(),
+                0,
+            ))),
+            &keypair,
+        ))
+        .unwrap();
+        let mut pings = PingTracker::default();
+        let mut handler = LogicHandler {
+            from_addr,
+            len: 0,
+            counter: 0,
+            keypair: &keypair,
+            tx: &tx,
+            data_tx: &data_tx,
+            crds: &mut crds,
+            pings: &mut pings,
+            entrypoint_addr: from_addr,
+            entrypoint_id: &mut None,
+            pruned_by: &mut HashSet::new(),
+        };
+        let caller_keypair = Keypair::new();
+        let caller = |gossip| {
+            let info = LegacyContactInfo::new_spy(&caller_keypair, gossip, 0);
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &caller_keypair)
+        };
+        let received = |rx: &Receiver<Payload>| {
+            rx.try_iter()
+                .map(|payload| payload.deserialize_slice::<Protocol, _>(..).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // not gossiping from the source, possibly spoofed
+        let elsewhere = parse_addr("10.0.0.3:8001").unwrap();
+        handler.on_pull_request(CrdsFilter::default(), caller(elsewhere));
+        assert!(received(&rx).is_empty());
+
+        // forged
+        let forged = CrdsValue {
+            signature: Signature::default(),
+            ..caller(from_addr)
+        };
+        handler.on_pull_request(CrdsFilter::default(), forged);
+        assert!(received(&rx).is_empty());
+
+        // pinged first, answered once it answers the ping
+        handler.on_pull_request(CrdsFilter::default(), caller(from_addr));
+        let ping = match received(&rx).as_slice() {
+            [Protocol::PingMessage(ping)] => ping.clone(),
+            other => panic!("unexpected {other:?}"),
+        };
+        handler.on_pong(Pong::new(&ping, &caller_keypair).unwrap());
+        handler.on_pull_request(CrdsFilter::default(), caller(from_addr));
+        assert!(matches!(
+            received(&rx).as_slice(),
+            [Protocol::PullResponse(from, values)] if *from == keypair.pubkey() && values.len() == 1
+        ));
+    }
+
+    #[test]
+    fn test_received_values_invalid_signature() {
+        for push in [false, true] {
+            check_received_values_invalid_signature(push);
+        }
+    }
+
+    fn check_received_values_invalid_signature(push: bool) {
+        let (tx, _rx) = mpsc::channel();
+        let (data_tx, data_rx) = mpsc::sync_channel(DATA_CHANNEL_CAPACITY);
+        let keypair = Keypair::new();
+        let from_addr = parse_addr("10.0.0.1:8001").unwrap();
+        let mut crds = CrdsTable::default();
+        let mut pings = PingTracker::default();
+        let mut handler = LogicHandler {
+            from_addr,
+            len: 0,
+            counter: 0,
+            keypair: &keypair,
+            tx: &tx,
+            data_tx: &data_tx,
+            crds: &mut crds,
+            pings: &mut pings,
+            entrypoint_addr: from_addr,
+            entrypoint_id: &mut None,
+            pruned_by: &mut HashSet::new(),
+        };
+
+        let origin = Keypair::new();
+        let instance = |token| {
+            CrdsData::NodeInstance(NodeInstance {
+                from: origin.pubkey(),
+                wallclock: 1_000,
+                timestamp: 1_000,
+                token,
+            })
+        };
+        let valid = CrdsValue::new_signed(instance(1), &origin);
+        // the data no longer matches its signature
+        let tampered = CrdsValue {
+            data: instance(2),
+            ..valid.clone()
+        };
+        let values = vec![tampered, valid.clone()];
+        if push {
+            handler.on_push_message(keypair.pubkey(), values);
+        } else {
+            handler.on_pull_response(keypair.pubkey(), values);
+        }
+
+        assert!(matches!(
+            data_rx.try_recv(),
+            Ok(Data::InvalidSignature(addr, pubkey)) if addr == from_addr && pubkey == origin.pubkey()
+        ));
+        assert!(matches!(data_rx.try_recv(), Ok(Data::CrdsValue(_, value)) if *value == valid));
+        assert!(data_rx.try_recv().is_err());
+        // only the valid value is served to the peers
+        assert_eq!(crds.values().collect::<Vec<_>>(), vec![&valid]);
+    }
+
+    #[test]
+    fn test_push_peers() {
+        let start = Instant::now();
+        let me = Keypair::new();
+        let mut crds = CrdsTable::default();
+        let mut pings = PingTracker::default();
+        let mut add_peer = |port| {
+            let keypair = Keypair::new();
+            let info = LegacyContactInfo {
+                id: keypair.pubkey(),
+                gossip: parse_addr(&format!("10.0.0.1:{port}")).unwrap(),
+                wallclock: 1,
+                ..LegacyContactInfo::default()
+            };
+            crds.insert(CrdsValue::new_signed(
+                CrdsData::LegacyContactInfo(Box::new(info.clone())),
+                &keypair,
+            ))
+            .unwrap();
+            (keypair, info)
+        };
+        let (entrypoint, entrypoint_info) = add_peer(8001);
+        let (fast, fast_info) = add_peer(8002);
+        let (slow, slow_info) = add_peer(8003);
+        let (_, pending_info) = add_peer(8004);
+        let (silent, silent_info) = add_peer(8005);
+        let (pruning, _) = add_peer(8006);
+        let self_info = add_peer(8007).1;
+
+        for (peer, info, rtt) in [(&fast, &fast_info, 42), (&slow, &slow_info, 100)] {
+            let sent = Ping::new(&me);
+            pings.record_ping(peer.pubkey(), info.gossip, sent.clone(), start);
+            let answer = Pong::new(&sent, peer).unwrap();
+            pings.record_pong(&answer, start + Duration::from_millis(rtt));
+        }
+        pings.record_ping(silent.pubkey(), silent_info.gossip, Ping::new(&me), start);
+        pings.expire(start + Duration::from_mins(1));
+        let mut pruned_by = HashSet::from([pruning.pubkey()]);
+
+        let entrypoint_addr = (entrypoint_info.gossip, Some(entrypoint.pubkey()));
+        let peers_by = |pruned_by: &HashSet<Pubkey>, stakes: &Stakes, fanout| {
+            let mut targets = PushTargets::new(fanout, stakes.clone(), StdRng::seed_from_u64(42));
+            targets.peers(&crds, &pings, pruned_by, &self_info, entrypoint_addr)
+        };
+        let peers =
+            |pruned_by: &HashSet<Pubkey>, fanout| peers_by(pruned_by, &Stakes::new(), fanout);
+        assert_eq!(
+            peers(&pruned_by, 6),
+            vec![
+                entrypoint_info.gossip,
+                fast_info.gossip,
+                slow_info.gossip,
+                pending_info.gossip
+            ]
+        );
+        assert_eq!(
+            peers(&pruned_by, 1),
+            vec![entrypoint_info.gossip, fast_info.gossip]
+        );
+        assert_eq!(peers(&pruned_by, 0), vec![entrypoint_info.gossip]);
+
+        pruned_by.insert(entrypoint.pubkey());
+        assert_eq!(peers(&pruned_by, 1), vec![fast_info.gossip]);
+
+        // the staked peer is picked first whatever its round-trip time
+        let stakes = Stakes::from([(pending_info.id, 1_000), (slow_info.id, 1)]);
+        assert_eq!(
+            peers_by(&pruned_by, &stakes, 3),
+            vec![pending_info.gossip, slow_info.gossip, fast_info.gossip]
+        );
+    }
+}
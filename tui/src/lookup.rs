@@ -0,0 +1,119 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io,
+    net::IpAddr,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::Builder,
+};
+
+use log::trace;
+
+/// Per-IP cache of lookups performed on a background thread, so that the
+/// render loop never blocks on them.
+#[derive(Debug)]
+pub struct IpLookup {
+    cache: HashMap<IpAddr, Option<String>>,
+    requests_tx: Sender<IpAddr>,
+    results_rx: Receiver<(IpAddr, Option<String>)>,
+}
+
+impl IpLookup {
+    /// Spawn the lookup thread, it terminates when the `IpLookup` is dropped.
+    pub fn spawn<F>(name: &str, lookup: F) -> io::Result<Self>
+    where
+        F: Fn(IpAddr) -> Option<String> + Send + 'static,
+    {
+        let (requests_tx, requests_rx) = mpsc::channel::<IpAddr>();
+        let (results_tx, results_rx) = mpsc::channel();
+
+        let thread_name = name.to_string();
+        Builder::new().name(name.to_string()).spawn(move || {
+            for ip in requests_rx {
+                let result = lookup(ip);
+                trace!("[{thread_name}] ip:{ip} result:{result:?}");
+                if results_tx.send((ip, result)).is_err() {
+                    break;
+                }
+            }
+            trace!("[{thread_name}] terminated");
+        })?;
+
+        Ok(IpLookup {
+            cache: HashMap::new(),
+            requests_tx,
+            results_rx,
+        })
+    }
+
+    /// Cached result for the address, the lookup is queued the first time it is seen.
+    pub fn get(&mut self, ip: IpAddr) -> Option<&str> {
+        match self.cache.entry(ip) {
+            Entry::Occupied(entry) => entry.into_mut().as_deref(),
+            Entry::Vacant(entry) => {
+                self.requests_tx.send(ip).unwrap_or(());
+                entry.insert(None);
+                None
+            }
+        }
+    }
+
+    /// Collect the completed lookups, returns the ones which produced a result.
+    pub fn poll(&mut self) -> Vec<(IpAddr, String)> {
+        let mut resolved = Vec::new();
+        for (ip, result) in self.results_rx.try_iter() {
+            if let Some(value) = &result {
+                resolved.push((ip, value.clone()));
+            }
+            self.cache.insert(ip, result);
+        }
+        resolved
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::Ipv4Addr,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+
+    fn poll_until(lookup: &mut IpLookup, count: usize) -> Vec<(IpAddr, String)> {
+        let start = Instant::now();
+        let mut resolved = Vec::new();
+        while resolved.len() < count && start.elapsed() < Duration::from_secs(5) {
+            resolved.extend(lookup.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+        resolved
+    }
+
+    #[test]
+    fn test_ip_lookup() {
+        let mut lookup = IpLookup::spawn("lookup_test_t", |ip| match ip {
+            IpAddr::V4(ip) if ip.is_private() => None,
+            ip => Some(format!("host-{ip}")),
+        })
+        .unwrap();
+
+        let public = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let private = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        assert_eq!(lookup.get(public), None);
+        assert_eq!(lookup.get(private), None);
+        // queued only once
+        assert_eq!(lookup.get(public), None);
+
+        let resolved = poll_until(&mut lookup, 1);
+        assert_eq!(
+            resolved,
+            vec![(public, String::from("host-141.98.219.218"))]
+        );
+        assert_eq!(lookup.get(public), Some("host-141.98.219.218"));
+        assert_eq!(lookup.get(private), None);
+        assert!(lookup.poll().is_empty());
+    }
+}
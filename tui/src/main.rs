@@ -1,42 +1,140 @@
 mod app;
+mod bad_packets;
 mod common;
+mod config;
+mod dns;
+mod export;
+mod geo;
+mod history;
 mod logic;
+mod lookup;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod ping;
+mod rate;
+mod stakes;
 mod stm;
+mod stream;
 mod terminal;
 mod transport;
 mod ui;
 
 use std::{
-    io,
-    sync::mpsc::Receiver,
+    collections::HashSet,
+    env, io,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
-use crossterm::event::{self, Event, KeyCode};
-use log::error;
-use solana_gossip_proto::protocol::{LegacyContactInfo, LegacyVersion2};
+use clap::Parser;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use log::{error, info, LevelFilter};
+use signal_hook::consts::{SIGINT, SIGTERM};
+use simple_logger::SimpleLogger;
+use solana_gossip_proto::{
+    crds::{
+        detect_shred_version_changes, CrdsTableHistory, CrdsValueLabel,
+        DUPLICATE_SHRED_PROOF_TIMEOUT,
+    },
+    protocol::{CrdsData, CrdsValue},
+    utils::since_the_epoch_millis,
+};
 use tui::{backend::Backend, Terminal};
 
+#[cfg(feature = "metrics")]
+use crate::metrics::{Metrics, SharedMetrics};
 use crate::{
-    app::Context,
+    app::{Context, InputMode},
     common::{init_threads, Data},
-    logic::RECV_TIMEOUT,
-    stm::{events, stm_main::MainStm, States},
+    config::{AppConfig, Cli, FileConfig},
+    dns::resolve_ptr,
+    export::{write_crds_checkpoint, write_crds_snapshot},
+    geo::{GeoIp, GEOIP_DB_ENV},
+    logic::{CRDS_MAX_AGE, RECV_TIMEOUT},
+    lookup::IpLookup,
+    rate::PacketKind,
+    stm::{
+        events,
+        keymap::{action_for, Action, HOME_KEY_BINDINGS},
+        stm_main::MainStm,
+        States,
+    },
+    stream::JsonStream,
     transport::{CtrlCmd, Stats},
+    ui::quit_summary_widget::RunSummary,
 };
 
 const APP_ID: &str = "solana_gossip_tui";
 const APP_VERSION: &str = "0.0.1+";
 const STATS_INTERVAL: Duration = Duration::from_millis(1000);
+/// Data handled at most between two frames, a pull response round carries
+/// hundreds of values.
+const MAX_DATA_PER_FRAME: usize = 2_000;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // initialize terminal state
-    let mut xterm = terminal::XTerminal::new()?;
+    let cli = Cli::parse();
+    let file = FileConfig::load(&cli.config)?;
+    let mut config = AppConfig::new(cli, file);
 
     // initialize app context and state machine
     let mut ctx = Context::new(String::from(APP_ID), String::from(APP_VERSION));
+    if !config.entrypoints.is_empty() {
+        ctx.model.entrypoints = config.entrypoints.iter().map(ToString::to_string).collect();
+    } else if let Some(cluster) = config.cluster {
+        // the hostnames are listed, the pull requests rotate across the resolved ones
+        ctx.model.entrypoints = cluster
+            .entrypoints()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        config.entrypoints = cluster.resolve_entrypoints();
+    }
+    ctx.config = config;
     let mut stm = MainStm::new("stm", true);
 
+    // optional Prometheus metrics
+    #[cfg(feature = "metrics")]
+    if let Some(addr) = ctx.config.metrics_addr {
+        let shared = SharedMetrics::default();
+        metrics::spawn_metrics_server(addr, shared.clone())?;
+        ctx.model.metrics = Some(shared);
+    }
+
+    // optional country lookups
+    let geoip_db = ctx
+        .config
+        .geoip_db
+        .clone()
+        .or_else(|| env::var_os(GEOIP_DB_ENV).map(PathBuf::from));
+    if let Some(path) = geoip_db {
+        let geo = GeoIp::open(&path)?;
+        ctx.model.geo = Some(IpLookup::spawn("geo_t", move |ip| geo.lookup_country(ip))?);
+        ctx.model.home_node_table.show_country = true;
+    }
+
+    ctx.model.crds_history = ctx.config.history.map(CrdsTableHistory::new);
+
+    // no terminal at all, the values are only written to the snapshot
+    if ctx.config.headless {
+        SimpleLogger::new().with_level(LevelFilter::Info).init()?;
+        if ctx.config.stream_json {
+            ctx.model.stream = Some(JsonStream::new(io::stdout()));
+        }
+        return Ok(run_headless(&mut ctx)?);
+    }
+
+    // hostnames of the nodes, resolved on demand by the detail view
+    ctx.model.dns = Some(IpLookup::spawn("dns_t", resolve_ptr)?);
+
+    // initialize terminal state
+    let mut xterm = terminal::XTerminal::new(ctx.config.mouse)?;
+
     let res = run_app(&mut xterm.terminal, &mut ctx, &mut stm);
 
     // check for errors
@@ -61,90 +159,47 @@ fn run_app<B: Backend>(
     let mut data_rx: Option<Receiver<Data>> = None;
     let mut stats_rx: Option<Receiver<Stats>> = None;
     let mut before = Instant::now();
+    let mut checkpoint_at = Instant::now();
 
     loop {
         terminal.draw(|f| stm.draw(f, ctx))?;
 
-        if event::poll(Duration::from_millis(200))? {
-            if let Event::Key(key) = event::read()? {
-                stm.on_event(events::Event::Key { key_code: key.code }, ctx);
-
-                if let KeyCode::Char('q') = key.code {
+        if event::poll(ctx.config.refresh)? {
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                on_mouse_event(ctx, stm, &mouse);
+            }
+            if let Event::Key(key) = event {
+                if is_hard_exit(&key) || on_quit_key(ctx, key.code) {
                     for ctrl_tx in &ctx.ctrl_txs {
                         ctrl_tx.send(CtrlCmd::Stop).unwrap_or(());
                     }
                     return Ok(());
-                } else if KeyCode::Char('c') == key.code
-                    && stm.current_st == States::Home
-                    && data_rx.is_none()
-                {
-                    let res = init_threads(ctx);
-                    if let Ok((data_receiver, stats_receiver, _)) = res {
-                        data_rx = Some(data_receiver);
-                        stats_rx = Some(stats_receiver);
-                    }
-                } else if KeyCode::Char('d') == key.code
-                    && stm.current_st == States::Home
-                    && data_rx.is_some()
-                {
-                    for ctrl_tx in &ctx.ctrl_txs {
-                        ctrl_tx.send(CtrlCmd::Stop).unwrap_or(());
-                    }
-
-                    data_rx = None;
-                    stats_rx = None;
                 }
+                on_key(ctx, stm, key.code, &mut data_rx, &mut stats_rx);
             }
         }
 
         if let Some(ref data_rx) = data_rx {
-            if let Ok(data) = data_rx.recv_timeout(RECV_TIMEOUT) {
-                match data {
-                    Data::LegacyContactInfo(info) => {
-                        let rows = ctx
-                            .model
-                            .home_stateful_table
-                            .items
-                            .iter()
-                            .filter(|row| -> bool {
-                                let id = &row[2];
-
-                                id == &info.id.to_string()
-                            })
-                            .collect::<Vec<_>>();
-
-                        if rows.is_empty() {
-                            let row = create_row(&info);
-                            ctx.model.home_stateful_table.push_row(row);
-                        }
-                    }
-                    Data::Version(version) => {
-                        fn format_version(version: &LegacyVersion2) -> String {
-                            format!("{}.{}.{}", version.major, version.minor, version.patch)
-                        }
-
-                        let at_index =
-                            ctx.model
-                                .home_stateful_table
-                                .items
-                                .iter()
-                                .position(|row| -> bool {
-                                    let id = &row[2];
-
-                                    id == &version.from.to_string()
-                                });
-
-                        if let Some(index) = at_index {
-                            let row = &mut ctx.model.home_stateful_table.items[index];
-
-                            let _ =
-                                std::mem::replace(&mut row[3], format_version(&version.version));
-                        }
-                    }
-                }
+            drain_data(ctx, data_rx);
+        }
+
+        if !ctx.model.paused && !ctx.model.paused_queue.is_empty() {
+            for (from, value) in ctx.model.paused_queue.drain() {
+                integrate_value(ctx, from, value);
+            }
+        }
+
+        if let Some(geo) = &mut ctx.model.geo {
+            for (ip, country) in geo.poll() {
+                ctx.model.home_node_table.set_country(ip, &country);
             }
         }
 
+        if let Some(dns) = &mut ctx.model.dns {
+            dns.poll();
+        }
+
         if let Some(ref stats_rx) = stats_rx {
             if let Ok(stats) = stats_rx.recv_timeout(RECV_TIMEOUT) {
                 fn format_stats(ctx: &mut Context, index: usize, stats: &Stats) {
@@ -164,34 +219,448 @@ fn run_app<B: Backend>(
         let now = Instant::now();
         if (now - before) > STATS_INTERVAL {
             before = now;
+            on_stats_interval(ctx, data_rx.is_some());
+        }
+        if data_rx.is_some() && is_checkpoint_due(ctx, checkpoint_at, now) {
+            checkpoint_at = now;
+            write_checkpoint(ctx);
+        }
+    }
+}
+
+/// The key goes to the state machine, then to the home actions connecting,
+/// disconnecting or quitting, unless it only closed the help.
+fn on_key(
+    ctx: &mut Context,
+    stm: &mut MainStm,
+    key_code: KeyCode,
+    data_rx: &mut Option<Receiver<Data>>,
+    stats_rx: &mut Option<Receiver<Stats>>,
+) {
+    let closes_help = stm.current_st == States::Help;
+    if ctx.model.quit_summary.is_none() {
+        stm.on_event(events::Event::Key { key_code }, ctx);
+    }
+
+    let action = action_for(HOME_KEY_BINDINGS, key_code);
+    if closes_help || ctx.model.input_mode != InputMode::Normal || ctx.model.quit_summary.is_some()
+    {
+        // keys are consumed by the help, the search or follow input, or by the quit summary
+    } else if action == Some(Action::Quit) {
+        ctx.model.quit_summary = Some(RunSummary::new(&ctx.model, Instant::now()));
+    } else if action == Some(Action::Connect) && stm.current_st == States::Home && data_rx.is_none()
+    {
+        let res = init_threads(ctx);
+        if let Ok((data_receiver, stats_receiver, _)) = res {
+            *data_rx = Some(data_receiver);
+            *stats_rx = Some(stats_receiver);
+            ctx.model.connected_at = Some(Instant::now());
+            ctx.model.last_response_at = None;
+        }
+    } else if action == Some(Action::Disconnect)
+        && stm.current_st == States::Home
+        && data_rx.is_some()
+    {
+        for ctrl_tx in &ctx.ctrl_txs {
+            ctrl_tx.send(CtrlCmd::Stop).unwrap_or(());
+        }
+
+        *data_rx = None;
+        *stats_rx = None;
+        ctx.model.connected_at = None;
+        ctx.model.entrypoint_responses.clear();
+    }
+}
+
+/// The mouse events go to the state machine when enabled, and not while the
+/// quit summary is shown.
+fn on_mouse_event(ctx: &mut Context, stm: &mut MainStm, mouse: &MouseEvent) {
+    if ctx.config.mouse && ctx.model.quit_summary.is_none() {
+        let event = events::Event::Mouse {
+            kind: mouse.kind,
+            column: mouse.column,
+            row: mouse.row,
+        };
+        stm.on_event(event, ctx);
+    }
+}
+
+/// `Ctrl-C` quits at once, without the summary.
+fn is_hard_exit(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// While the quit summary is shown `q` or `Enter` confirm the quit, returning
+/// true, and `Esc` cancels it. The other keys are ignored.
+fn on_quit_key(ctx: &mut Context, key_code: KeyCode) -> bool {
+    if ctx.model.quit_summary.is_none() {
+        return false;
+    }
+    match key_code {
+        KeyCode::Char('q') | KeyCode::Enter => true,
+        KeyCode::Esc => {
+            ctx.model.quit_summary = None;
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Collect the values without the terminal UI, the snapshot of the CRDS table is
+/// written every interval and a last time when SIGTERM or SIGINT is received.
+fn run_headless(ctx: &mut Context) -> io::Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(SIGTERM, Arc::clone(&stop))?;
+    signal_hook::flag::register(SIGINT, Arc::clone(&stop))?;
+
+    // the first entrypoint, the ones given on the command line replace the default ones
+    ctx.model.entrypoint = ctx.model.entrypoints.first().cloned();
+    let (data_rx, stats_rx, _) = init_threads(ctx)?;
+    ctx.model.connected_at = Some(Instant::now());
+    info!(
+        "[headless] entrypoint:{:?} snapshot:{}",
+        ctx.model.entrypoint,
+        ctx.config.snapshot.display()
+    );
+
+    let mut before = Instant::now();
+    let mut snapshot_at = Instant::now();
+    let mut checkpoint_at = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        drain_data(ctx, &data_rx);
+        // the counters are only shown by the UI
+        while stats_rx.try_recv().is_ok() {}
+
+        let now = Instant::now();
+        if (now - before) > STATS_INTERVAL {
+            before = now;
+            on_stats_interval(ctx, true);
+        }
+        if (now - snapshot_at) >= ctx.config.snapshot_interval {
+            snapshot_at = now;
+            write_snapshot(ctx);
+        }
+        if is_checkpoint_due(ctx, checkpoint_at, now) {
+            checkpoint_at = now;
+            write_checkpoint(ctx);
+        }
+    }
+
+    for ctrl_tx in &ctx.ctrl_txs {
+        ctrl_tx.send(CtrlCmd::Stop).unwrap_or(());
+    }
+    write_snapshot(ctx);
+    info!("[headless] stopped");
+    Ok(())
+}
+
+fn write_snapshot(ctx: &Context) {
+    let path = &ctx.config.snapshot;
+    match write_crds_snapshot(&ctx.model.crds, path) {
+        Ok(()) => info!(
+            "[headless] {} values written to {}",
+            ctx.model.crds.len(),
+            path.display()
+        ),
+        Err(err) => error!("[headless] snapshot {} failed err:{err}", path.display()),
+    }
+}
+
+fn is_checkpoint_due(ctx: &Context, checkpoint_at: Instant, now: Instant) -> bool {
+    ctx.config
+        .checkpoint_interval
+        .is_some_and(|interval| (now - checkpoint_at) >= interval)
+}
+
+/// Write a checkpoint of the CRDS table, pruning the oldest ones.
+fn write_checkpoint(ctx: &mut Context) {
+    let result = write_crds_checkpoint(
+        &ctx.model.crds,
+        &ctx.config.checkpoint_dir,
+        since_the_epoch_millis(),
+        ctx.config.checkpoint_keep,
+    );
+    match result {
+        Ok(path) if ctx.config.headless => {
+            info!("[headless] checkpoint written to {}", path.display());
+        }
+        Ok(path) => ctx.debug(format!("[Main] checkpoint written to {}", path.display())),
+        Err(err) if ctx.config.headless => error!("[headless] checkpoint failed err:{err}"),
+        Err(err) => ctx.debug(format!("[Main] checkpoint failed err:{err}")),
+    }
+}
+
+/// Handle the data received since the last call, up to `MAX_DATA_PER_FRAME`,
+/// waiting `RECV_TIMEOUT` for the first one, then sort the node table once.
+fn drain_data(ctx: &mut Context, data_rx: &Receiver<Data>) {
+    if let Ok(data) = data_rx.recv_timeout(RECV_TIMEOUT) {
+        handle_data(ctx, data);
+        for data in data_rx.try_iter().take(MAX_DATA_PER_FRAME - 1) {
+            handle_data(ctx, data);
+        }
+    }
+    ctx.model.home_node_table.sort_if_dirty();
+}
 
-            for ctrl_tx in &ctx.ctrl_txs {
-                ctrl_tx.send(CtrlCmd::Counter).unwrap_or(());
+fn handle_data(ctx: &mut Context, data: Data) {
+    match data {
+        Data::CrdsValue(from, value) if ctx.model.paused => {
+            ctx.model.paused_queue.push(from, *value);
+        }
+        Data::CrdsValue(from, value) => integrate_value(ctx, from, *value),
+        Data::Rates(rates) => {
+            if rates.packets_of(PacketKind::PullResponse) > 0 {
+                ctx.model.last_response_at = Some(Instant::now());
+            }
+            ctx.model.rates = rates;
+            #[cfg(feature = "metrics")]
+            if let Some(shared) = &ctx.model.metrics {
+                if let Ok(mut metrics) = shared.lock() {
+                    *metrics = Metrics::new(&ctx.model);
+                }
+            }
+        }
+        Data::PeerStatus(peer, status) => {
+            ctx.model.home_node_table.set_peer_status(peer, status);
+        }
+        Data::PrunedBy(peer, wallclock) => {
+            ctx.model.pruned_by.insert(peer, wallclock);
+        }
+        Data::Entrypoints(responses) => {
+            ctx.model.entrypoint_responses = responses;
+        }
+        Data::PullFilter(peer, filter) => {
+            ctx.model.pull_filter = Some((peer, *filter));
+        }
+        Data::InvalidSignature(from, origin) => {
+            // reported once per origin, the next ones are only counted
+            let failures = ctx.model.verification.verification_failures();
+            if !failures.contains_key(&origin) {
+                ctx.debug(format!(
+                    "[Main] invalid signature of {origin} received from {from}"
+                ));
             }
+            ctx.model.verification.record_failure(origin);
+        }
+    }
+}
+
+fn on_stats_interval(ctx: &mut Context, connected: bool) {
+    // nodes are dropped together with their contact info, not while paused
+    if !ctx.model.paused
+        && ctx
+            .model
+            .crds
+            .purge_stale(&ctx.config.crds_ttl, since_the_epoch_millis())
+            > 0
+    {
+        ctx.model.slots_coverage.clear();
+        let crds = &ctx.model.crds;
+        ctx.model.home_node_table.retain(|row| {
+            crds.get(&CrdsValueLabel::LegacyContactInfo(row.info.id))
+                .is_some()
+        });
+        // the nodes whose values all expired
+        let origins = crds
+            .values()
+            .filter_map(|value| value.data.pubkey())
+            .collect::<HashSet<_>>();
+        ctx.model
+            .highest_slots
+            .retain(|pubkey| origins.contains(pubkey));
+    }
+    ctx.model
+        .node_instances
+        .purge_stale(since_the_epoch_millis());
+    let conflicts = ctx.model.node_instances.conflicts();
+    for pubkey in &conflicts {
+        if !ctx.model.instance_conflicts.contains(pubkey) {
+            ctx.debug(format!("[Main] duplicate instances running for {pubkey}"));
         }
     }
+    ctx.model.instance_conflicts = conflicts;
+
+    if let Some(history) = &mut ctx.model.crds_history {
+        history.purge_stale(CRDS_MAX_AGE, since_the_epoch_millis());
+        let changes = detect_shred_version_changes(history);
+        ctx.model
+            .home_node_table
+            .update_shred_version_changes(&changes);
+    }
+
+    ctx.model.cluster_tip = ctx.model.highest_slots.cluster_tip();
+    ctx.model
+        .home_node_table
+        .update_vote_lags(&ctx.model.votes, ctx.model.cluster_tip);
+    ctx.model
+        .duplicate_shreds
+        .purge_incomplete(DUPLICATE_SHRED_PROOF_TIMEOUT, since_the_epoch_millis());
+
+    if connected {
+        let count = ctx.model.home_node_table.rows.len();
+        ctx.model.node_count_history.push(count);
+    }
+
+    for ctrl_tx in &ctx.ctrl_txs {
+        ctrl_tx.send(CtrlCmd::Counter).unwrap_or(());
+    }
 }
 
-fn create_row(info: &LegacyContactInfo) -> Vec<String> {
-    vec![
-        format!("{}", info.gossip.ip()),         // "IP",
-        format!("{}", info.wallclock),           // "Age(ms)",
-        format!("{:?}", info.id),                // "Node Identifier",
-        format!(" - ",),                         // "Version",
-        format!("{}", info.gossip.port()),       // "Gossip",
-        format!("{}", info.tpu_vote.port()),     // "TPUvote",
-        format!("{}", info.tpu.port()),          // "TPU",
-        format!("{}", info.tpu_forwards.port()), // "TPUfwd",
-        format!("{}", info.tvu.port()),          // "TVU",
-        format!("{}", info.tvu_forwards.port()), // "TVUfwd",
-        format!("{}", info.repair.port()),       // "Repair",
-        format!("{}", info.serve_repair.port()), // "ServeR",
-        format!("{}", info.shred_version),       // "ShredVer",
-    ]
+/// Write the value to the JSON stream, when enabled. The stream is closed on
+/// the first error, most likely the reader of the pipe went away.
+fn stream_value(ctx: &mut Context, from_addr: SocketAddr, value: &CrdsValue) {
+    let Some(stream) = &mut ctx.model.stream else {
+        return;
+    };
+    if let Err(err) = stream.write_value(since_the_epoch_millis(), from_addr, value) {
+        error!("[stream] closed after {} values: {err}", stream.written());
+        ctx.model.stream = None;
+    }
+}
+
+fn integrate_value(ctx: &mut Context, from_addr: SocketAddr, value: CrdsValue) {
+    // the instances of a duplicate identity are not all newer than the stored one
+    if let CrdsData::NodeInstance(instance) = &value.data {
+        ctx.model.node_instances.insert(instance);
+    }
+    if ctx.model.crds.insert(value.clone()).is_ok() {
+        stream_value(ctx, from_addr, &value);
+        let from = value.data.pubkey();
+        if let Some(history) = &mut ctx.model.crds_history {
+            history.push(value.clone(), since_the_epoch_millis());
+        }
+        ctx.model.votes.insert(&value, since_the_epoch_millis());
+        ctx.model.highest_slots.insert(&value);
+        match value.data {
+            CrdsData::LegacyContactInfo(info) => {
+                let (id, ip) = (info.id, info.gossip.ip());
+                let table = &mut ctx.model.home_node_table;
+                if table.upsert_contact_info(*info) {
+                    table.backfill(id, &ctx.model.crds, &ctx.model.votes);
+                }
+                if let Some(geo) = &mut ctx.model.geo {
+                    if let Some(country) = geo.get(ip) {
+                        table.set_country(ip, country);
+                    }
+                }
+            }
+            CrdsData::Version(version) => {
+                ctx.model.home_node_table.update_version(&version);
+            }
+            CrdsData::LegacyVersion(version) => {
+                ctx.model.home_node_table.update_legacy_version(&version);
+            }
+            CrdsData::LowestSlot(_, slots) => {
+                ctx.model.home_node_table.update_lowest_slot(&slots);
+            }
+            CrdsData::EpochSlots(_, slots) => {
+                ctx.model.slots_coverage.remove(&slots.from);
+            }
+            CrdsData::DuplicateShred(_, shred) => {
+                ctx.model
+                    .duplicate_shreds
+                    .insert(&shred, since_the_epoch_millis());
+            }
+            CrdsData::Vote(_, vote) => {
+                if let (Some(from), Some(slot)) = (from, vote.slots().last()) {
+                    ctx.model.home_node_table.update_last_vote(from, *slot);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use solana_gossip_proto::protocol::EpochSlots;
+    use solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_quit_confirmation() {
+        let mut ctx = Context::new_for_testing();
+        assert!(!on_quit_key(&mut ctx, KeyCode::Char('q')));
+
+        ctx.model.quit_summary = Some(RunSummary::new(&ctx.model, Instant::now()));
+        assert!(!on_quit_key(&mut ctx, KeyCode::Char('x')));
+        assert!(on_quit_key(&mut ctx, KeyCode::Char('q')));
+        assert!(on_quit_key(&mut ctx, KeyCode::Enter));
+        assert!(!on_quit_key(&mut ctx, KeyCode::Esc));
+        assert!(ctx.model.quit_summary.is_none());
+
+        assert!(is_hard_exit(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_hard_exit(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::NONE
+        )));
+    }
+
+    #[test]
+    fn test_key_closing_help() {
+        let mut ctx = Context::new_for_testing();
+        let mut stm = MainStm::new("stm", false);
+        stm.switch_state(States::Home, &mut ctx);
+        let (mut data_rx, mut stats_rx) = (None, None);
+
+        for key_code in [KeyCode::Char('c'), KeyCode::Char('q')] {
+            stm.switch_state(States::Help, &mut ctx);
+            on_key(&mut ctx, &mut stm, key_code, &mut data_rx, &mut stats_rx);
+            assert_eq!(stm.current_st, States::Home);
+            assert!(data_rx.is_none());
+            assert!(ctx.ctrl_txs.is_empty());
+            assert!(ctx.model.quit_summary.is_none());
+        }
+
+        // out of the help the key is acted upon
+        on_key(
+            &mut ctx,
+            &mut stm,
+            KeyCode::Char('q'),
+            &mut data_rx,
+            &mut stats_rx,
+        );
+        assert!(ctx.model.quit_summary.is_some());
+    }
+
+    #[test]
+    fn test_drain_data() {
+        let mut ctx = Context::new_for_testing();
+        let (data_tx, data_rx) = std::sync::mpsc::channel();
+        for wallclock in 0..MAX_DATA_PER_FRAME + 10 {
+            data_tx
+                .send(Data::PrunedBy(
+                    Pubkey::new_unique(),
+                    u64::try_from(wallclock).unwrap(),
+                ))
+                .unwrap();
+        }
+
+        drain_data(&mut ctx, &data_rx);
+        assert_eq!(ctx.model.pruned_by.len(), MAX_DATA_PER_FRAME);
+        drain_data(&mut ctx, &data_rx);
+        assert_eq!(ctx.model.pruned_by.len(), MAX_DATA_PER_FRAME + 10);
+    }
+
+    #[test]
+    fn test_integrate_epoch_slots() {
+        let mut ctx = Context::new_for_testing();
+        let keypair = Keypair::new();
+        ctx.model.slots_coverage.insert(keypair.pubkey(), None);
+
+        let slots = EpochSlots::from_slots(keypair.pubkey(), &[100, 102], since_the_epoch_millis());
+        let value = CrdsValue::new_signed(CrdsData::EpochSlots(0, slots), &keypair);
+        integrate_value(&mut ctx, "141.98.219.218:8001".parse().unwrap(), value);
+        assert!(ctx.model.slots_coverage.is_empty());
+    }
+
     #[test]
     fn test_format_version() {
         let mut items = vec![
@@ -218,28 +687,25 @@ mod tests {
 
         assert_eq!(at_index, Some(2));
 
-        match at_index {
-            Some(index) => {
-                let row = &mut items[index];
+        if let Some(index) = at_index {
+            let row = &mut items[index];
 
-                let eq = vec!["row_3x1", "row_3x2", "row_3x3"]
-                    .into_iter()
-                    .map(|cell| cell.to_string())
-                    .collect::<Vec<String>>();
+            let eq = vec!["row_3x1", "row_3x2", "row_3x3"]
+                .into_iter()
+                .map(|cell| cell.to_string())
+                .collect::<Vec<String>>();
 
-                assert_eq!(row, &eq);
+            assert_eq!(row, &eq);
 
-                let _ = std::mem::replace(&mut row[0], "XXX".to_string());
-                let _ = std::mem::replace(&mut row[1], "YYY".to_string());
+            let _ = std::mem::replace(&mut row[0], "XXX".to_string());
+            let _ = std::mem::replace(&mut row[1], "YYY".to_string());
 
-                let eq = vec!["XXX", "YYY", "row_3x3"]
-                    .into_iter()
-                    .map(|cell| cell.to_string())
-                    .collect::<Vec<String>>();
+            let eq = vec!["XXX", "YYY", "row_3x3"]
+                .into_iter()
+                .map(|cell| cell.to_string())
+                .collect::<Vec<String>>();
 
-                assert_eq!(items[index], eq);
-            }
-            None => {}
+            assert_eq!(items[index], eq);
         }
     }
 }
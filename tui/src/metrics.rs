@@ -0,0 +1,211 @@
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread::{Builder, JoinHandle},
+    time::Duration,
+};
+
+use log::trace;
+
+use crate::{
+    app::Model,
+    ping::PeerStatus,
+    rate::{PacketKind, StatsSnapshot},
+};
+
+/// Upper bounds of the peer round-trip time histogram buckets, in milliseconds.
+pub const RTT_BUCKETS_MS: [u64; 7] = [10, 25, 50, 100, 250, 500, 1_000];
+
+/// Values exposed to the scraper, refreshed by the main loop.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub nodes: usize,
+    pub rates: StatsSnapshot,
+    /// round-trip times of the peers answering the pings
+    pub rtts: Vec<Duration>,
+}
+
+pub type SharedMetrics = Arc<Mutex<Metrics>>;
+
+impl Metrics {
+    pub fn new(model: &Model) -> Self {
+        let rows = &model.home_node_table.rows;
+        Metrics {
+            nodes: rows.len(),
+            rates: model.rates,
+            rtts: rows
+                .iter()
+                .filter_map(|row| match row.peer_status {
+                    Some(PeerStatus::Alive(rtt)) => Some(rtt),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "# HELP gossip_nodes Nodes discovered through gossip.");
+        let _ = writeln!(text, "# TYPE gossip_nodes gauge");
+        let _ = writeln!(text, "gossip_nodes {}", self.nodes);
+
+        let _ = writeln!(
+            text,
+            "# HELP gossip_packets_total Packets received, by kind."
+        );
+        let _ = writeln!(text, "# TYPE gossip_packets_total counter");
+        for kind in PacketKind::ALL {
+            let _ = writeln!(
+                text,
+                "gossip_packets_total{{kind=\"{kind}\"}} {}",
+                self.rates.totals[kind as usize]
+            );
+        }
+
+        let _ = writeln!(text, "# HELP gossip_values_total CRDS values received.");
+        let _ = writeln!(text, "# TYPE gossip_values_total counter");
+        let _ = writeln!(text, "gossip_values_total {}", self.rates.total_values);
+
+        let _ = writeln!(
+            text,
+            "# HELP gossip_values_per_second CRDS values received over the last second."
+        );
+        let _ = writeln!(text, "# TYPE gossip_values_per_second gauge");
+        let _ = writeln!(
+            text,
+            "gossip_values_per_second {}",
+            self.rates.values_per_sec
+        );
+
+        let _ = writeln!(
+            text,
+            "# HELP gossip_peer_rtt_seconds Round-trip time of the peers."
+        );
+        let _ = writeln!(text, "# TYPE gossip_peer_rtt_seconds histogram");
+        for bound in RTT_BUCKETS_MS {
+            let count = self
+                .rtts
+                .iter()
+                .filter(|rtt| rtt.as_millis() <= u128::from(bound))
+                .count();
+            let _ = writeln!(
+                text,
+                "gossip_peer_rtt_seconds_bucket{{le=\"{}\"}} {count}",
+                Duration::from_millis(bound).as_secs_f64()
+            );
+        }
+        let sum = self.rtts.iter().sum::<Duration>().as_secs_f64();
+        let _ = writeln!(
+            text,
+            "gossip_peer_rtt_seconds_bucket{{le=\"+Inf\"}} {}",
+            self.rtts.len()
+        );
+        let _ = writeln!(text, "gossip_peer_rtt_seconds_sum {sum}");
+        let _ = writeln!(text, "gossip_peer_rtt_seconds_count {}", self.rtts.len());
+        text
+    }
+}
+
+fn respond(stream: &mut TcpStream, metrics: &SharedMetrics) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&*stream).read_line(&mut request_line)?;
+
+    let (status, body) = match request_line.split_whitespace().nth(1) {
+        Some("/metrics") => {
+            let metrics = metrics
+                .lock()
+                .map_or_else(|_| Metrics::default(), |m| m.clone());
+            ("200 OK", metrics.render())
+        }
+        _ => ("404 Not Found", String::from("not found\n")),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Serve the metrics on `GET /metrics`, one connection at a time.
+pub fn spawn_metrics_server(
+    addr: SocketAddr,
+    metrics: SharedMetrics,
+) -> io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+
+    let handle = Builder::new()
+        .name("metrics_t".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|mut stream| {
+                    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+                    respond(&mut stream, &metrics)
+                });
+                if let Err(err) = result {
+                    trace!("[metrics_t] err:{err:?}");
+                }
+            }
+        })?;
+    Ok((local_addr, handle))
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    fn metrics() -> Metrics {
+        let mut rates = StatsSnapshot {
+            values_per_sec: 12,
+            total_values: 340,
+            ..StatsSnapshot::default()
+        };
+        rates.totals[PacketKind::PullResponse as usize] = 7;
+        Metrics {
+            nodes: 3,
+            rates,
+            rtts: vec![Duration::from_millis(20), Duration::from_millis(300)],
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let text = metrics().render();
+        assert!(text.contains("\ngossip_nodes 3\n"));
+        assert!(text.contains("gossip_packets_total{kind=\"pull_resp\"} 7\n"));
+        assert!(text.contains("gossip_packets_total{kind=\"ping\"} 0\n"));
+        assert!(text.contains("gossip_values_total 340\n"));
+        assert!(text.contains("gossip_values_per_second 12\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_bucket{le=\"0.01\"} 0\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_bucket{le=\"0.025\"} 1\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_bucket{le=\"0.5\"} 2\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_sum 0.32\n"));
+        assert!(text.contains("gossip_peer_rtt_seconds_count 2\n"));
+    }
+
+    #[test]
+    fn test_metrics_server() {
+        let shared = Arc::new(Mutex::new(metrics()));
+        let (addr, _handle) = spawn_metrics_server("127.0.0.1:0".parse().unwrap(), shared).unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&metrics().render()));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
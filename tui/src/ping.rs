@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use solana_gossip_proto::protocol::{Ping, Pong};
+use solana_sdk::pubkey::Pubkey;
+
+/// Pings not answered within this time mark the peer as unresponsive.
+pub const PING_TIMEOUT: Duration = Duration::from_secs(10);
+/// The peers are pinged again this long after the last ping, refreshing their
+/// round-trip time and giving the unresponsive ones another chance.
+pub const PING_INTERVAL: Duration = Duration::from_mins(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerStatus {
+    /// answered the ping, with the measured round-trip time
+    Alive(Duration),
+    Unresponsive,
+}
+
+impl fmt::Display for PeerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PeerStatus::Alive(rtt) => write!(f, "{}ms", rtt.as_millis()),
+            PeerStatus::Unresponsive => write!(f, "✗"),
+        }
+    }
+}
+
+/// Pings sent to the peers, matched against the received pongs.
+#[derive(Debug)]
+pub struct PingTracker {
+    pending: HashMap<Pubkey, (Ping, SocketAddr, Instant)>,
+    status: HashMap<Pubkey, PeerStatus>,
+    /// address the last answered ping was sent to
+    verified: HashMap<Pubkey, SocketAddr>,
+    /// when the last ping was sent
+    pinged_at: HashMap<Pubkey, Instant>,
+    timeout: Duration,
+    interval: Duration,
+}
+
+impl Default for PingTracker {
+    fn default() -> Self {
+        PingTracker::new(PING_TIMEOUT, PING_INTERVAL)
+    }
+}
+
+impl PingTracker {
+    pub fn new(timeout: Duration, interval: Duration) -> Self {
+        PingTracker {
+            pending: HashMap::new(),
+            status: HashMap::new(),
+            verified: HashMap::new(),
+            pinged_at: HashMap::new(),
+            timeout,
+            interval,
+        }
+    }
+
+    /// Whether the peer was never pinged, or not for `interval` and no ping is pending.
+    pub fn should_ping(&self, peer: &Pubkey, now: Instant) -> bool {
+        !self.pending.contains_key(peer)
+            && self
+                .pinged_at
+                .get(peer)
+                .is_none_or(|at| now.saturating_duration_since(*at) >= self.interval)
+    }
+
+    pub fn record_ping(&mut self, peer: Pubkey, addr: SocketAddr, ping: Ping, now: Instant) {
+        self.pending.insert(peer, (ping, addr, now));
+        self.pinged_at.insert(peer, now);
+    }
+
+    /// Mark the sender alive when the pong answers its pending ping, also when
+    /// it was unresponsive to an earlier one, returns the peer and the round-trip time.
+    pub fn record_pong(&mut self, pong: &Pong, now: Instant) -> Option<(Pubkey, Duration)> {
+        let peer = pong.from();
+        let (sent, addr, sent_at) = self.pending.get(&peer)?;
+        if !pong.verify_for_ping(sent) {
+            return None;
+        }
+
+        let rtt = now.saturating_duration_since(*sent_at);
+        self.verified.insert(peer, *addr);
+        self.pending.remove(&peer);
+        self.status.insert(peer, PeerStatus::Alive(rtt));
+        Some((peer, rtt))
+    }
+
+    /// Mark the peers whose ping timed out as unresponsive, returns them.
+    pub fn expire(&mut self, now: Instant) -> Vec<Pubkey> {
+        let timeout = self.timeout;
+        let expired = self
+            .pending
+            .iter()
+            .filter(|(_, (_, _, sent_at))| now.saturating_duration_since(*sent_at) >= timeout)
+            .map(|(peer, _)| *peer)
+            .collect::<Vec<_>>();
+        for peer in &expired {
+            self.pending.remove(peer);
+            self.verified.remove(peer);
+            self.status.insert(*peer, PeerStatus::Unresponsive);
+        }
+        expired
+    }
+
+    /// Outcome of the ping, none while it is pending or never sent.
+    pub fn status(&self, peer: &Pubkey) -> Option<PeerStatus> {
+        self.status.get(peer).copied()
+    }
+
+    /// Whether the peer answered the ping sent to `addr`, which proves that it
+    /// receives there and that answering it cannot reflect traffic to a victim.
+    pub fn is_verified(&self, peer: &Pubkey, addr: SocketAddr) -> bool {
+        self.verified.get(peer) == Some(&addr)
+    }
+
+    /// Round-trip time of the peer which answered the ping.
+    pub fn rtt(&self, peer: &Pubkey) -> Option<Duration> {
+        match self.status.get(peer) {
+            Some(PeerStatus::Alive(rtt)) => Some(*rtt),
+            _ => None,
+        }
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+
+    #[test]
+    fn test_ping_tracker() {
+        let start = Instant::now();
+        let mut tracker = PingTracker::new(PING_TIMEOUT, PING_INTERVAL);
+
+        let me = Keypair::new();
+        let alive = Keypair::new();
+        let silent = Keypair::new();
+        assert!(tracker.should_ping(&alive.pubkey(), start));
+
+        let addr: SocketAddr = "10.0.0.1:8001".parse().unwrap();
+        let sent = Ping::new(&me);
+        tracker.record_ping(alive.pubkey(), addr, sent.clone(), start);
+        tracker.record_ping(silent.pubkey(), addr, Ping::new(&me), start);
+        assert!(!tracker.should_ping(&alive.pubkey(), start));
+
+        // a pong answering another ping is ignored
+        let other = Pong::new(&Ping::new(&me), &alive).unwrap();
+        assert_eq!(tracker.record_pong(&other, start), None);
+
+        let answer = Pong::new(&sent, &alive).unwrap();
+        let rtt = Duration::from_millis(42);
+        assert_eq!(
+            tracker.record_pong(&answer, start + rtt),
+            Some((alive.pubkey(), rtt))
+        );
+        assert_eq!(tracker.rtt(&alive.pubkey()), Some(rtt));
+        assert!(tracker.is_verified(&alive.pubkey(), addr));
+        assert!(!tracker.is_verified(&alive.pubkey(), "10.0.0.2:8001".parse().unwrap()));
+        assert!(!tracker.is_verified(&silent.pubkey(), addr));
+        assert_eq!(PeerStatus::Alive(rtt).to_string(), "42ms");
+
+        assert!(tracker.expire(start + Duration::from_secs(1)).is_empty());
+        assert_eq!(tracker.expire(start + PING_TIMEOUT), vec![silent.pubkey()]);
+        assert_eq!(tracker.rtt(&silent.pubkey()), None);
+        assert!(!tracker.should_ping(&silent.pubkey(), start + PING_TIMEOUT));
+        assert!(!tracker.should_ping(&alive.pubkey(), start + PING_TIMEOUT));
+
+        // both are pinged again after the interval, a pong clears the unresponsive
+        let later = start + PING_INTERVAL;
+        assert!(tracker.should_ping(&alive.pubkey(), later));
+        assert!(tracker.should_ping(&silent.pubkey(), later));
+        let again = Ping::new(&me);
+        tracker.record_ping(silent.pubkey(), addr, again.clone(), later);
+        assert!(!tracker.should_ping(&silent.pubkey(), later));
+        assert_eq!(
+            tracker.status(&silent.pubkey()),
+            Some(PeerStatus::Unresponsive)
+        );
+        let answer = Pong::new(&again, &silent).unwrap();
+        assert_eq!(
+            tracker.record_pong(&answer, later + rtt),
+            Some((silent.pubkey(), rtt))
+        );
+        assert_eq!(
+            tracker.status(&silent.pubkey()),
+            Some(PeerStatus::Alive(rtt))
+        );
+        assert!(tracker.is_verified(&silent.pubkey(), addr));
+    }
+}
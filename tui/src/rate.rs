@@ -0,0 +1,237 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use solana_gossip_proto::protocol::Protocol;
+
+/// Length of the window the rates are computed over.
+pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+const PACKET_KINDS: usize = 6;
+
+/// Kind of a received `Protocol` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketKind {
+    PullRequest,
+    PullResponse,
+    PushMessage,
+    Prune,
+    Ping,
+    Pong,
+}
+
+impl PacketKind {
+    pub const ALL: [PacketKind; PACKET_KINDS] = [
+        PacketKind::PullRequest,
+        PacketKind::PullResponse,
+        PacketKind::PushMessage,
+        PacketKind::Prune,
+        PacketKind::Ping,
+        PacketKind::Pong,
+    ];
+
+    pub fn of(protocol: &Protocol) -> Self {
+        match protocol {
+            Protocol::PullRequest(..) => PacketKind::PullRequest,
+            Protocol::PullResponse(..) => PacketKind::PullResponse,
+            Protocol::PushMessage(..) => PacketKind::PushMessage,
+            Protocol::PruneMessage(..) => PacketKind::Prune,
+            Protocol::PingMessage(..) => PacketKind::Ping,
+            Protocol::PongMessage(..) => PacketKind::Pong,
+        }
+    }
+}
+
+impl fmt::Display for PacketKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PacketKind::PullRequest => write!(f, "pull_req"),
+            PacketKind::PullResponse => write!(f, "pull_resp"),
+            PacketKind::PushMessage => write!(f, "push"),
+            PacketKind::Prune => write!(f, "prune"),
+            PacketKind::Ping => write!(f, "ping"),
+            PacketKind::Pong => write!(f, "pong"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Counters {
+    packets: [u64; PACKET_KINDS],
+    values: u64,
+}
+
+/// Rates over the last complete window and the totals since the start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    pub packets: [u64; PACKET_KINDS],
+    pub values_per_sec: u64,
+    pub total_packets: u64,
+    pub total_values: u64,
+    /// packets received since the start, by kind
+    pub totals: [u64; PACKET_KINDS],
+}
+
+impl StatsSnapshot {
+    pub fn packets_per_sec(&self) -> u64 {
+        self.packets.iter().sum()
+    }
+
+    pub fn packets_of(&self, kind: PacketKind) -> u64 {
+        self.packets[kind as usize]
+    }
+}
+
+impl fmt::Display for StatsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "packets/s:{} values/s:{}",
+            self.packets_per_sec(),
+            self.values_per_sec
+        )?;
+        for kind in PacketKind::ALL {
+            write!(f, " {kind}:{}", self.packets_of(kind))?;
+        }
+        Ok(())
+    }
+}
+
+/// Counters of the received messages, by kind, over a rolling one second window.
+#[derive(Debug)]
+pub struct Stats {
+    window_start: Instant,
+    current: Counters,
+    last: Counters,
+    total: Counters,
+}
+
+impl Stats {
+    pub fn new(now: Instant) -> Self {
+        Stats {
+            window_start: now,
+            current: Counters::default(),
+            last: Counters::default(),
+            total: Counters::default(),
+        }
+    }
+
+    /// Count the message, and the CRDS values it carries.
+    pub fn record(&mut self, protocol: &Protocol, now: Instant) {
+        self.tick(now);
+
+        let values = match protocol {
+            Protocol::PullResponse(_, values) | Protocol::PushMessage(_, values) => values.len(),
+            _ => 0,
+        } as u64;
+
+        for counters in [&mut self.current, &mut self.total] {
+            counters.packets[PacketKind::of(protocol) as usize] += 1;
+            counters.values += values;
+        }
+    }
+
+    /// Close the window once it elapsed, returns true when a new one started.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < RATE_WINDOW {
+            return false;
+        }
+
+        // nothing was received in the windows skipped while idle
+        self.last = if elapsed < RATE_WINDOW * 2 {
+            self.current
+        } else {
+            Counters::default()
+        };
+        self.current = Counters::default();
+        self.window_start = now;
+        true
+    }
+
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            packets: self.last.packets,
+            values_per_sec: self.last.values,
+            total_packets: self.total.packets.iter().sum(),
+            total_values: self.total.values,
+            totals: self.total.packets,
+        }
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use solana_gossip_proto::protocol::{CrdsData, CrdsValue, LegacyContactInfo, Ping, PruneData};
+    use solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+    };
+
+    use super::*;
+
+    fn create_push(values: usize) -> Protocol {
+        let keypair = Keypair::new();
+        let value = CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::default()), &keypair);
+        Protocol::PushMessage(Pubkey::new_unique(), vec![value; values])
+    }
+
+    #[test]
+    fn test_stats() {
+        let start = Instant::now();
+        let mut stats = Stats::new(start);
+
+        let ping = Protocol::PingMessage(Ping::new(&Keypair::new()));
+        stats.record(&ping, start);
+        stats.record(&create_push(3), start + Duration::from_millis(200));
+        stats.record(&create_push(2), start + Duration::from_millis(900));
+
+        // the window is still open
+        assert_eq!(stats.snapshot().packets_per_sec(), 0);
+        assert_eq!(stats.snapshot().total_packets, 3);
+        assert_eq!(stats.snapshot().total_values, 5);
+        assert_eq!(stats.snapshot().totals[PacketKind::PushMessage as usize], 2);
+
+        assert!(!stats.tick(start + Duration::from_millis(999)));
+        assert!(stats.tick(start + RATE_WINDOW));
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.packets_per_sec(), 3);
+        assert_eq!(snapshot.packets_of(PacketKind::Ping), 1);
+        assert_eq!(snapshot.packets_of(PacketKind::PushMessage), 2);
+        assert_eq!(snapshot.values_per_sec, 5);
+        assert_eq!(
+            snapshot.to_string(),
+            "packets/s:3 values/s:5 pull_req:0 pull_resp:0 push:2 prune:0 ping:1 pong:0"
+        );
+
+        // idle windows report no traffic
+        stats.record(&ping, start + Duration::from_millis(1_500));
+        assert!(stats.tick(start + Duration::from_secs(5)));
+        assert_eq!(stats.snapshot().packets_per_sec(), 0);
+        assert_eq!(stats.snapshot().total_packets, 4);
+    }
+
+    #[test]
+    fn test_packet_kind() {
+        let info = LegacyContactInfo::default();
+        let value =
+            CrdsValue::new_signed(CrdsData::LegacyContactInfo(Box::new(info)), &Keypair::new());
+        let protocol = Protocol::PullResponse(Pubkey::new_unique(), vec![value]);
+        assert_eq!(PacketKind::of(&protocol), PacketKind::PullResponse);
+        assert_eq!(PacketKind::of(&create_push(0)), PacketKind::PushMessage);
+        let from = Pubkey::new_unique();
+        let prune = PruneData {
+            pubkey: from,
+            prunes: vec![],
+            signature: Signature::default(),
+            destination: Pubkey::new_unique(),
+            wallclock: 0,
+        };
+        assert_eq!(
+            PacketKind::of(&Protocol::PruneMessage(from, prune)),
+            PacketKind::Prune
+        );
+    }
+}
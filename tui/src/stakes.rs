@@ -0,0 +1,69 @@
+use std::{collections::HashMap, fs, io, path::Path, str::FromStr};
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Activated stake of the nodes, in lamports, by identity pubkey.
+pub type Stakes = HashMap<Pubkey, u64>;
+
+/// Read the stakes file, one `PUBKEY STAKE` pair per line. The gossip values
+/// do not carry the stakes, they come from an external source, for instance
+/// the `solana validators` output reduced to the identity and stake columns.
+pub fn load(path: &Path) -> io::Result<Stakes> {
+    parse(&fs::read_to_string(path)?).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+/// The blank lines and the ones starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<Stakes, String> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            let mut fields = line.split_whitespace();
+            let (Some(pubkey), Some(stake), None) = (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(format!("line {number}: expected PUBKEY STAKE"));
+            };
+            let pubkey = Pubkey::from_str(pubkey).map_err(|err| format!("line {number}: {err}"))?;
+            let stake = stake
+                .parse::<u64>()
+                .map_err(|err| format!("line {number}: {err}"))?;
+            Ok((pubkey, stake))
+        })
+        .collect()
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let heavy = Pubkey::new_unique();
+        let light = Pubkey::new_unique();
+        let text = format!("# identity stake\n{heavy} 1000000\n\n  {light}\t42  \n");
+
+        let stakes = parse(&text).unwrap();
+        assert_eq!(stakes.len(), 2);
+        assert_eq!(stakes[&heavy], 1_000_000);
+        assert_eq!(stakes[&light], 42);
+
+        assert!(parse("").unwrap().is_empty());
+        assert_eq!(
+            parse(&format!("{heavy}\n")).unwrap_err(),
+            "line 1: expected PUBKEY STAKE"
+        );
+        assert!(parse(&format!("{heavy} lots\n"))
+            .unwrap_err()
+            .starts_with("line 1:"));
+        assert!(parse("# header\nnot-a-pubkey 1\n")
+            .unwrap_err()
+            .starts_with("line 2:"));
+    }
+}
@@ -12,6 +12,9 @@ pub enum States {
 
     EntrypointSelection,
     Home,
+    NodeDetail,
+    Versions,
+    DuplicateShreds,
     Help,
 }
 
@@ -29,17 +32,27 @@ trait State {
 
     fn ui<B: Backend>(&self, f: &mut Frame<B>, _ctx: &mut Context);
 
-    fn help_text(&self) -> &str {
-        r##"
+    fn help_text(&self) -> &'static str {
+        r"
     Help
-    "##
+    "
+    }
+
+    // states listing their keys in a table get them rendered in the help popup
+    // instead of the help text.
+    fn key_bindings(&self) -> &'static [keymap::KeyBinding] {
+        &[]
     }
 }
 
 pub(crate) mod events;
+pub(crate) mod keymap;
 mod state_debug;
+mod state_duplicate_shreds;
 mod state_entrypoint_selection;
 mod state_help;
 mod state_home;
+mod state_node_detail;
 mod state_unknown;
+mod state_versions;
 pub(crate) mod stm_main;
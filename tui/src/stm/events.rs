@@ -1,9 +1,19 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, MouseEventKind};
 
 #[allow(unused)]
 #[derive(Debug, Clone)]
 pub enum Event {
     Reset,
-    Key { key_code: KeyCode },
-    Debug { message: String },
+    Key {
+        key_code: KeyCode,
+    },
+    /// only sent with `--mouse`, at the terminal cell of the pointer
+    Mouse {
+        kind: MouseEventKind,
+        column: u16,
+        row: u16,
+    },
+    Debug {
+        message: String,
+    },
 }
@@ -0,0 +1,210 @@
+use crossterm::event::KeyCode;
+
+/// What a key does, the handlers match on it instead of the raw key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Back,
+    Connect,
+    Disconnect,
+    Previous,
+    Next,
+    PreviousPage,
+    NextPage,
+    First,
+    Last,
+    Deselect,
+    ShowDetail,
+    CycleSort,
+    ReverseSort,
+    Search,
+    Follow,
+    ExportCrds,
+    ExportNodes,
+    ShowVersions,
+    ShowDuplicateShreds,
+    FullHistory,
+    RpcOnly,
+    RoutableOnly,
+    TogglePause,
+    Help,
+    Debug,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    /// how the key is shown in the help
+    pub label: &'static str,
+    pub action: Action,
+    pub description: &'static str,
+}
+
+const fn bind(
+    key: KeyCode,
+    label: &'static str,
+    action: Action,
+    description: &'static str,
+) -> KeyBinding {
+    KeyBinding {
+        key,
+        label,
+        action,
+        description,
+    }
+}
+
+/// Keys of the home view, both listed by the help and dispatched by the handlers.
+pub const HOME_KEY_BINDINGS: &[KeyBinding] = &[
+    bind(KeyCode::Esc, "ESC", Action::Back, "clear search / back"),
+    bind(
+        KeyCode::Char('c'),
+        "c",
+        Action::Connect,
+        "connect to entrypoint",
+    ),
+    bind(
+        KeyCode::Char('d'),
+        "d",
+        Action::Disconnect,
+        "disconnect from entrypoint",
+    ),
+    bind(KeyCode::Up, "UP", Action::Previous, "previous node"),
+    bind(KeyCode::Down, "DOWN", Action::Next, "next node"),
+    bind(
+        KeyCode::PageUp,
+        "PGUP",
+        Action::PreviousPage,
+        "previous page",
+    ),
+    bind(KeyCode::PageDown, "PGDN", Action::NextPage, "next page"),
+    bind(KeyCode::Home, "HOME", Action::First, "first node"),
+    bind(KeyCode::End, "END", Action::Last, "last node"),
+    bind(KeyCode::Left, "LEFT", Action::Deselect, "deselect"),
+    bind(
+        KeyCode::Enter,
+        "ENTER",
+        Action::ShowDetail,
+        "show node detail",
+    ),
+    bind(
+        KeyCode::Char('s'),
+        "s",
+        Action::CycleSort,
+        "cycle sort column",
+    ),
+    bind(
+        KeyCode::Char('S'),
+        "S",
+        Action::ReverseSort,
+        "reverse sort order",
+    ),
+    bind(
+        KeyCode::Char('/'),
+        "/",
+        Action::Search,
+        "search by pubkey or IP",
+    ),
+    bind(
+        KeyCode::Char('F'),
+        "F",
+        Action::Follow,
+        "follow a node by pubkey",
+    ),
+    bind(
+        KeyCode::Char('e'),
+        "e",
+        Action::ExportCrds,
+        "export CRDS values to JSON",
+    ),
+    bind(
+        KeyCode::Char('x'),
+        "x",
+        Action::ExportNodes,
+        "export nodes to CSV",
+    ),
+    bind(
+        KeyCode::Char('v'),
+        "v",
+        Action::ShowVersions,
+        "show versions and clock skew",
+    ),
+    bind(
+        KeyCode::Char('p'),
+        "p",
+        Action::ShowDuplicateShreds,
+        "show shred proofs, bad peers",
+    ),
+    bind(
+        KeyCode::Char('f'),
+        "f",
+        Action::FullHistory,
+        "show only full history nodes",
+    ),
+    bind(
+        KeyCode::Char('r'),
+        "r",
+        Action::RpcOnly,
+        "show only nodes with RPC",
+    ),
+    bind(
+        KeyCode::Char('u'),
+        "u",
+        Action::RoutableOnly,
+        "hide nodes with private IPs",
+    ),
+    bind(
+        KeyCode::Char(' '),
+        "SPACE",
+        Action::TogglePause,
+        "pause/resume the updates",
+    ),
+    bind(KeyCode::Char('?'), "?", Action::Help, "show this help"),
+    bind(KeyCode::Char('D'), "D", Action::Debug, "show Debug"),
+    bind(KeyCode::Char('q'), "q", Action::Quit, "Quit program"),
+];
+
+/// Action bound to the key, if any.
+pub fn action_for(bindings: &[KeyBinding], key: KeyCode) -> Option<Action> {
+    bindings
+        .iter()
+        .find(|binding| binding.key == key)
+        .map(|binding| binding.action)
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_action_for() {
+        assert_eq!(
+            action_for(HOME_KEY_BINDINGS, KeyCode::Char('S')),
+            Some(Action::ReverseSort)
+        );
+        assert_eq!(
+            action_for(HOME_KEY_BINDINGS, KeyCode::Char(' ')),
+            Some(Action::TogglePause)
+        );
+        assert_eq!(action_for(HOME_KEY_BINDINGS, KeyCode::Char('*')), None);
+        assert_eq!(action_for(&[], KeyCode::Esc), None);
+    }
+
+    #[test]
+    fn test_home_key_bindings_unique() {
+        let keys = HOME_KEY_BINDINGS
+            .iter()
+            .map(|binding| binding.key)
+            .collect::<HashSet<_>>();
+        assert_eq!(keys.len(), HOME_KEY_BINDINGS.len());
+
+        let actions = HOME_KEY_BINDINGS
+            .iter()
+            .map(|binding| binding.action)
+            .collect::<HashSet<_>>();
+        assert_eq!(actions.len(), HOME_KEY_BINDINGS.len());
+    }
+}
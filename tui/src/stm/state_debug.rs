@@ -1,8 +1,25 @@
+use std::net::SocketAddr;
+
 use super::{events::Event, State, States};
-use crate::ui::core::{centered_rect, draw_box};
+use crate::ui::core::{centered_rect, draw_box, draw_paragraph};
 use crate::{app::Context, ui::list_stateful_widget::draw_stateful_list};
 use crossterm::event::KeyCode;
-use tui::{backend::Backend, Frame};
+use solana_gossip_proto::protocol::CrdsFilter;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    Frame,
+};
+
+fn pull_filter_line(peer: &SocketAddr, filter: &CrdsFilter) -> String {
+    format!(
+        "pull filter from {peer} mask:{:#018x} mask_bits:{} bits:{} fill:{:.1}%",
+        filter.mask,
+        filter.mask_bits,
+        filter.filter.bits.len(),
+        filter.fill_ratio() * 100.0
+    )
+}
 
 pub struct DebugState;
 
@@ -38,7 +55,16 @@ impl State for DebugState {
         let size = f.size();
         draw_box(f, size, " Debug State ");
 
-        let bbox = centered_rect(95, 90, size);
+        let mut bbox = centered_rect(95, 90, size);
+        // the last received bloom filter above the messages
+        if let Some((peer, filter)) = &ctx.model.pull_filter {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+                .split(bbox);
+            draw_paragraph(f, chunks[0], &pull_filter_line(peer, filter));
+            bbox = chunks[1];
+        }
         draw_stateful_list(
             f,
             bbox,
@@ -113,6 +139,20 @@ mod tests {
         terminal.backend().assert_buffer(&expected);
     }
 
+    #[test]
+    fn test_pull_filter_line() {
+        let peer: SocketAddr = "1.2.3.4:8001".parse().unwrap();
+        let filter = CrdsFilter::new(10_000, 0.1, 7424);
+        let line = pull_filter_line(&peer, &filter);
+        assert_eq!(
+            line,
+            format!(
+                "pull filter from 1.2.3.4:8001 mask:0x1fffffffffffffff mask_bits:3 bits:{} fill:0.0%",
+                filter.filter.bits.len()
+            )
+        );
+    }
+
     #[test]
     fn test_state_help() -> Result<(), String> {
         let state = DebugState;
@@ -0,0 +1,110 @@
+use super::{events::Event, State, States};
+use crate::app::Context;
+use crate::ui::bad_peers_widget::render_bad_peers;
+use crate::ui::core::{centered_rect, draw_box};
+use crate::ui::duplicate_shred_widget::render_duplicate_shreds;
+use crossterm::event::KeyCode;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    Frame,
+};
+
+pub struct DuplicateShredsState;
+
+impl State for DuplicateShredsState {
+    fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
+        if let Event::Key {
+            key_code: KeyCode::Esc,
+        } = event
+        {
+            Some(States::PreviousOne)
+        } else {
+            ctx.debug(format!("[DuplicateShredsS] on_event {event:?} not match"));
+            None
+        }
+    }
+
+    fn ui<B: Backend>(&self, f: &mut Frame<B>, ctx: &mut Context) {
+        let size = f.size();
+        draw_box(f, size, " Duplicate Shred Proofs and Bad Peers ");
+
+        let bbox = centered_rect(95, 90, size);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(bbox);
+        render_duplicate_shreds(f, chunks[0], &ctx.model.duplicate_shreds);
+        render_bad_peers(f, chunks[1], &ctx.model.verification);
+    }
+
+    fn help_text(&self) -> &'static str {
+        r"
+        Nodes gossiping duplicate slot proofs, by accused slot.
+        A partial proof is dropped if it is not completed in 5 minutes.
+        Below, the origins of the values failing their signature.
+
+        ESC    -> back
+
+        D      -> show Debug
+        q      -> Quit program
+        "
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::core::screen;
+    use crossterm::event::KeyCode;
+    use solana_gossip_proto::protocol::{DuplicateShred, ShredType};
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_duplicate_shreds_state() {
+        let mut ctx = Context::new_for_testing();
+        let mut state = DuplicateShredsState;
+
+        let event = Event::Key {
+            key_code: KeyCode::Char('*'),
+        };
+        assert_eq!(state.on_event(event, &mut ctx), None);
+
+        let event = Event::Key {
+            key_code: KeyCode::Esc,
+        };
+        assert_eq!(state.on_event(event, &mut ctx), Some(States::PreviousOne));
+    }
+
+    #[test]
+    fn test_ui() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ctx = Context::new_for_testing();
+        ctx.model.duplicate_shreds.insert(
+            &DuplicateShred::new(
+                Pubkey::new_unique(),
+                0,
+                193_221_345,
+                ShredType::Data,
+                1,
+                0,
+                vec![],
+            ),
+            0,
+        );
+        let origin = Pubkey::new_unique();
+        ctx.model.verification.record_failure(origin);
+
+        let state = DuplicateShredsState;
+        terminal.draw(|f| state.ui(f, &mut ctx)).unwrap();
+
+        let screen = screen(terminal.backend().buffer());
+        assert!(screen.contains(" Duplicate Shreds [1 slots] "));
+        assert!(screen.contains("193221345"));
+        assert!(screen.contains("complete"));
+        assert!(screen.contains(" Peers sending invalid signatures [1 peers] "));
+        assert!(screen.contains(&origin.to_string()));
+    }
+}
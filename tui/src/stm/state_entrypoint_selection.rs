@@ -24,7 +24,7 @@ impl State for EntrypointSelectionState {
         self.on_enter_first = false;
 
         for s in &ctx.model.entrypoints {
-            ctx.model.entrypoints_stateful.push(s.to_string());
+            ctx.model.entrypoints_stateful.push(s.clone());
         }
     }
 
@@ -85,8 +85,8 @@ impl State for EntrypointSelectionState {
         );
     }
 
-    fn help_text(&self) -> &str {
-        r##"
+    fn help_text(&self) -> &'static str {
+        r"
         ESC    -> back
 
         UP     -> previous
@@ -96,7 +96,7 @@ impl State for EntrypointSelectionState {
 
         D      -> show Debug
         q      -> Quit program
-        "##
+        "
     }
 }
 
@@ -1,16 +1,13 @@
 use super::{events::Event, State, States};
 use crate::app::Context;
-use crossterm::event::KeyCode;
 use tui::{backend::Backend, Frame};
 
 pub struct HelpState;
 
 impl State for HelpState {
+    // any key closes the help
     fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
-        if let Event::Key {
-            key_code: KeyCode::Esc,
-        } = event
-        {
+        if let Event::Key { .. } = event {
             Some(States::PreviousOne)
         } else {
             ctx.debug(format!("[HelpS] on_event {event:?} not match"));
@@ -36,6 +33,12 @@ mod tests {
             key_code: KeyCode::Char('*'),
         };
         let to_state = help.on_event(event, &mut ctx);
+        assert_eq!(to_state, Some(States::PreviousOne));
+
+        let event = Event::Debug {
+            message: String::from("ignored"),
+        };
+        let to_state = help.on_event(event, &mut ctx);
         assert_eq!(to_state, None);
 
         Ok(())
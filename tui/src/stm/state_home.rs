@@ -1,49 +1,349 @@
-use super::{events::Event, State, States};
-use crate::ui::core::draw_box;
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use super::{
+    events::Event,
+    keymap::{action_for, Action, KeyBinding, HOME_KEY_BINDINGS},
+    State, States,
+};
+use crate::export::export_nodes_csv;
+use crate::ui::core::{draw_box, short_pubkey};
+use crate::ui::crds_counts_widget::render_crds_counts;
+use crate::ui::identity_widget::render_identity;
 use crate::ui::list_stateful_widget::draw_stateful_list;
-use crate::ui::table_stateful_widget::draw_stateful_table;
-use crate::{app::Context, ui::core::layout_columns_70_30};
-use crossterm::event::KeyCode;
-use tui::{backend::Backend, layout::Constraint, Frame};
+use crate::ui::node_table_widget::{render_node_table, StatefulNodeTable};
+use crate::ui::shred_version_widget::render_shred_versions;
+use crate::ui::status_bar_widget::{render_status_bar, AppState};
+use crate::{
+    app::{Context, InputMode, Model},
+    ui::core::layout_columns_70_30,
+};
+use crossterm::event::{KeyCode, MouseButton, MouseEventKind};
+use solana_gossip_proto::{protocol::shred_version_counts, utils::since_the_epoch_millis};
+use solana_sdk::pubkey::Pubkey;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Paragraph, Sparkline},
+    Frame,
+};
+
+/// Two clicks on the same row within this interval open its detail.
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
 
 pub struct HomeState {
     pub on_enter_first: bool,
+    /// row and time of the last click, to detect the double clicks
+    last_click: Option<(usize, Instant)>,
 }
 
 impl Default for HomeState {
     fn default() -> Self {
         Self {
             on_enter_first: true,
+            last_click: None,
         }
     }
 }
 
-impl HomeState {}
+impl HomeState {
+    fn export_nodes_csv(ctx: &mut Context) {
+        let path = ctx
+            .config
+            .export_dir
+            .join(format!("nodes-{}.csv", since_the_epoch_millis()));
+        let nodes = &ctx.model.home_node_table.rows;
+        match export_nodes_csv(nodes, &path) {
+            Ok(()) => ctx.debug(format!("[HomeS] nodes exported to {}", path.display())),
+            Err(err) => ctx.debug(format!("[HomeS] nodes export failed err:{err}")),
+        }
+    }
 
-impl State for HomeState {
-    fn on_enter_once(&mut self, _ctx: &mut Context) {
-        self.on_enter_first = false;
+    fn export_crds_json(ctx: &mut Context) {
+        let path = ctx
+            .config
+            .export_dir
+            .join(format!("crds-{}.json", since_the_epoch_millis()));
+        match ctx.model.crds.export_json(&path) {
+            Ok(()) => ctx.debug(format!("[HomeS] crds exported to {}", path.display())),
+            Err(err) => ctx.debug(format!("[HomeS] crds export failed err:{err}")),
+        }
     }
 
-    fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
+    /// Title of the nodes table, with the active sort, search and filters.
+    fn table_title(model: &Model) -> String {
+        let table = &model.home_node_table;
+        let search = if model.input_mode == InputMode::Editing {
+            format!(" search:/{}_", table.filter)
+        } else if table.filter.is_empty() {
+            String::new()
+        } else {
+            format!(" search:/{}", table.filter)
+        };
+        let follow = match (model.input_mode, &model.follow_error) {
+            (InputMode::Following, None) => format!(" follow:{}_", model.follow_input),
+            (InputMode::Following, Some(err)) => {
+                format!(" follow:{}_ ({err})", model.follow_input)
+            }
+            _ => table
+                .followed
+                .map_or_else(String::new, |id| format!(" follow:{}", short_pubkey(&id))),
+        };
+        let full_history = table
+            .full_history_below
+            .map_or_else(String::new, |below| format!(" lowest<{below}"));
+        let rpc_only = if table.rpc_only { " rpc" } else { "" };
+        let routable_only = if table.routable_only { " public" } else { "" };
+        format!(
+            "Nodes Info [{:?}] sort:{} {}{search}{follow}{full_history}{rpc_only}{routable_only}",
+            model.entrypoint,
+            table.sort_key,
+            if table.sort_descending { "▼" } else { "▲" }
+        )
+    }
+
+    fn show_detail(ctx: &mut Context) -> Option<States> {
+        let node = ctx.model.home_node_table.selected()?;
+        ctx.model.detail_node = Some(node.info.id);
+        ctx.model.detail_field = 0;
+        ctx.model.detail_hex = None;
+        Some(States::NodeDetail)
+    }
+
+    /// A click selects the row under the pointer and a second click on the same
+    /// row opens its detail, the wheel moves the selection.
+    fn on_mouse_event(
+        &mut self,
+        kind: MouseEventKind,
+        column: u16,
+        row: u16,
+        ctx: &mut Context,
+    ) -> Option<States> {
+        let table = &mut ctx.model.home_node_table;
+        match kind {
+            MouseEventKind::ScrollDown => table.scroll_down(),
+            MouseEventKind::ScrollUp => table.scroll_up(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let i = table.row_at(column, row)?;
+                table.state.select(Some(i));
+                let now = Instant::now();
+                let double = self
+                    .last_click
+                    .is_some_and(|(last, at)| last == i && now - at <= DOUBLE_CLICK);
+                if double {
+                    self.last_click = None;
+                    return HomeState::show_detail(ctx);
+                }
+                self.last_click = Some((i, now));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Move the selection of the nodes table, false for the other actions.
+    fn on_navigation_action(action: Action, table: &mut StatefulNodeTable) -> bool {
+        match action {
+            Action::Next => table.next_row(),
+            Action::Previous => table.previous_row(),
+            Action::NextPage => table.next_page(),
+            Action::PreviousPage => table.previous_page(),
+            Action::First => table.first_row(),
+            Action::Last => table.last_row(),
+            Action::Deselect => table.unselect(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn on_edit_event(event: &Event, ctx: &mut Context) {
+        let table = &mut ctx.model.home_node_table;
         match event {
             Event::Key {
-                key_code: KeyCode::Down,
+                key_code: KeyCode::Char(c),
             } => {
-                ctx.model.home_stateful_table.next_row();
-                None
+                let mut query = table.filter.clone();
+                query.push(*c);
+                table.set_filter(query);
             }
             Event::Key {
-                key_code: KeyCode::Up,
+                key_code: KeyCode::Backspace,
             } => {
-                ctx.model.home_stateful_table.previous_row();
-                None
+                let mut query = table.filter.clone();
+                query.pop();
+                table.set_filter(query);
+            }
+            Event::Key {
+                key_code: KeyCode::Enter,
+            } => {
+                ctx.model.input_mode = InputMode::Normal;
+            }
+            Event::Key {
+                key_code: KeyCode::Esc,
+            } => {
+                table.set_filter(String::new());
+                ctx.model.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Cluster tip, duplicate instances warning and traffic rates.
+    fn header_spans(model: &Model) -> Spans<'static> {
+        let tip = model
+            .cluster_tip
+            .map_or_else(|| String::from("—"), |slot| slot.to_string());
+        let mut spans = vec![Span::styled(
+            format!(" tip {tip} "),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )];
+        // an identity running twice risks double signing
+        if !model.instance_conflicts.is_empty() {
+            let pubkeys = model
+                .instance_conflicts
+                .iter()
+                .map(short_pubkey)
+                .collect::<Vec<_>>();
+            spans.push(Span::styled(
+                format!(" DUPLICATE INSTANCES {} ", pubkeys.join(",")),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK),
+            ));
+        }
+        spans.push(Span::raw(format!(" {}", model.rates)));
+        Spans::from(spans)
+    }
+
+    fn on_follow_event(event: &Event, ctx: &mut Context) {
+        let model = &mut ctx.model;
+        match event {
+            Event::Key {
+                key_code: KeyCode::Char(c),
+            } => {
+                model.follow_input.push(*c);
+                model.follow_error = None;
+            }
+            Event::Key {
+                key_code: KeyCode::Backspace,
+            } => {
+                model.follow_input.pop();
+                model.follow_error = None;
+            }
+            Event::Key {
+                key_code: KeyCode::Enter,
+            } => {
+                let input = model.follow_input.trim();
+                if input.is_empty() {
+                    model.home_node_table.unfollow();
+                    model.input_mode = InputMode::Normal;
+                    return;
+                }
+                match Pubkey::from_str(input) {
+                    Ok(id) if model.home_node_table.follow(id) => {
+                        model.follow_input.clear();
+                        model.input_mode = InputMode::Normal;
+                    }
+                    Ok(_) => model.follow_error = Some(String::from("unknown node")),
+                    Err(err) => model.follow_error = Some(err.to_string()),
+                }
             }
             Event::Key {
-                key_code: KeyCode::Left,
+                key_code: KeyCode::Esc,
             } => {
-                ctx.model.home_stateful_table.unselect();
+                model.follow_input.clear();
+                model.follow_error = None;
+                model.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl State for HomeState {
+    fn on_enter_once(&mut self, _ctx: &mut Context) {
+        self.on_enter_first = false;
+    }
 
+    fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
+        match ctx.model.input_mode {
+            InputMode::Editing => {
+                HomeState::on_edit_event(&event, ctx);
+                return None;
+            }
+            InputMode::Following => {
+                HomeState::on_follow_event(&event, ctx);
+                return None;
+            }
+            InputMode::Normal => {}
+        }
+        if let Event::Mouse { kind, column, row } = event {
+            return self.on_mouse_event(kind, column, row, ctx);
+        }
+
+        let action = match &event {
+            Event::Key { key_code } => action_for(HOME_KEY_BINDINGS, *key_code),
+            _ => None,
+        };
+        if let Some(action) = action {
+            if HomeState::on_navigation_action(action, &mut ctx.model.home_node_table) {
+                return None;
+            }
+        }
+        match action {
+            Some(Action::ShowDetail) => HomeState::show_detail(ctx),
+            Some(Action::ExportCrds) => {
+                HomeState::export_crds_json(ctx);
+                None
+            }
+            Some(Action::ExportNodes) => {
+                HomeState::export_nodes_csv(ctx);
+                None
+            }
+            Some(Action::ShowVersions) => Some(States::Versions),
+            Some(Action::ShowDuplicateShreds) => Some(States::DuplicateShreds),
+            Some(Action::FullHistory) => {
+                ctx.model.home_node_table.toggle_full_history();
+                None
+            }
+            Some(Action::RpcOnly) => {
+                ctx.model.home_node_table.toggle_rpc_only();
+                None
+            }
+            Some(Action::RoutableOnly) => {
+                ctx.model.home_node_table.toggle_routable_only();
+                None
+            }
+            Some(Action::TogglePause) => {
+                ctx.model.paused = !ctx.model.paused;
+                None
+            }
+            Some(Action::Search) => {
+                ctx.model.input_mode = InputMode::Editing;
+                None
+            }
+            Some(Action::Follow) => {
+                ctx.model.input_mode = InputMode::Following;
+                None
+            }
+            Some(Action::Back) if !ctx.model.home_node_table.filter.is_empty() => {
+                ctx.model.home_node_table.set_filter(String::new());
+                None
+            }
+            Some(Action::CycleSort) => {
+                ctx.model.home_node_table.next_sort_key();
+                None
+            }
+            Some(Action::ReverseSort) => {
+                ctx.model.home_node_table.toggle_sort_order();
                 None
             }
             _ => {
@@ -54,81 +354,111 @@ impl State for HomeState {
     }
 
     fn ui<B: Backend>(&self, f: &mut Frame<B>, ctx: &mut Context) {
-        let size = f.size();
+        // connection status bar below the home box
+        let screen_bboxs = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(f.size());
+        let size = screen_bboxs[0];
         draw_box(f, size, " Solana Home ");
+        let status = AppState::new(&ctx.model, Instant::now());
+        render_status_bar(f, screen_bboxs[1], &status);
 
         let bboxs = layout_columns_70_30(size);
 
-        let title = format!("Nodes Info [{:?}]", ctx.model.entrypoint);
-
-        let withs = [
-            Constraint::Percentage(14),
-            Constraint::Percentage(6),
-            Constraint::Percentage(16),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-            Constraint::Percentage(6),
-        ];
-        draw_stateful_table(
+        let header = HomeState::header_spans(&ctx.model);
+        let title = HomeState::table_title(&ctx.model);
+        let table = &mut ctx.model.home_node_table;
+
+        // traffic rates header above the nodes, below our node when given
+        let identity_height = if ctx.config.identity.is_some() { 5 } else { 0 };
+        let main_bboxs = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(identity_height),
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(bboxs[0]);
+        if let Some(identity) = &ctx.config.identity {
+            let row = table.rows.iter().find(|row| row.info.id == *identity);
+            render_identity(f, main_bboxs[0], identity, row);
+        }
+        let header_bboxs = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Percentage(30)].as_ref())
+            .split(main_bboxs[1]);
+        let header = Paragraph::new(header);
+        f.render_widget(header, header_bboxs[0]);
+
+        // discovered nodes over time, the newest samples on the right
+        let samples = ctx.model.node_count_history.samples();
+        let width = usize::from(header_bboxs[1].width);
+        let sparkline = Sparkline::default()
+            .data(&samples[samples.len().saturating_sub(width)..])
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, header_bboxs[1]);
+
+        let rows = table.visible_rows();
+        let mut state = table.state.clone();
+        render_node_table(
             f,
-            bboxs[0],
+            main_bboxs[2],
             &title,
-            &[
-                "IP",
-                "Age(ms)",
-                "Node Identifier",
-                "Version",
-                "Gossip",
-                "TPUvote",
-                "TPU",
-                "TPUfwd",
-                "TVU",
-                "TVUfwd",
-                "Repair",
-                "ServeR",
-                "ShredVer",
-            ],
-            &withs,
-            &mut ctx.model.home_stateful_table,
+            &rows,
+            &mut state,
+            table.show_country,
+            ctx.config.color.then_some(&ctx.config.freshness),
         );
+        table.state = state;
+        table.set_render_area(main_bboxs[2]);
 
+        let infos = table
+            .rows
+            .iter()
+            .map(|row| row.info.clone())
+            .collect::<Vec<_>>();
+
+        // the three thread counters, the values held by kind and the shred versions
+        let side_bboxs = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(5),
+                    Constraint::Percentage(50),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(bboxs[1]);
         draw_stateful_list(
             f,
-            bboxs[1],
+            side_bboxs[0],
             " stats ",
             &mut ctx.model.home_stats_stateful_list,
             false,
         );
+        render_crds_counts(f, side_bboxs[1], &ctx.model.crds.counts_by_kind());
+        render_shred_versions(f, side_bboxs[2], &shred_version_counts(&infos));
     }
 
-    fn help_text(&self) -> &str {
-        r##"
-        ESC    -> back
-        c      -> connect to entrypoint
-        d      -> disconnect from entrypoint
-
-        UP     -> previous
-        DOWN   -> next
-        LEFT   -> deselect
-
-        D      -> show Debug
-        q      -> Quit program
-        "##
+    fn key_bindings(&self) -> &'static [KeyBinding] {
+        HOME_KEY_BINDINGS
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::core::screen;
+    use crate::ui::node_table_widget::SortKey;
     use crossterm::event::KeyCode;
-    use tui::{backend::TestBackend, buffer::Buffer, Terminal};
+    use solana_gossip_proto::protocol::LegacyContactInfo;
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, buffer::Buffer, layout::Rect, Terminal};
 
     #[test]
     fn test_home_state() -> Result<(), String> {
@@ -160,9 +490,214 @@ mod tests {
         let to_state = state.on_event(event, &mut ctx);
         assert_eq!(to_state, None);
 
+        let event = Event::Key {
+            key_code: KeyCode::Char('s'),
+        };
+        let to_state = state.on_event(event, &mut ctx);
+        assert_eq!(to_state, None);
+
+        // space toggles the pause
+        let event = Event::Key {
+            key_code: KeyCode::Char(' '),
+        };
+        state.on_event(event.clone(), &mut ctx);
+        assert!(ctx.model.paused);
+        state.on_event(event, &mut ctx);
+        assert!(!ctx.model.paused);
+        assert_eq!(ctx.model.home_node_table.sort_key, SortKey::Pubkey);
+
+        let event = Event::Key {
+            key_code: KeyCode::Char('S'),
+        };
+        let to_state = state.on_event(event, &mut ctx);
+        assert_eq!(to_state, None);
+        assert!(ctx.model.home_node_table.sort_descending);
+
         Ok(())
     }
 
+    #[test]
+    fn test_home_state_search() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+        let mut state = HomeState::default();
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        assert_eq!(send(KeyCode::Char('/')), None);
+        for c in "1.2x".chars() {
+            assert_eq!(send(KeyCode::Char(c)), None);
+        }
+        assert_eq!(send(KeyCode::Backspace), None);
+        assert_eq!(send(KeyCode::Enter), None);
+        assert_eq!(ctx.model.input_mode, InputMode::Normal);
+        assert_eq!(ctx.model.home_node_table.filter, "1.2");
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        assert_eq!(send(KeyCode::Esc), None);
+        assert_eq!(ctx.model.home_node_table.filter, "");
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Char('/'));
+        send(KeyCode::Char('q'));
+        assert_eq!(ctx.model.input_mode, InputMode::Editing);
+        assert_eq!(ctx.model.home_node_table.filter, "q");
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Esc);
+        assert_eq!(ctx.model.input_mode, InputMode::Normal);
+        assert_eq!(ctx.model.home_node_table.filter, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_spans() {
+        let mut ctx = Context::new_for_testing();
+        ctx.model.cluster_tip = Some(42);
+        let text = |spans: Spans| {
+            spans
+                .0
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect::<String>()
+        };
+        let header = text(HomeState::header_spans(&ctx.model));
+        assert!(header.starts_with(" tip 42 "));
+        assert!(!header.contains("DUPLICATE"));
+
+        let pubkey = Pubkey::new_unique();
+        ctx.model.instance_conflicts = vec![pubkey];
+        let header = HomeState::header_spans(&ctx.model);
+        assert_eq!(
+            header.0[1].content,
+            format!(" DUPLICATE INSTANCES {} ", short_pubkey(&pubkey))
+        );
+        assert_eq!(header.0[1].style.bg, Some(Color::Red));
+    }
+
+    fn type_follow(state: &mut HomeState, ctx: &mut Context, text: &str) {
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, ctx);
+        send(KeyCode::Char('F'));
+        for c in text.chars() {
+            send(KeyCode::Char(c));
+        }
+        send(KeyCode::Enter);
+    }
+
+    #[test]
+    fn test_home_state_follow() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+        let mut state = HomeState::default();
+        let id = Pubkey::new_unique();
+        for id in [Pubkey::new_unique(), id] {
+            ctx.model
+                .home_node_table
+                .upsert_contact_info(LegacyContactInfo {
+                    id,
+                    ..LegacyContactInfo::default()
+                });
+        }
+
+        type_follow(&mut state, &mut ctx, "0OIl");
+        assert_eq!(ctx.model.input_mode, InputMode::Following);
+        assert!(ctx.model.follow_error.is_some());
+        assert_eq!(ctx.model.home_node_table.followed, None);
+
+        let esc = Event::Key {
+            key_code: KeyCode::Esc,
+        };
+        state.on_event(esc.clone(), &mut ctx);
+        assert_eq!(ctx.model.input_mode, InputMode::Normal);
+        assert_eq!(ctx.model.follow_input, "");
+
+        type_follow(&mut state, &mut ctx, &Pubkey::new_unique().to_string());
+        assert_eq!(ctx.model.follow_error.as_deref(), Some("unknown node"));
+        state.on_event(esc, &mut ctx);
+
+        type_follow(&mut state, &mut ctx, &id.to_string());
+        assert_eq!(ctx.model.input_mode, InputMode::Normal);
+        assert_eq!(ctx.model.home_node_table.followed, Some(id));
+        assert_eq!(ctx.model.home_node_table.visible_rows()[0].info.id, id);
+        assert_eq!(
+            ctx.model.home_node_table.selected().map(|row| row.info.id),
+            Some(id)
+        );
+
+        // an empty pubkey stops following
+        type_follow(&mut state, &mut ctx, "");
+        assert_eq!(ctx.model.home_node_table.followed, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_home_state_node_detail() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+        let mut state = HomeState::default();
+
+        let event = Event::Key {
+            key_code: KeyCode::Enter,
+        };
+        assert_eq!(state.on_event(event.clone(), &mut ctx), None);
+
+        let id = Pubkey::new_unique();
+        ctx.model
+            .home_node_table
+            .upsert_contact_info(LegacyContactInfo {
+                id,
+                ..LegacyContactInfo::default()
+            });
+        ctx.model.home_node_table.next_row();
+
+        assert_eq!(state.on_event(event, &mut ctx), Some(States::NodeDetail));
+        assert_eq!(ctx.model.detail_node, Some(id));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_home_state_mouse() {
+        let mut ctx = Context::new_for_testing();
+        let mut state = HomeState::default();
+        let ids = [Pubkey::new_unique(), Pubkey::new_unique()];
+        for id in ids {
+            ctx.model
+                .home_node_table
+                .upsert_contact_info(LegacyContactInfo {
+                    id,
+                    ..LegacyContactInfo::default()
+                });
+        }
+        ctx.model
+            .home_node_table
+            .set_render_area(Rect::new(0, 0, 80, 10));
+        let click = |row| Event::Mouse {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 10,
+            row,
+        };
+
+        // the header is not a row
+        assert_eq!(state.on_event(click(2), &mut ctx), None);
+        assert_eq!(ctx.model.home_node_table.state.selected(), None);
+
+        assert_eq!(state.on_event(click(4), &mut ctx), None);
+        assert_eq!(ctx.model.home_node_table.state.selected(), Some(1));
+        // a click on another row only selects it
+        assert_eq!(state.on_event(click(3), &mut ctx), None);
+        assert_eq!(ctx.model.home_node_table.state.selected(), Some(0));
+        assert_eq!(state.on_event(click(3), &mut ctx), Some(States::NodeDetail));
+        let selected = ctx.model.home_node_table.selected().unwrap().info.id;
+        assert_eq!(ctx.model.detail_node, Some(selected));
+
+        let wheel = Event::Mouse {
+            kind: MouseEventKind::ScrollDown,
+            column: 0,
+            row: 0,
+        };
+        assert_eq!(state.on_event(wheel, &mut ctx), None);
+        assert_eq!(ctx.model.home_node_table.state.selected(), Some(1));
+    }
+
     #[test]
     fn test_ui() {
         let backend = TestBackend::new(7, 4);
@@ -178,21 +713,48 @@ mod tests {
             .unwrap();
 
         #[rustfmt::skip]
-    let expected = Buffer::with_lines(vec![
+    let mut expected = Buffer::with_lines(vec![
       " Sola─╮",
-      "│ ┌┐┌ │",
-      "│ └┘└ │",
-      "╰─────╯"
+      "│   ┌ │",
+      "╰─────╯",
+      " — [dis"
       ]);
+        // the cluster tip leads the header
+        expected.set_style(
+            Rect::new(2, 1, 1, 1),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+        // the connection state is colored
+        expected.set_style(Rect::new(3, 3, 4, 1), Style::default().fg(Color::DarkGray));
 
         terminal.backend().assert_buffer(&expected);
     }
 
     #[test]
-    fn test_state_help() -> Result<(), String> {
+    fn test_ui_identity() {
+        let backend = TestBackend::new(160, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ctx = Context::new_for_testing();
+        let identity = Pubkey::new_unique();
+        ctx.config.identity = Some(identity);
+
         let state = HomeState::default();
-        assert_eq!(state.help_text().len(), 256);
+        terminal.draw(|f| state.ui(f, &mut ctx)).unwrap();
+        assert!(screen(terminal.backend().buffer()).contains(&format!(" my node {identity} ")));
+        assert!(screen(terminal.backend().buffer()).contains("not yet observed"));
 
-        Ok(())
+        ctx.model
+            .home_node_table
+            .upsert_contact_info(LegacyContactInfo {
+                id: identity,
+                shred_version: 50_093,
+                ..LegacyContactInfo::default()
+            });
+        terminal.draw(|f| state.ui(f, &mut ctx)).unwrap();
+        assert!(!screen(terminal.backend().buffer()).contains("not yet observed"));
+        assert!(screen(terminal.backend().buffer()).contains("shred_version 50093"));
     }
 }
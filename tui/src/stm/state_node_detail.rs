@@ -0,0 +1,417 @@
+use super::{events::Event, State, States};
+use crate::app::{Context, Model};
+use crate::ui::core::{centered_rect, draw_box, draw_paragraph, layout_rows_50_50};
+use crate::ui::epoch_slots_widget::{render_epoch_slots, SlotsCoverage};
+use crate::ui::hex_dump_widget::render_hex_dump;
+use crate::ui::node_detail_widget::{copyable_fields, render_node_detail};
+use crate::ui::snapshot_hashes_widget::{render_incremental_chain, render_snapshot_hashes};
+use crossterm::event::KeyCode;
+use solana_gossip_proto::protocol::{is_valid_addr, CrdsData, SnapshotHashesKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+/// Height of the node panel, the epoch slots panel takes the rest.
+const DETAIL_HEIGHT: u16 = 18;
+/// Lines scrolled by a page of the hex dump.
+const HEX_PAGE: u16 = 8;
+
+pub struct NodeDetailState;
+
+impl NodeDetailState {
+    /// Copyable fields of the node shown, the pubkey and the service addresses.
+    fn fields(model: &Model) -> Vec<(&'static str, Option<String>)> {
+        model
+            .detail_node
+            .and_then(|id| {
+                model
+                    .home_node_table
+                    .rows
+                    .iter()
+                    .find(|row| row.info.id == id)
+            })
+            .map(|row| copyable_fields(&row.info))
+            .unwrap_or_default()
+    }
+
+    /// Move the field selection down or up, wrapping around.
+    fn move_field(model: &mut Model, down: bool) {
+        let count = NodeDetailState::fields(model).len().max(1);
+        model.detail_field = if down {
+            (model.detail_field + 1) % count
+        } else {
+            (model.detail_field + count - 1) % count
+        };
+    }
+
+    fn copy_selected_field(ctx: &mut Context) {
+        let value = NodeDetailState::fields(&ctx.model)
+            .into_iter()
+            .nth(ctx.model.detail_field)
+            .and_then(|(_, value)| value);
+
+        let message = match value {
+            Some(value) => match ctx.copy_to_clipboard(&value) {
+                Ok(()) => String::from("Copied"),
+                Err(err) => format!("Copy failed: {err}"),
+            },
+            None => String::from("Nothing to copy"),
+        };
+        ctx.model.status_message = Some(message);
+    }
+
+    /// Show the hex dump of the first value of the node, or hide it.
+    fn toggle_hex(ctx: &mut Context) {
+        let model = &mut ctx.model;
+        model.detail_hex = match model.detail_hex {
+            Some(_) => None,
+            None => Some(0),
+        };
+        model.detail_hex_scroll = 0;
+    }
+
+    /// Inspect the next, or with `back` the previous, value of the node.
+    fn cycle_hex(ctx: &mut Context, back: bool) {
+        let model = &mut ctx.model;
+        let (Some(index), Some(id)) = (model.detail_hex, model.detail_node) else {
+            return;
+        };
+        let count = model.crds.values_from(id).len().max(1);
+        model.detail_hex = Some(if back {
+            (index + count - 1) % count
+        } else {
+            (index + 1) % count
+        });
+        model.detail_hex_scroll = 0;
+    }
+
+    /// The bincode bytes of the inspected value, with its kind and hash in the title.
+    fn render_value_hex<B: Backend>(f: &mut Frame<B>, area: Rect, model: &Model, index: usize) {
+        let Some(id) = model.detail_node else {
+            return;
+        };
+        let values = model.crds.values_from(id);
+        let Some(value) = values.get(index) else {
+            draw_paragraph(f, area, "no value received from the node");
+            return;
+        };
+        match value.to_bytes() {
+            Ok(bytes) => {
+                let title = format!(
+                    " {value} {}/{} · {} bytes · hash {} ",
+                    index + 1,
+                    values.len(),
+                    bytes.len(),
+                    value.hash()
+                );
+                render_hex_dump(f, area, &title, &bytes, model.detail_hex_scroll);
+            }
+            Err(err) => draw_paragraph(f, area, &format!("serialization failed: {err}")),
+        }
+    }
+}
+
+impl State for NodeDetailState {
+    fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
+        match event {
+            Event::Key {
+                key_code: KeyCode::Esc,
+            } => {
+                ctx.model.status_message = None;
+                Some(States::PreviousOne)
+            }
+            Event::Key {
+                key_code: KeyCode::Down,
+            } => {
+                NodeDetailState::move_field(&mut ctx.model, true);
+                None
+            }
+            Event::Key {
+                key_code: KeyCode::Up,
+            } => {
+                NodeDetailState::move_field(&mut ctx.model, false);
+                None
+            }
+            Event::Key {
+                key_code: KeyCode::Char('c'),
+            } => {
+                NodeDetailState::copy_selected_field(ctx);
+                None
+            }
+            Event::Key {
+                key_code: KeyCode::Char('x'),
+            } => {
+                NodeDetailState::toggle_hex(ctx);
+                None
+            }
+            Event::Key {
+                key_code: code @ (KeyCode::Left | KeyCode::Right),
+            } => {
+                NodeDetailState::cycle_hex(ctx, code == KeyCode::Left);
+                None
+            }
+            Event::Key {
+                key_code: KeyCode::PageDown,
+            } => {
+                ctx.model.detail_hex_scroll = ctx.model.detail_hex_scroll.saturating_add(HEX_PAGE);
+                None
+            }
+            Event::Key {
+                key_code: KeyCode::PageUp,
+            } => {
+                ctx.model.detail_hex_scroll = ctx.model.detail_hex_scroll.saturating_sub(HEX_PAGE);
+                None
+            }
+            _ => {
+                ctx.debug(format!("[NodeDetailS] on_event {event:?} not match"));
+                None
+            }
+        }
+    }
+
+    fn ui<B: Backend>(&self, f: &mut Frame<B>, ctx: &mut Context) {
+        let size = f.size();
+        draw_box(f, size, " Node Detail ");
+
+        let bbox = centered_rect(95, 90, size);
+        let model = &mut ctx.model;
+        let node = model.detail_node.and_then(|id| {
+            model
+                .home_node_table
+                .rows
+                .iter()
+                .find(|row| row.info.id == id)
+        });
+
+        if let Some(node) = node {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(DETAIL_HEIGHT), Constraint::Min(0)].as_ref())
+                .split(bbox);
+
+            let hostname = model
+                .dns
+                .as_mut()
+                .filter(|_| is_valid_addr(&node.info.gossip))
+                .and_then(|dns| dns.get(node.info.gossip.ip()));
+            let pruned_at = model.pruned_by.get(&node.info.id).copied();
+            let selected = Some(model.detail_field);
+            render_node_detail(
+                f,
+                chunks[0],
+                &node.info,
+                node.version.as_ref(),
+                hostname,
+                pruned_at,
+                selected,
+            );
+            if let Some(index) = model.detail_hex {
+                NodeDetailState::render_value_hex(f, chunks[1], model, index);
+            } else {
+                let crds = &model.crds;
+                let coverage = model.slots_coverage.entry(node.info.id).or_insert_with(|| {
+                    SlotsCoverage::new(crds.values().filter_map(|value| match &value.data {
+                        CrdsData::EpochSlots(_, slots) if slots.from == node.info.id => Some(slots),
+                        _ => None,
+                    }))
+                });
+                // the slots and both kinds of hashes side by side, each in its own panel
+                let panels = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(
+                        [
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(35),
+                            Constraint::Percentage(35),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(chunks[1]);
+                render_epoch_slots(f, panels[0], coverage.as_ref());
+                // the full snapshots above the incremental chain built on them
+                let snapshot_panels = layout_rows_50_50(panels[1]);
+                render_incremental_chain(
+                    f,
+                    snapshot_panels[1],
+                    model.crds.incremental_snapshot_hashes(node.info.id),
+                );
+                for (kind, area) in [
+                    (SnapshotHashesKind::Snapshot, snapshot_panels[0]),
+                    (SnapshotHashesKind::Accounts, panels[2]),
+                ] {
+                    let hashes = model.crds.snapshot_hashes(node.info.id, kind);
+                    render_snapshot_hashes(f, area, kind, hashes);
+                }
+            }
+        } else {
+            draw_paragraph(f, bbox, "no node selected");
+        }
+
+        if let Some(message) = &ctx.model.status_message {
+            let status = Rect::new(bbox.x, bbox.bottom(), bbox.width, 1);
+            draw_paragraph(f, status, message);
+        }
+    }
+
+    fn help_text(&self) -> &'static str {
+        r"
+        ESC    -> back
+        UP     -> previous field
+        DOWN   -> next field
+        c      -> copy field to clipboard
+        x      -> show/hide the raw bytes of the node values
+        LEFT   -> previous value, in the raw bytes
+        RIGHT  -> next value, in the raw bytes
+        PGUP   -> scroll the raw bytes up
+        PGDN   -> scroll the raw bytes down
+
+        D      -> show Debug
+        q      -> Quit program
+        "
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::core::screen;
+    use crossterm::event::KeyCode;
+    use solana_gossip_proto::protocol::{CrdsValue, LegacyContactInfo};
+    use solana_sdk::{
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    };
+    use tui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_node_detail_state() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+
+        let mut state = NodeDetailState;
+
+        let event = Event::Key {
+            key_code: KeyCode::Char('*'),
+        };
+        let to_state = state.on_event(event, &mut ctx);
+        assert_eq!(to_state, None);
+
+        let event = Event::Key {
+            key_code: KeyCode::Esc,
+        };
+        let to_state = state.on_event(event, &mut ctx);
+        assert_eq!(to_state, Some(States::PreviousOne));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_node_detail_state_copy() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+        let mut state = NodeDetailState;
+
+        let id = Pubkey::new_unique();
+        ctx.model
+            .home_node_table
+            .upsert_contact_info(LegacyContactInfo {
+                id,
+                ..LegacyContactInfo::default()
+            });
+        ctx.model.detail_node = Some(id);
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Up);
+        // wrapped to the last field
+        let last = copyable_fields(&LegacyContactInfo::default()).len() - 1;
+        assert_eq!(ctx.model.detail_field, last);
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Down);
+        send(KeyCode::Down);
+        assert_eq!(ctx.model.detail_field, 1);
+
+        // the gossip address is unset
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Char('c'));
+        assert_eq!(ctx.model.status_message.as_deref(), Some("Nothing to copy"));
+
+        // without a clipboard the copy degrades to an error message
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Up);
+        send(KeyCode::Char('c'));
+        let message = ctx.model.status_message.clone().unwrap();
+        assert!(message == "Copied" || message.starts_with("Copy failed"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui() {
+        let backend = TestBackend::new(80, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ctx = Context::new_for_testing();
+
+        let id = Pubkey::new_unique();
+        ctx.model
+            .home_node_table
+            .upsert_contact_info(LegacyContactInfo {
+                id,
+                ..LegacyContactInfo::default()
+            });
+        ctx.model.detail_node = Some(id);
+
+        let state = NodeDetailState;
+
+        terminal
+            .draw(|f| {
+                state.ui(f, &mut ctx);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen = screen(buffer);
+        assert!(screen.contains(" Node Detail "));
+        assert!(screen.contains(&id.to_string()));
+        // computed once, until the epoch slots of the node change
+        assert_eq!(ctx.model.slots_coverage.get(&id), Some(&None));
+    }
+
+    #[test]
+    fn test_node_detail_hex() {
+        let backend = TestBackend::new(100, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ctx = Context::new_for_testing();
+        let mut state = NodeDetailState;
+
+        let keypair = Keypair::new();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            ..LegacyContactInfo::default()
+        };
+        let value = CrdsValue::new_signed(
+            CrdsData::LegacyContactInfo(Box::new(info.clone())),
+            &keypair,
+        );
+        ctx.model.crds.insert(value.clone()).unwrap();
+        ctx.model.home_node_table.upsert_contact_info(info);
+        ctx.model.detail_node = Some(keypair.pubkey());
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Char('x'));
+        send(KeyCode::PageDown);
+        send(KeyCode::Right);
+        assert_eq!(ctx.model.detail_hex, Some(0));
+        assert_eq!(ctx.model.detail_hex_scroll, 0);
+
+        terminal.draw(|f| state.ui(f, &mut ctx)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen = screen(buffer);
+        let len = value.to_bytes().unwrap().len();
+        assert!(screen.contains(&format!(" LegacyContactInfo 1/1 · {len} bytes · hash")));
+        assert!(screen.contains("00000000  "));
+
+        let mut send = |key_code| state.on_event(Event::Key { key_code }, &mut ctx);
+        send(KeyCode::Char('x'));
+        assert_eq!(ctx.model.detail_hex, None);
+    }
+}
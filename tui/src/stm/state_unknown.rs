@@ -28,10 +28,10 @@ impl State for UnknownState {
         draw_box(f, area, " Popup");
     }
 
-    fn help_text(&self) -> &str {
-        r##"
+    fn help_text(&self) -> &'static str {
+        r"
         ESC   -> back
-        "##
+        "
     }
 }
 
@@ -0,0 +1,100 @@
+use super::{events::Event, State, States};
+use crate::app::Context;
+use crate::ui::clock_skew_widget::{clock_skew_stats, render_clock_skew};
+use crate::ui::core::{centered_rect, draw_box};
+use crate::ui::version_histogram_widget::{render_version_histogram, version_histogram};
+use crossterm::event::KeyCode;
+use solana_gossip_proto::utils::since_the_epoch_millis;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    Frame,
+};
+
+pub struct VersionsState;
+
+impl State for VersionsState {
+    fn on_event(&mut self, event: Event, ctx: &mut Context) -> Option<States> {
+        if let Event::Key {
+            key_code: KeyCode::Esc,
+        } = event
+        {
+            Some(States::PreviousOne)
+        } else {
+            ctx.debug(format!("[VersionsS] on_event {event:?} not match"));
+            None
+        }
+    }
+
+    fn ui<B: Backend>(&self, f: &mut Frame<B>, ctx: &mut Context) {
+        let size = f.size();
+        draw_box(f, size, " Versions ");
+
+        let bbox = centered_rect(95, 90, size);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(bbox);
+        let rows = &ctx.model.home_node_table.rows;
+        let histogram = version_histogram(rows);
+        render_version_histogram(f, chunks[0], &histogram);
+
+        let infos = rows.iter().map(|row| row.info.clone()).collect::<Vec<_>>();
+        let stats = clock_skew_stats(&infos, since_the_epoch_millis());
+        render_clock_skew(f, chunks[1], &stats);
+    }
+
+    fn help_text(&self) -> &'static str {
+        r"
+        ESC    -> back
+
+        D      -> show Debug
+        q      -> Quit program
+        "
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::core::screen;
+    use crossterm::event::KeyCode;
+    use solana_gossip_proto::protocol::LegacyContactInfo;
+    use tui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_versions_state() -> Result<(), String> {
+        let mut ctx = Context::new_for_testing();
+        let mut state = VersionsState;
+
+        let event = Event::Key {
+            key_code: KeyCode::Char('*'),
+        };
+        assert_eq!(state.on_event(event, &mut ctx), None);
+
+        let event = Event::Key {
+            key_code: KeyCode::Esc,
+        };
+        assert_eq!(state.on_event(event, &mut ctx), Some(States::PreviousOne));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui() {
+        let backend = TestBackend::new(80, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut ctx = Context::new_for_testing();
+        ctx.model
+            .home_node_table
+            .upsert_contact_info(LegacyContactInfo::default());
+
+        let state = VersionsState;
+        terminal.draw(|f| state.ui(f, &mut ctx)).unwrap();
+
+        let screen = screen(terminal.backend().buffer());
+        assert!(screen.contains(" Versions [1 nodes] "));
+        assert!(screen.contains("unknown"));
+        assert!(screen.contains(" Clock skew [1 nodes, 0 ahead] "));
+    }
+}
@@ -1,12 +1,17 @@
 use super::state_entrypoint_selection::EntrypointSelectionState;
 use super::{State, States};
-use crate::app::Context;
+use crate::app::{Context, InputMode};
 use crate::stm::events::Event;
 use crate::stm::state_debug::DebugState;
+use crate::stm::state_duplicate_shreds::DuplicateShredsState;
 use crate::stm::state_help::HelpState;
 use crate::stm::state_home::HomeState;
+use crate::stm::state_node_detail::NodeDetailState;
 use crate::stm::state_unknown::UnknownState;
+use crate::stm::state_versions::VersionsState;
 use crate::ui::core::{centered_rect, clear_box, draw_box, draw_paragraph};
+use crate::ui::help_popup_widget::render_help_popup;
+use crate::ui::quit_summary_widget::render_quit_summary;
 use crossterm::event::KeyCode;
 use tui::{backend::Backend, Frame};
 
@@ -22,13 +27,16 @@ pub struct MainStm<'a> {
     help_st: HelpState,
     entrypoints_st: EntrypointSelectionState,
     home_st: HomeState,
+    node_detail_st: NodeDetailState,
+    versions_st: VersionsState,
+    duplicate_shreds_st: DuplicateShredsState,
 
     trace: bool,
 }
 
 #[allow(dead_code)]
 impl MainStm<'_> {
-    pub fn new(name: &'_ str, trace: bool) -> MainStm {
+    pub fn new(name: &'_ str, trace: bool) -> MainStm<'_> {
         MainStm {
             name,
             current_st: States::Unknown,
@@ -40,6 +48,9 @@ impl MainStm<'_> {
             help_st: HelpState,
             entrypoints_st: EntrypointSelectionState::default(),
             home_st: HomeState::default(),
+            node_detail_st: NodeDetailState,
+            versions_st: VersionsState,
+            duplicate_shreds_st: DuplicateShredsState,
 
             trace,
         }
@@ -56,15 +67,11 @@ impl MainStm<'_> {
             self.current_st = to_state;
 
             match self.current_st {
-                States::EntrypointSelection => {
-                    if self.entrypoints_st.on_enter_first {
-                        self.entrypoints_st.on_enter_once(ctx);
-                    }
+                States::EntrypointSelection if self.entrypoints_st.on_enter_first => {
+                    self.entrypoints_st.on_enter_once(ctx);
                 }
-                States::Home => {
-                    if self.home_st.on_enter_first {
-                        self.home_st.on_enter_once(ctx);
-                    }
+                States::Home if self.home_st.on_enter_first => {
+                    self.home_st.on_enter_once(ctx);
                 }
                 _ => {}
             }
@@ -80,8 +87,19 @@ impl MainStm<'_> {
         }
 
         match (self.current_st, event.clone()) {
+            // while editing the home search or follow input every key goes to the home state
+            (States::Home, _) if ctx.model.input_mode != InputMode::Normal => {
+                if let Some(to_state) = self.home_st.on_event(event, ctx) {
+                    self.switch_state(to_state, ctx);
+                }
+            }
             (
-                States::Unknown | States::EntrypointSelection | States::Home,
+                States::Unknown
+                | States::EntrypointSelection
+                | States::Home
+                | States::NodeDetail
+                | States::Versions
+                | States::DuplicateShreds,
                 Event::Key {
                     key_code: KeyCode::Char('D'),
                 },
@@ -89,7 +107,11 @@ impl MainStm<'_> {
                 self.switch_state(States::Debug, ctx);
             }
             (
-                States::EntrypointSelection | States::Home,
+                States::EntrypointSelection
+                | States::Home
+                | States::NodeDetail
+                | States::Versions
+                | States::DuplicateShreds,
                 Event::Key {
                     key_code: KeyCode::Char('?'),
                 },
@@ -116,6 +138,21 @@ impl MainStm<'_> {
                     self.switch_state(to_state, ctx);
                 }
             }
+            (States::NodeDetail, _) => {
+                if let Some(to_state) = self.node_detail_st.on_event(event, ctx) {
+                    self.switch_state(to_state, ctx);
+                }
+            }
+            (States::Versions, _) => {
+                if let Some(to_state) = self.versions_st.on_event(event, ctx) {
+                    self.switch_state(to_state, ctx);
+                }
+            }
+            (States::DuplicateShreds, _) => {
+                if let Some(to_state) = self.duplicate_shreds_st.on_event(event, ctx) {
+                    self.switch_state(to_state, ctx);
+                }
+            }
             (States::Help, _) => {
                 if let Some(to_state) = self.help_st.on_event(event, ctx) {
                     self.switch_state(to_state, ctx);
@@ -128,50 +165,86 @@ impl MainStm<'_> {
     pub fn draw<B: Backend>(&self, f: &mut Frame<B>, ctx: &mut Context) {
         // it Help is the current state, overlay to the previous state ui the help view.
         if self.current_st == States::Help {
-            let text = match self.previous_st {
+            let (text, bindings) = match self.previous_st {
                 Some(States::Unknown) => {
                     self.unknow_st.ui(f, ctx);
-                    self.unknow_st.help_text()
+                    (self.unknow_st.help_text(), self.unknow_st.key_bindings())
                 }
                 Some(States::Debug) => {
                     self.debug_st.ui(f, ctx);
-                    self.debug_st.help_text()
+                    (self.debug_st.help_text(), self.debug_st.key_bindings())
                 }
                 Some(States::EntrypointSelection) => {
                     self.entrypoints_st.ui(f, ctx);
-                    self.entrypoints_st.help_text()
+                    (
+                        self.entrypoints_st.help_text(),
+                        self.entrypoints_st.key_bindings(),
+                    )
                 }
                 Some(States::Home) => {
                     self.home_st.ui(f, ctx);
-                    self.home_st.help_text()
+                    (self.home_st.help_text(), self.home_st.key_bindings())
+                }
+                Some(States::NodeDetail) => {
+                    self.node_detail_st.ui(f, ctx);
+                    (
+                        self.node_detail_st.help_text(),
+                        self.node_detail_st.key_bindings(),
+                    )
+                }
+                Some(States::Versions) => {
+                    self.versions_st.ui(f, ctx);
+                    (
+                        self.versions_st.help_text(),
+                        self.versions_st.key_bindings(),
+                    )
+                }
+                Some(States::DuplicateShreds) => {
+                    self.duplicate_shreds_st.ui(f, ctx);
+                    (
+                        self.duplicate_shreds_st.help_text(),
+                        self.duplicate_shreds_st.key_bindings(),
+                    )
                 }
                 _ => {
                     ctx.debug(format!(
                         "[STM] draw for current:{:?} not match",
                         self.current_st
                     ));
-                    "None"
+                    ("None", &[][..])
                 }
             };
 
             // overlay the help popup
-            let bbox = centered_rect(75, 70, f.size());
-            let bbox_inner = centered_rect(95, 90, bbox);
-            clear_box(f, bbox);
-            draw_box(f, bbox, " Help State ");
-            draw_paragraph(f, bbox_inner, text);
+            if bindings.is_empty() {
+                let bbox = centered_rect(75, 70, f.size());
+                let bbox_inner = centered_rect(95, 90, bbox);
+                clear_box(f, bbox);
+                draw_box(f, bbox, " Help State ");
+                draw_paragraph(f, bbox_inner, text);
+            } else {
+                render_help_popup(f, f.size(), bindings);
+            }
         } else {
             match self.current_st {
                 States::Unknown => self.unknow_st.ui(f, ctx),
                 States::Debug => self.debug_st.ui(f, ctx),
                 States::EntrypointSelection => self.entrypoints_st.ui(f, ctx),
                 States::Home => self.home_st.ui(f, ctx),
+                States::NodeDetail => self.node_detail_st.ui(f, ctx),
+                States::Versions => self.versions_st.ui(f, ctx),
+                States::DuplicateShreds => self.duplicate_shreds_st.ui(f, ctx),
                 _ => ctx.debug(format!(
                     "[STM] draw for current:{:?} not match",
                     self.current_st
                 )),
             }
         }
+
+        // overlay the quit confirmation to any state
+        if let Some(summary) = &ctx.model.quit_summary {
+            render_quit_summary(f, f.size(), summary);
+        }
     }
 }
 
@@ -223,6 +296,18 @@ mod tests {
         stm.on_event(e, &mut ctx);
         assert!(matches!(stm.current_st, States::Home));
 
+        // search input captures the keys
+        let e = Event::Key {
+            key_code: KeyCode::Char('/'),
+        };
+        stm.on_event(e, &mut ctx);
+        let e = Event::Key {
+            key_code: KeyCode::Char('D'),
+        };
+        stm.on_event(e, &mut ctx);
+        assert!(matches!(stm.current_st, States::Home));
+        assert_eq!(ctx.model.home_node_table.filter, "D");
+
         Ok(())
     }
 }
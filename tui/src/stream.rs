@@ -0,0 +1,97 @@
+use std::{
+    io::{self, Write},
+    net::SocketAddr,
+};
+
+use serde_json::json;
+use solana_gossip_proto::protocol::CrdsValue;
+
+/// Writes one JSON object per line for each value integrated in the table,
+/// flushed at once so that the consumers of a pipe see it without delay.
+#[derive(Debug)]
+pub struct JsonStream<W: Write> {
+    out: W,
+    written: u64,
+}
+
+impl<W: Write> JsonStream<W> {
+    pub fn new(out: W) -> Self {
+        JsonStream { out, written: 0 }
+    }
+
+    /// Write the value received from `from` at `received`, in milliseconds since the epoch.
+    pub fn write_value(
+        &mut self,
+        received: u64,
+        from: SocketAddr,
+        value: &CrdsValue,
+    ) -> io::Result<()> {
+        let line = json!({
+            "timestamp": received,
+            "from": from.to_string(),
+            "kind": value.data.kind(),
+            "pubkey": value.data.pubkey().map(|pubkey| pubkey.to_string()),
+            "wallclock": value.data.wallclock(),
+        });
+        serde_json::to_writer(&mut self.out, &line)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of lines written.
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use solana_gossip_proto::protocol::{CrdsData, LegacyContactInfo, NodeInstance};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+
+    #[test]
+    fn test_json_stream() {
+        let keypair = Keypair::new();
+        let from = "141.98.219.218:8001".parse().unwrap();
+        let info = LegacyContactInfo {
+            id: keypair.pubkey(),
+            wallclock: 1_000,
+            ..LegacyContactInfo::default()
+        };
+        let instance = NodeInstance {
+            from: keypair.pubkey(),
+            wallclock: 2_000,
+            timestamp: 2_000,
+            token: 1,
+        };
+
+        let mut stream = JsonStream::new(vec![]);
+        for data in [
+            CrdsData::LegacyContactInfo(Box::new(info)),
+            CrdsData::NodeInstance(instance),
+        ] {
+            let value = CrdsValue::new_signed(data, &keypair);
+            stream.write_value(1_681_834_717_999, from, &value).unwrap();
+        }
+        assert_eq!(stream.written(), 2);
+
+        let text = String::from_utf8(stream.out).unwrap();
+        let lines = text
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["timestamp"], 1_681_834_717_999_u64);
+        assert_eq!(lines[0]["from"], "141.98.219.218:8001");
+        assert_eq!(lines[0]["kind"], "LegacyContactInfo");
+        assert_eq!(lines[0]["pubkey"], keypair.pubkey().to_string().as_str());
+        assert_eq!(lines[1]["kind"], "NodeInstance");
+        assert_eq!(lines[1]["wallclock"], 2_000);
+    }
+}
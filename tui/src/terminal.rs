@@ -1,6 +1,10 @@
-use std::io::{self, Stdout};
+use std::{
+    io::{self, Stdout},
+    panic,
+};
 
 use crossterm::{
+    cursor::Show,
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -8,21 +12,67 @@ use crossterm::{
 
 use tui::{backend::CrosstermBackend, Terminal};
 
+/// Leave the raw mode and the alternate screen, show the cursor.
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        Show
+    )
+}
+
+/// Restores the terminal when dropped, and before the message of a panic is
+/// printed so that a crash doesn't leave the shell in raw mode.
+#[allow(clippy::module_name_repetitions)]
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    /// Chain the terminal restore to the current panic hook.
+    pub fn install() -> Self {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal().unwrap_or(());
+            hook(info);
+        }));
+        TerminalGuard
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal().unwrap_or(());
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub struct XTerminal {
     pub terminal: tui::Terminal<CrosstermBackend<Stdout>>,
+    _guard: TerminalGuard,
 }
 
 impl XTerminal {
-    pub fn new() -> Result<XTerminal, Box<dyn std::error::Error>> {
+    /// The mouse is captured only when asked, the capture prevents selecting
+    /// the text with the mouse.
+    pub fn new(mouse: bool) -> Result<XTerminal, Box<dyn std::error::Error>> {
+        // the guard restores the terminal even if the setup fails halfway
+        let guard = TerminalGuard::install();
+
         // setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        execute!(stdout, EnterAlternateScreen)?;
+        if mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
-        Ok(XTerminal { terminal })
+        Ok(XTerminal {
+            terminal,
+            _guard: guard,
+        })
     }
 
     pub fn restore(&mut self) -> io::Result<()> {
@@ -1,4 +1,6 @@
+pub(crate) mod capture;
 pub(crate) mod receiver;
+pub(crate) mod replay;
 pub(crate) mod sender;
 
 use std::time::Duration;
@@ -22,4 +24,4 @@ pub struct Stats {
     pub counter: u32,
 }
 
-const RECV_TIMEOUT: Duration = Duration::from_millis(1000);
+const RECV_TIMEOUT: Duration = Duration::from_secs(1);
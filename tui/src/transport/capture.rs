@@ -0,0 +1,189 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use solana_gossip_proto::wire::PACKET_DATA_SIZE;
+
+/// Leading bytes of a capture file, followed by `CAPTURE_VERSION`.
+pub const CAPTURE_MAGIC: &[u8; 8] = b"SGTDUMP\0";
+pub const CAPTURE_VERSION: u8 = 1;
+
+/// A datagram as stored in the capture file: the milliseconds since the capture
+/// started (u64), the source IPv6 or IPv4-mapped address (16 bytes), the source
+/// port (u16), the datagram length (u16) and the datagram bytes.
+/// The integers are little endian.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub elapsed: Duration,
+    pub addr: SocketAddr,
+    pub data: Vec<u8>,
+}
+
+/// Appends the received datagrams to a capture.
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+    start: Instant,
+}
+
+impl CaptureWriter<File> {
+    /// Create the capture file, replacing an existing one.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        CaptureWriter::new(File::create(path)?)
+    }
+}
+
+impl<W: Write> CaptureWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(CAPTURE_MAGIC)?;
+        writer.write_all(&[CAPTURE_VERSION])?;
+        Ok(CaptureWriter {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Write the datagram with a single call, so that nothing is lost when
+    /// the program exits without flushing.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write(&mut self, addr: SocketAddr, data: &[u8]) -> io::Result<()> {
+        let len = u16::try_from(data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "datagram too large"))?;
+        let ip = match addr.ip() {
+            IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+            IpAddr::V6(ip) => ip,
+        };
+
+        let mut record = Vec::with_capacity(28 + data.len());
+        record.extend_from_slice(&(self.start.elapsed().as_millis() as u64).to_le_bytes());
+        record.extend_from_slice(&ip.octets());
+        record.extend_from_slice(&addr.port().to_le_bytes());
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(data);
+        self.writer.write_all(&record)
+    }
+}
+
+/// Reads back the datagrams of a capture, in the order they were received.
+pub struct CaptureReader<R: Read> {
+    reader: R,
+}
+
+impl CaptureReader<BufReader<File>> {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        CaptureReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Check the header, captures of another format or version are rejected.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0u8; 9];
+        reader.read_exact(&mut header)?;
+        if &header[..8] != CAPTURE_MAGIC {
+            return Err(invalid_data("not a capture file"));
+        }
+        if header[8] != CAPTURE_VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported capture version {}",
+                header[8]
+            )));
+        }
+        Ok(CaptureReader { reader })
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let mut elapsed = [0u8; 8];
+        // a clean end of file falls between two records
+        match self.reader.read(&mut elapsed[..1])? {
+            0 => return Ok(None),
+            _ => self.reader.read_exact(&mut elapsed[1..])?,
+        }
+        let mut ip = [0u8; 16];
+        self.reader.read_exact(&mut ip)?;
+        let mut port = [0u8; 2];
+        self.reader.read_exact(&mut port)?;
+        let mut len = [0u8; 2];
+        self.reader.read_exact(&mut len)?;
+
+        let len = usize::from(u16::from_le_bytes(len));
+        if len > PACKET_DATA_SIZE {
+            return Err(invalid_data(&format!("datagram of {len} bytes")));
+        }
+        let mut data = vec![0u8; len];
+        self.reader.read_exact(&mut data)?;
+
+        Ok(Some(Record {
+            elapsed: Duration::from_millis(u64::from_le_bytes(elapsed)),
+            addr: SocketAddr::new(Ipv6Addr::from(ip).to_canonical(), u16::from_le_bytes(port)),
+            data,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_round_trip() {
+        let v4: SocketAddr = "141.98.219.218:8001".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:8000".parse().unwrap();
+
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer.write(v4, &[1, 2, 3]).unwrap();
+        writer.write(v6, &[]).unwrap();
+        let bytes = writer.writer;
+        assert!(bytes.starts_with(CAPTURE_MAGIC));
+
+        let records = CaptureReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (records[0].addr, records[0].data.as_slice()),
+            (v4, &[1, 2, 3][..])
+        );
+        assert_eq!((records[1].addr, records[1].data.len()), (v6, 0));
+        assert!(records[0].elapsed <= records[1].elapsed);
+    }
+
+    #[test]
+    fn test_capture_reader_errors() {
+        let err = CaptureReader::new(&b"PCAPDUMP\x01"[..]).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut header = CAPTURE_MAGIC.to_vec();
+        header.push(CAPTURE_VERSION + 1);
+        let err = CaptureReader::new(header.as_slice()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // the last record is cut
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write("141.98.219.218:8001".parse().unwrap(), &[7; 10])
+            .unwrap();
+        let mut bytes = writer.writer;
+        bytes.truncate(bytes.len() - 1);
+        let mut reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    fs::File,
     io,
     net::UdpSocket,
     sync::mpsc::{Receiver, Sender},
@@ -6,17 +7,23 @@ use std::{
     thread::{Builder, JoinHandle},
 };
 
-use log::{error, trace};
+use log::{error, trace, warn};
 
-use solana_gossip_proto::wire::{Payload, PACKET_DATA_SIZE};
+use solana_gossip_proto::{
+    utils::canonical_addr,
+    wire::{Payload, PACKET_DATA_SIZE},
+};
 
-use crate::transport::{CtrlCmd, Stats, StatsId};
+use crate::transport::{capture::CaptureWriter, CtrlCmd, Stats, StatsId};
 
+/// Every datagram passed on is also written to the capture, when set, so that a
+/// replay sees the same traffic. The datagrams larger than a packet are dropped.
 pub(crate) fn spawn_receiver(
     socket: Arc<UdpSocket>,
     tx: Sender<Payload>,
     ctrl_rx: Receiver<CtrlCmd>,
     stats_tx: Sender<Stats>,
+    mut capture: Option<CaptureWriter<File>>,
 ) -> io::Result<JoinHandle<()>> {
     Builder::new()
         .name("udp_receiver_t".to_string())
@@ -40,7 +47,8 @@ pub(crate) fn spawn_receiver(
                     }
                 }
 
-                let mut buf = [0; PACKET_DATA_SIZE];
+                // one byte more than a packet, to tell the oversized datagrams apart
+                let mut buf = [0; PACKET_DATA_SIZE + 1];
 
                 match socket.recv_from(&mut buf) {
                     Ok((len, addr)) => {
@@ -48,35 +56,28 @@ pub(crate) fn spawn_receiver(
                             "counter:{counter} received addr:{addr:?} len:{len} bytes {:?}",
                             &buf[..len]
                         );
+                        let addr = canonical_addr(addr);
+                        if len > PACKET_DATA_SIZE {
+                            warn!("counter:{counter} datagram from {addr} larger than {PACKET_DATA_SIZE} bytes dropped");
+                            counter += 1;
+                            continue;
+                        }
 
-                        let include: Vec<usize> = vec![
-                            132, // PingMessage / PongMessage
-                        ];
-
-                        let exlude: Vec<usize> = vec![
-                            //254,  // LegacyContactInfo
-                            472, // Vote
-                            430, 442, 446, 454, 466, 478, 491, 503, 515, 724,
-                            185, // LowestSlot
-                            240, // SnapshotHashes
-                            200, 800,  // AccountsHashes
-                            1049, // EpochSlots
-                            1022, 1026, 1028, 1032, 1038, 1039, 163, // LegacyVersion
-                            //167,  // Version
-                            168, // NodeInstance
-                            360, // IncrementalSnapshotHashes
-                            320, 280,
-                        ];
-
-                        if include.contains(&len) || !exlude.contains(&len) {
-                            tx.send(Payload {
-                                len,
-                                buf,
-                                addr: Some(addr),
-                            })
-                            .unwrap_or(());
+                        if let Some(writer) = &mut capture {
+                            if let Err(err) = writer.write(addr, &buf[..len]) {
+                                error!("counter:{counter} capture err:{err}");
+                                capture = None;
+                            }
                         }
 
+                        let mut payload = Payload {
+                            len,
+                            addr: Some(addr),
+                            ..Payload::default()
+                        };
+                        payload.buf.copy_from_slice(&buf[..PACKET_DATA_SIZE]);
+                        tx.send(payload).unwrap_or(());
+
                         counter += 1;
                     }
                     Err(err) => {
@@ -88,3 +89,77 @@ pub(crate) fn spawn_receiver(
             trace!("index:{counter} terminated");
         })
 }
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use solana_gossip_proto::protocol::{CrdsData, CrdsValue, Protocol, Vote};
+    use solana_sdk::{
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+        vote,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_receiver_vote() {
+        // a tower of 31 slots with a timestamp, as the validators vote
+        let keypair = Keypair::new();
+        let update = vote::state::VoteStateUpdate {
+            hash: Hash::new_unique(),
+            timestamp: Some(1_700_000_000),
+            ..vote::state::VoteStateUpdate::from(
+                (0..31u32)
+                    .map(|i| (100 + u64::from(i), 31 - i))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let instruction = vote::instruction::compact_update_vote_state(
+            &Pubkey::new_unique(),
+            &keypair.pubkey(),
+            update,
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&keypair.pubkey()),
+            &[&keypair],
+            Hash::new_unique(),
+        );
+        let vote = Vote::new(keypair.pubkey(), transaction, 1_000);
+        let value = CrdsValue::new_signed(CrdsData::Vote(0, vote), &keypair);
+        let push = Protocol::PushMessage(keypair.pubkey(), vec![value]);
+        let bytes = push.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 472);
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let (ctrl_tx, ctrl_rx) = mpsc::channel();
+        let (stats_tx, _stats_rx) = mpsc::channel();
+        let handle = spawn_receiver(Arc::new(socket), tx, ctrl_rx, stats_tx, None).unwrap();
+
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.send_to(&bytes, addr).unwrap();
+        peer.send_to(&[1; PACKET_DATA_SIZE + 1], addr).unwrap();
+        peer.send_to(&[1; PACKET_DATA_SIZE], addr).unwrap();
+
+        let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(payload.len, 472);
+        assert_eq!(payload.addr, Some(peer.local_addr().unwrap()));
+        assert_eq!(payload.deserialize_slice::<Protocol, _>(..).unwrap(), push);
+        // the larger datagram is dropped, the full packet goes through
+        let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(payload.len, PACKET_DATA_SIZE);
+
+        ctrl_tx.send(CtrlCmd::Stop).unwrap();
+        handle.join().unwrap();
+    }
+}
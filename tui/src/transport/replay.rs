@@ -0,0 +1,143 @@
+use std::{
+    io::Read,
+    sync::mpsc::{Receiver, Sender},
+    thread::{self, Builder, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use log::{error, trace};
+
+use solana_gossip_proto::wire::{Payload, PACKET_DATA_SIZE};
+
+use crate::transport::{
+    capture::{CaptureReader, Record},
+    CtrlCmd, Stats, StatsId,
+};
+
+/// Longest sleep between two checks of the control commands.
+const REPLAY_TICK: Duration = Duration::from_millis(30);
+
+fn payload(record: &Record) -> Payload {
+    let mut buf = [0; PACKET_DATA_SIZE];
+    buf[..record.data.len()].copy_from_slice(&record.data);
+    Payload {
+        len: record.data.len(),
+        buf,
+        addr: Some(record.addr),
+    }
+}
+
+/// Feed the captured datagrams in place of the receiver, keeping their pace
+/// scaled by `speed`. The messages the logic sends are dropped, nothing
+/// reaches the network.
+pub(crate) fn spawn_replay<R: Read + Send + 'static>(
+    mut reader: CaptureReader<R>,
+    speed: f64,
+    tx: Sender<Payload>,
+    sender_rx: Receiver<Payload>,
+    ctrl_rx: Receiver<CtrlCmd>,
+    stats_tx: Sender<Stats>,
+) -> std::io::Result<JoinHandle<()>> {
+    Builder::new().name("replay_t".to_string()).spawn(move || {
+        let mut counter: u32 = 0;
+        let start = Instant::now();
+        let mut pending: Option<Record> = None;
+        let mut finished = false;
+
+        'main_l: loop {
+            if let Ok(ctrl_msg) = ctrl_rx.try_recv() {
+                match ctrl_msg {
+                    CtrlCmd::Stop => break 'main_l,
+                    CtrlCmd::Counter => {
+                        stats_tx
+                            .send(Stats {
+                                id: StatsId::Receiver,
+                                counter,
+                            })
+                            .unwrap_or(());
+                    }
+                }
+            }
+
+            let dropped = sender_rx.try_iter().count();
+            if dropped > 0 {
+                trace!("counter:{counter} dropped {dropped} outgoing messages");
+            }
+
+            if pending.is_none() && !finished {
+                match reader.next() {
+                    Some(Ok(record)) => pending = Some(record),
+                    Some(Err(err)) => {
+                        error!("counter:{counter} replay err:{err}");
+                        finished = true;
+                    }
+                    None => {
+                        trace!("counter:{counter} replay completed");
+                        finished = true;
+                    }
+                }
+            }
+
+            let Some(record) = &pending else {
+                thread::sleep(REPLAY_TICK);
+                continue;
+            };
+            let due = start + record.elapsed.div_f64(speed);
+            let now = Instant::now();
+            if now < due {
+                thread::sleep((due - now).min(REPLAY_TICK));
+                continue;
+            }
+
+            tx.send(payload(record)).unwrap_or(());
+            pending = None;
+            counter += 1;
+        }
+
+        trace!("counter:{counter} terminated");
+    })
+}
+
+//tests
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use solana_gossip_proto::protocol::{Ping, Protocol};
+    use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+
+    use super::*;
+    use crate::transport::capture::CaptureWriter;
+
+    #[test]
+    fn test_replay() {
+        let ping = Protocol::PingMessage(Ping::new(&Keypair::new()));
+        let bytes = ping.to_bytes().unwrap();
+        let addr = "141.98.219.218:8001".parse().unwrap();
+
+        let path = std::env::temp_dir().join(format!("replay-{}.dump", Pubkey::new_unique()));
+        let mut writer = CaptureWriter::create(&path).unwrap();
+        writer.write(addr, &bytes).unwrap();
+        writer.write(addr, &bytes).unwrap();
+        drop(writer);
+
+        let (tx, rx) = mpsc::channel();
+        let (sender_tx, sender_rx) = mpsc::channel();
+        let (ctrl_tx, ctrl_rx) = mpsc::channel();
+        let (stats_tx, _stats_rx) = mpsc::channel();
+        let reader = CaptureReader::open(&path).unwrap();
+        let handle = spawn_replay(reader, 10.0, tx, sender_rx, ctrl_rx, stats_tx).unwrap();
+        sender_tx.send(Payload::default()).unwrap();
+
+        for _ in 0..2 {
+            let payload = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(payload.addr, Some(addr));
+            assert_eq!(payload.len, bytes.len());
+            assert_eq!(payload.deserialize_slice::<Protocol, _>(..).unwrap(), ping);
+        }
+
+        ctrl_tx.send(CtrlCmd::Stop).unwrap();
+        handle.join().unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+}
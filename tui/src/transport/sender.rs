@@ -10,7 +10,7 @@ use std::{
 
 use log::trace;
 
-use solana_gossip_proto::wire::Payload;
+use solana_gossip_proto::{utils::dest_addr_for, wire::Payload};
 
 use crate::transport::{CtrlCmd, Stats, StatsId, RECV_TIMEOUT};
 
@@ -20,6 +20,8 @@ pub(crate) fn spawn_sender(
     ctrl_rx: Receiver<CtrlCmd>,
     stats_tx: Sender<Stats>,
 ) -> io::Result<JoinHandle<()>> {
+    let local_addr = socket.local_addr()?;
+
     Builder::new()
         .name("udp_sender_t".to_string())
         .spawn(move || {
@@ -45,7 +47,8 @@ pub(crate) fn spawn_sender(
                 if let Ok(data) = rx.recv_timeout(RECV_TIMEOUT) {
                     if let Some(addr) = data.addr {
                         if let Some(buf) = data.data(..) {
-                            if let Err(err) = socket.send_to(buf, addr) {
+                            if let Err(err) = socket.send_to(buf, dest_addr_for(&local_addr, addr))
+                            {
                                 trace!("counter:{counter} sending err:{err:?}");
                             }
 
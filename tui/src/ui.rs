@@ -1,3 +1,18 @@
+pub(crate) mod bad_peers_widget;
+pub(crate) mod clock_skew_widget;
 pub(crate) mod core;
+pub(crate) mod crds_counts_widget;
+pub(crate) mod duplicate_shred_widget;
+pub(crate) mod epoch_slots_widget;
+pub(crate) mod help_popup_widget;
+pub(crate) mod hex_dump_widget;
+pub(crate) mod identity_widget;
 pub(crate) mod list_stateful_widget;
+pub(crate) mod node_detail_widget;
+pub(crate) mod node_table_widget;
+pub(crate) mod quit_summary_widget;
+pub(crate) mod shred_version_widget;
+pub(crate) mod snapshot_hashes_widget;
+pub(crate) mod status_bar_widget;
 pub(crate) mod table_stateful_widget;
+pub(crate) mod version_histogram_widget;
@@ -0,0 +1,67 @@
+use solana_gossip_proto::crds::VerificationTracker;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+
+const BAD_PEER_HEADERS: [&str; 2] = ["Origin", "Invalid Signatures"];
+
+/// One row per origin, the most failing first.
+pub fn bad_peer_rows(tracker: &VerificationTracker) -> Vec<[String; 2]> {
+    tracker
+        .bad_peers()
+        .into_iter()
+        .map(|(origin, failures)| [origin.to_string(), failures.to_string()])
+        .collect()
+}
+
+pub fn render_bad_peers<B: Backend>(f: &mut Frame<B>, area: Rect, tracker: &VerificationTracker) {
+    let header = Row::new(BAD_PEER_HEADERS)
+        .style(Style::default().fg(Color::White).bg(Color::LightBlue))
+        .bottom_margin(1);
+
+    let rows = bad_peer_rows(tracker)
+        .into_iter()
+        .map(|cells| Row::new(cells.into_iter().map(Cell::from)));
+
+    let title = format!(
+        " Peers sending invalid signatures [{} peers] ",
+        tracker.verification_failures().len()
+    );
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&[Constraint::Length(44), Constraint::Min(0)]);
+    f.render_widget(table, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_bad_peer_rows() {
+        let origin = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut tracker = VerificationTracker::default();
+        assert!(bad_peer_rows(&tracker).is_empty());
+
+        tracker.record_failure(other);
+        tracker.record_failure(origin);
+        tracker.record_failure(origin);
+        let rows = bad_peer_rows(&tracker);
+        assert_eq!(
+            rows,
+            vec![
+                [origin.to_string(), "2".to_string()],
+                [other.to_string(), "1".to_string()],
+            ]
+        );
+    }
+}
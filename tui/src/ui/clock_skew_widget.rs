@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use solana_gossip_proto::protocol::LegacyContactInfo;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{BarChart, Block, Borders},
+    Frame,
+};
+
+const BAR_WIDTH: u16 = 5;
+
+/// Number of contact infos by skew, `now_ms - wallclock` floored to the second, in
+/// ascending order. Negative skews are wallclocks in the future, signed by a node
+/// with a clock running ahead or forged.
+pub fn clock_skew_stats(infos: &[LegacyContactInfo], now_ms: u64) -> Vec<(i64, usize)> {
+    let mut buckets: BTreeMap<i64, usize> = BTreeMap::new();
+    for info in infos {
+        let skew_ms = i128::from(now_ms) - i128::from(info.wallclock);
+        let skew = i64::try_from(skew_ms.div_euclid(1000)).unwrap_or(i64::MAX);
+        *buckets.entry(skew).or_default() += 1;
+    }
+    buckets.into_iter().collect()
+}
+
+/// Fold the buckets into at most `max_bars` bars of the same width, the empty ones
+/// included, each labeled by the first second it covers.
+fn skew_bars(stats: &[(i64, usize)], max_bars: usize) -> Vec<(String, u64)> {
+    let (Some((first, _)), Some((last, _))) = (stats.first(), stats.last()) else {
+        return vec![];
+    };
+    let span = last - first + 1;
+    let max_bars = i64::try_from(max_bars.max(1)).unwrap_or(i64::MAX);
+    let width = (span + max_bars - 1) / max_bars;
+
+    let mut bars = (0..(span + width - 1) / width)
+        .map(|i| (first + i * width, 0))
+        .collect::<Vec<_>>();
+    for (skew, count) in stats {
+        let i = usize::try_from((skew - first) / width).unwrap_or_default();
+        bars[i].1 += *count as u64;
+    }
+    bars.into_iter()
+        .map(|(start, count)| (format!("{start}s"), count))
+        .collect()
+}
+
+pub fn render_clock_skew<B: Backend>(f: &mut Frame<B>, area: Rect, stats: &[(i64, usize)]) {
+    let max_bars = usize::from(area.width.saturating_sub(2) / (BAR_WIDTH + 1));
+    let bars = skew_bars(stats, max_bars);
+    let data = bars
+        .iter()
+        .map(|(label, count)| (label.as_str(), *count))
+        .collect::<Vec<_>>();
+
+    let nodes = stats.iter().map(|(_, count)| count).sum::<usize>();
+    let ahead = stats
+        .iter()
+        .filter(|(skew, _)| *skew < 0)
+        .map(|(_, count)| count)
+        .sum::<usize>();
+    let title = format!(" Clock skew [{nodes} nodes, {ahead} ahead] ");
+
+    let chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&data)
+        .bar_width(BAR_WIDTH)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(
+            Style::default()
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(chart, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::screen;
+
+    fn create_info(wallclock: u64) -> LegacyContactInfo {
+        LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            wallclock,
+            ..LegacyContactInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_clock_skew_stats() {
+        let infos = [99_500, 98_000, 98_999, 101_500, 40_000, 99_000]
+            .into_iter()
+            .map(create_info)
+            .collect::<Vec<_>>();
+
+        // 1.5 seconds in the future is floored to -2
+        assert_eq!(
+            clock_skew_stats(&infos, 100_000),
+            vec![(-2, 1), (0, 1), (1, 2), (2, 1), (60, 1)]
+        );
+        assert!(clock_skew_stats(&[], 100_000).is_empty());
+    }
+
+    #[test]
+    fn test_skew_bars() {
+        let stats = vec![(-2, 1), (0, 1), (1, 2), (2, 1), (60, 1)];
+        assert_eq!(skew_bars(&stats, 100).len(), 63);
+        assert_eq!(skew_bars(&stats, 100)[2], (String::from("0s"), 1));
+
+        // 63 seconds in 7 bars of 9 seconds
+        let bars = skew_bars(&stats, 7);
+        assert_eq!(
+            bars,
+            vec![
+                (String::from("-2s"), 5),
+                (String::from("7s"), 0),
+                (String::from("16s"), 0),
+                (String::from("25s"), 0),
+                (String::from("34s"), 0),
+                (String::from("43s"), 0),
+                (String::from("52s"), 1),
+            ]
+        );
+        assert!(skew_bars(&[], 7).is_empty());
+    }
+
+    #[test]
+    fn test_render_clock_skew() {
+        let backend = TestBackend::new(40, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let stats = vec![(-30, 1), (0, 3), (4, 2)];
+        terminal
+            .draw(|f| render_clock_skew(f, f.size(), &stats))
+            .unwrap();
+
+        let screen = screen(terminal.backend().buffer());
+        assert!(screen.contains(" Clock skew [6 nodes, 1 ahead] "));
+        assert!(screen.contains("-30s"));
+    }
+}
@@ -1,3 +1,4 @@
+use solana_sdk::pubkey::Pubkey;
 #[allow(unused_imports)]
 use tui::{
     backend::Backend,
@@ -77,9 +78,44 @@ pub fn layout_columns_70_30(r: Rect) -> Vec<Rect> {
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
         .split(r)
 }
+
+pub fn layout_rows_50_50(r: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(r)
+}
+
+/// First and last 4 characters of the base58 pubkey, as `AbCd…WxYz`, for the
+/// tables and lists. The detail views show the full key.
+pub fn short_pubkey(pubkey: &Pubkey) -> String {
+    let pubkey = pubkey.to_string();
+    format!("{}…{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+}
+
+/// All the symbols of the rendered buffer, row after row, for the render tests.
+#[cfg(test)]
+pub fn screen(buffer: &tui::buffer::Buffer) -> String {
+    buffer
+        .content()
+        .iter()
+        .map(|cell| cell.symbol.clone())
+        .collect()
+}
+
+/// The symbols of the row `y` of the rendered buffer, for the render tests.
+#[cfg(test)]
+pub fn line(buffer: &tui::buffer::Buffer, y: u16) -> String {
+    (buffer.area.left()..buffer.area.right())
+        .map(|x| buffer.get(x, y).symbol.clone())
+        .collect()
+}
+
 // tests
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
     use tui::{backend::TestBackend, buffer::Buffer, Terminal};
 
@@ -151,6 +187,14 @@ mod tests {
         terminal.backend().assert_buffer(&expected);
     }
 
+    #[test]
+    fn test_short_pubkey() {
+        let pubkey = Pubkey::from_str("7Np41oeYqPefeNQEHSv1UDhYrehxin3NStELsSKCT4K2").unwrap();
+        assert_eq!(short_pubkey(&pubkey), "7Np4…T4K2");
+        assert_eq!(short_pubkey(&pubkey), short_pubkey(&pubkey));
+        assert_eq!(short_pubkey(&Pubkey::default()), "1111…1111");
+    }
+
     #[test]
     fn test_centered_rect() {
         let rect = Rect::new(2, 3, 10, 10);
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+/// Render the number of values held of each kind, the total in the title.
+pub fn render_crds_counts<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    counts: &BTreeMap<&'static str, usize>,
+) {
+    let items = if counts.is_empty() {
+        vec![ListItem::new("none").style(Style::default().fg(Color::DarkGray))]
+    } else {
+        counts
+            .iter()
+            .map(|(kind, count)| ListItem::new(format!("{count:>6} {kind}")))
+            .collect()
+    };
+
+    let total = counts.values().sum::<usize>();
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" crds values ({total}) ")),
+    );
+    f.render_widget(list, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_render_crds_counts() {
+        let backend = TestBackend::new(30, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let counts = BTreeMap::from([("LegacyContactInfo", 1_200), ("Vote", 35)]);
+        terminal
+            .draw(|f| render_crds_counts(f, f.size(), &counts))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 0).contains(" crds values (1235) "));
+        assert!(line(buffer, 1).contains("  1200 LegacyContactInfo"));
+        assert!(line(buffer, 2).contains("    35 Vote"));
+    }
+}
@@ -0,0 +1,104 @@
+use solana_gossip_proto::crds::DuplicateShredTracker;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+
+use super::core::short_pubkey;
+
+const DUPLICATE_SHRED_HEADERS: [&str; 4] = ["Slot", "Reporter", "Chunks", "Proof"];
+
+/// One row per reporter, the accused slot is only shown on the first row of its group.
+/// The most recent slots come first.
+pub fn duplicate_shred_rows(tracker: &DuplicateShredTracker) -> Vec<[String; 4]> {
+    let mut rows = vec![];
+    for (slot, proofs) in tracker.by_slot().into_iter().rev() {
+        for (index, proof) in proofs.into_iter().enumerate() {
+            rows.push([
+                if index == 0 {
+                    slot.to_string()
+                } else {
+                    String::new()
+                },
+                short_pubkey(&proof.from),
+                format!("{}/{}", proof.received_chunks(), proof.num_chunks),
+                String::from(if proof.is_complete() {
+                    "complete"
+                } else {
+                    "partial"
+                }),
+            ]);
+        }
+    }
+    rows
+}
+
+pub fn render_duplicate_shreds<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    tracker: &DuplicateShredTracker,
+) {
+    let header = Row::new(DUPLICATE_SHRED_HEADERS)
+        .style(Style::default().fg(Color::White).bg(Color::LightBlue))
+        .bottom_margin(1);
+
+    let rows = duplicate_shred_rows(tracker).into_iter().map(|cells| {
+        let style = if cells[3] == "complete" {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default()
+        };
+        Row::new(cells.into_iter().map(Cell::from)).style(style)
+    });
+
+    let title = format!(" Duplicate Shreds [{} slots] ", tracker.by_slot().len());
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&[
+            Constraint::Percentage(15),
+            Constraint::Percentage(55),
+            Constraint::Percentage(12),
+            Constraint::Percentage(18),
+        ]);
+    f.render_widget(table, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_gossip_proto::protocol::{DuplicateShred, ShredType};
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_duplicate_shred_rows() {
+        let reporter = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut tracker = DuplicateShredTracker::default();
+        for (from, slot, num_chunks, index) in [
+            (reporter, 100, 1, 0),
+            (other, 100, 2, 0),
+            (reporter, 200, 2, 1),
+        ] {
+            tracker.insert(
+                &DuplicateShred::new(from, 0, slot, ShredType::Code, num_chunks, index, vec![]),
+                0,
+            );
+        }
+
+        let rows = duplicate_shred_rows(&tracker);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "200");
+        assert_eq!(rows[0][2..], ["1/2", "partial"]);
+        assert_eq!(rows[1][0], "100");
+        assert_eq!(rows[2][0], "");
+        let complete = rows.iter().find(|row| row[2] == "1/1").unwrap();
+        assert_eq!(complete[1], short_pubkey(&reporter));
+        assert_eq!(complete[3], "complete");
+    }
+}
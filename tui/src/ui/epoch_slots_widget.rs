@@ -0,0 +1,188 @@
+use solana_gossip_proto::protocol::{slot_gaps, EpochSlots, Slot};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Gap ranges listed by the panel, the remaining ones are only counted.
+const MAX_GAPS: usize = 8;
+
+/// Slots reported by a node across all its `EpochSlots`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SlotsCoverage {
+    pub min: Slot,
+    pub max: Slot,
+    pub count: usize,
+    /// inclusive ranges of the missing slots, the first `MAX_GAPS` only
+    pub gaps: Vec<(Slot, Slot)>,
+    pub gap_count: usize,
+}
+
+impl SlotsCoverage {
+    /// Merge the `EpochSlots` of a node, none when no slot can be decoded.
+    pub fn new<'a>(epoch_slots: impl IntoIterator<Item = &'a EpochSlots>) -> Option<Self> {
+        let mut merged: Option<EpochSlots> = None;
+        for value in epoch_slots {
+            match &mut merged {
+                Some(merged) => merged.slots.extend(value.slots.iter().cloned()),
+                None => merged = Some(value.clone()),
+            }
+        }
+        let slots = merged?.to_slots();
+
+        Some(SlotsCoverage {
+            min: *slots.first()?,
+            max: *slots.last()?,
+            count: slots.len(),
+            gaps: slot_gaps(&slots).take(MAX_GAPS).collect(),
+            gap_count: slot_gaps(&slots).count(),
+        })
+    }
+
+    fn gaps_text(&self) -> String {
+        if self.gaps.is_empty() {
+            return String::from("none");
+        }
+        let text = self
+            .gaps
+            .iter()
+            .map(|(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{first}-{last}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.gap_count > self.gaps.len() {
+            format!("{text} … (+{})", self.gap_count - self.gaps.len())
+        } else {
+            text
+        }
+    }
+}
+
+fn line<'a>(name: &str, value: String) -> Spans<'a> {
+    Spans::from(vec![
+        Span::styled(format!("{name:>7}: "), Style::default().fg(Color::DarkGray)),
+        Span::raw(value),
+    ])
+}
+
+/// Render the slot range the node reports, with its gaps.
+pub fn render_epoch_slots<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    coverage: Option<&SlotsCoverage>,
+) {
+    let lines = match coverage {
+        Some(coverage) => vec![
+            line("min", coverage.min.to_string()),
+            line("max", coverage.max.to_string()),
+            line("count", coverage.count.to_string()),
+            line("gaps", coverage.gaps_text()),
+        ],
+        None => vec![Spans::from(Span::styled(
+            "no epoch slots",
+            Style::default().fg(Color::DarkGray),
+        ))],
+    };
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Epoch Slots "),
+    );
+    f.render_widget(paragraph, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use bv::BitVec;
+    use solana_gossip_proto::protocol::{CompressedSlots, Uncompressed};
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    fn epoch_slots(first_slot: Slot, set: &[u64]) -> EpochSlots {
+        let mut slots: BitVec<u8> = BitVec::new_fill(false, 16);
+        for index in set {
+            slots.set(*index, true);
+        }
+        EpochSlots {
+            from: Pubkey::new_unique(),
+            slots: vec![CompressedSlots::Uncompressed(Uncompressed {
+                first_slot,
+                num: 16,
+                slots,
+            })],
+            wallclock: 0,
+        }
+    }
+
+    #[test]
+    fn test_slots_coverage() {
+        assert_eq!(SlotsCoverage::new([]), None);
+        assert_eq!(SlotsCoverage::new([&epoch_slots(100, &[])]), None);
+
+        let first = epoch_slots(100, &[0, 1, 4, 6]);
+        let second = epoch_slots(116, &[0, 15]);
+        let coverage = SlotsCoverage::new([&first, &second]).unwrap();
+        assert_eq!(
+            coverage,
+            SlotsCoverage {
+                min: 100,
+                max: 131,
+                count: 6,
+                gaps: vec![(102, 103), (105, 105), (107, 115), (117, 130)],
+                gap_count: 4,
+            }
+        );
+        assert_eq!(coverage.gaps_text(), "102-103, 105, 107-115, 117-130");
+
+        // only the first gaps are listed
+        let set = (0..10).map(|index| index * 2).collect::<Vec<_>>();
+        let coverage =
+            SlotsCoverage::new([&epoch_slots(0, &set[..8]), &epoch_slots(16, &[0, 2, 4])]);
+        assert_eq!(
+            coverage.as_ref().map(|coverage| coverage.gap_count),
+            Some(10)
+        );
+        assert_eq!(
+            coverage.unwrap().gaps_text(),
+            "1, 3, 5, 7, 9, 11, 13, 15 … (+2)"
+        );
+
+        // chunks far apart are not expanded slot by slot
+        let coverage =
+            SlotsCoverage::new([&epoch_slots(0, &[0]), &epoch_slots(1_000_000_000_000, &[0])])
+                .unwrap();
+        assert_eq!(coverage.gaps, vec![(1, 999_999_999_999)]);
+    }
+
+    #[test]
+    fn test_render_epoch_slots() {
+        let backend = TestBackend::new(40, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let coverage = SlotsCoverage::new([&epoch_slots(100, &[0, 1, 4])]);
+        terminal
+            .draw(|f| render_epoch_slots(f, f.size(), coverage.as_ref()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 0).contains(" Epoch Slots "));
+        assert!(line(buffer, 1).contains("min: 100"));
+        assert!(line(buffer, 2).contains("max: 104"));
+        assert!(line(buffer, 3).contains("count: 3"));
+        assert!(line(buffer, 4).contains("gaps: 102-103"));
+    }
+}
@@ -0,0 +1,82 @@
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, BorderType, Borders, Cell, Clear, Row, Table},
+    Frame,
+};
+
+use crate::{stm::keymap::KeyBinding, ui::core::centered_rect};
+
+/// Render the keybindings in a popup centered over `area`.
+pub fn render_help_popup<B: Backend>(f: &mut Frame<B>, area: Rect, bindings: &[KeyBinding]) {
+    let bbox = centered_rect(60, 80, area);
+
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let rows = bindings.iter().map(|binding| {
+        Row::new(vec![
+            Cell::from(binding.label).style(key_style),
+            Cell::from(binding.description),
+        ])
+    });
+
+    let table = Table::new(rows)
+        .block(
+            Block::default()
+                .title(" Help - press any key to close ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        )
+        .column_spacing(2)
+        .widths(&[Constraint::Length(8), Constraint::Min(30)]);
+
+    f.render_widget(Clear, bbox);
+    f.render_widget(table, bbox);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::stm::keymap::HOME_KEY_BINDINGS;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_render_help_popup() {
+        let backend = TestBackend::new(100, 40);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|f| render_help_popup(f, f.size(), HOME_KEY_BINDINGS))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen = (0..40).map(|y| line(buffer, y)).collect::<Vec<_>>();
+        assert!(screen[4].contains(" Help - press any key to close "));
+        // every binding is listed
+        for binding in HOME_KEY_BINDINGS {
+            assert!(
+                screen.iter().any(|line| line.contains(binding.description)),
+                "{} is not listed",
+                binding.description
+            );
+        }
+        let line = screen
+            .iter()
+            .position(|line| line.contains("SPACE"))
+            .unwrap();
+        let x = u16::try_from(screen[line].find("SPACE").unwrap()).unwrap();
+        assert_eq!(
+            buffer.get(x, u16::try_from(line).unwrap()).fg,
+            Color::Yellow
+        );
+
+        // centered, the borders are away from the screen edges
+        assert_eq!(buffer.get(0, 3).symbol, " ");
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::Write as _;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Bytes shown on each line of the dump.
+pub const HEX_DUMP_WIDTH: usize = 16;
+
+/// One line per 16 bytes: the offset, the bytes in hex then their printable
+/// ASCII characters, the others shown as `.`.
+pub fn hex_dump_lines(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut line = format!("{:08x} ", i * HEX_DUMP_WIDTH);
+            for (j, byte) in chunk.iter().enumerate() {
+                // an extra space splits the line in two halves of 8 bytes
+                let gap = if j == HEX_DUMP_WIDTH / 2 { "  " } else { " " };
+                let _ = write!(line, "{gap}{byte:02x}");
+            }
+            let padding =
+                (HEX_DUMP_WIDTH - chunk.len()) * 3 + usize::from(chunk.len() <= HEX_DUMP_WIDTH / 2);
+            line.push_str(&" ".repeat(padding));
+            line.push_str("  |");
+            line.extend(chunk.iter().map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    char::from(*byte)
+                } else {
+                    '.'
+                }
+            }));
+            line.push('|');
+            line
+        })
+        .collect()
+}
+
+/// Render the hex dump of the bytes, scrolled down by `scroll` lines. The
+/// scroll is clamped so that the last line stays at the bottom of the area.
+pub fn render_hex_dump<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    bytes: &[u8],
+    scroll: u16,
+) {
+    let lines = hex_dump_lines(bytes);
+    let visible = area.height.saturating_sub(2);
+    let last = u16::try_from(lines.len())
+        .unwrap_or(u16::MAX)
+        .saturating_sub(visible);
+
+    let lines = if lines.is_empty() {
+        vec![Spans::from(Span::styled(
+            "none",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        lines.into_iter().map(Spans::from).collect()
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .scroll((scroll.min(last), 0));
+    f.render_widget(paragraph, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_hex_dump_lines() {
+        let bytes = (0u8..20).chain(*b"gossip").collect::<Vec<_>>();
+        let lines = hex_dump_lines(&bytes);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "00000000  00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f  |................|"
+        );
+        assert_eq!(
+            lines[1],
+            "00000010  10 11 12 13 67 6f 73 73  69 70                    |....gossip|"
+        );
+        // the ASCII column is aligned whatever the length of the last line
+        let short = hex_dump_lines(b"abc");
+        assert_eq!(short[0].find('|'), lines[0].find('|'));
+        assert!(hex_dump_lines(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_render_hex_dump() {
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let bytes = (0u8..=255).collect::<Vec<_>>();
+
+        let mut draw = |scroll| {
+            terminal
+                .draw(|f| render_hex_dump(f, f.size(), " Raw ", &bytes, scroll))
+                .unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            (0..5).map(|y| line(&buffer, y)).collect::<Vec<_>>()
+        };
+
+        let screen = draw(0);
+        assert!(screen[0].contains(" Raw "));
+        assert!(screen[1].contains("00000000"));
+        assert!(screen[3].contains("00000020"));
+
+        let screen = draw(2);
+        assert!(screen[1].contains("00000020"));
+
+        // scrolled past the end the last line stays at the bottom
+        let screen = draw(100);
+        assert!(screen[1].contains("000000d0"));
+        assert!(screen[3].contains("000000f0"));
+    }
+}
@@ -0,0 +1,172 @@
+use std::net::SocketAddr;
+
+use solana_gossip_proto::{protocol::is_valid_addr, utils::wallclock_age};
+use solana_sdk::pubkey::Pubkey;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use super::node_table_widget::{compact_addr, NodeRow};
+
+fn field<'a>(name: &str, value: &str) -> Vec<Span<'a>> {
+    vec![
+        Span::styled(
+            format!("{name} "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("{value}  ")),
+    ]
+}
+
+fn addr_value(addr: &SocketAddr) -> String {
+    if is_valid_addr(addr) {
+        compact_addr(addr)
+    } else {
+        String::from("—")
+    }
+}
+
+/// What gossip tells about the node, the contact info then its version and votes.
+pub fn identity_lines<'a>(row: Option<&NodeRow>) -> Vec<Spans<'a>> {
+    let Some(row) = row else {
+        return vec![Spans::from(Span::styled(
+            "not yet observed",
+            Style::default().fg(Color::Yellow),
+        ))];
+    };
+    let info = &row.info;
+    let none = || String::from("—");
+    vec![
+        Spans::from(
+            [
+                field("gossip", &addr_value(&info.gossip)),
+                field("tpu", &addr_value(&info.tpu)),
+                field("rpc", &addr_value(&info.rpc)),
+            ]
+            .concat(),
+        ),
+        Spans::from(
+            [
+                field(
+                    "version",
+                    &row.semver().map_or_else(none, |_| row.version_string()),
+                ),
+                field("shred_version", &info.shred_version.to_string()),
+                field(
+                    "wallclock",
+                    &format!("{}s ago", wallclock_age(info.wallclock).as_secs()),
+                ),
+            ]
+            .concat(),
+        ),
+        Spans::from(
+            [
+                field(
+                    "last vote",
+                    &row.last_vote.map_or_else(none, |slot| slot.to_string()),
+                ),
+                field(
+                    "lag",
+                    &row.vote_lag.map_or_else(none, |lag| lag.to_string()),
+                ),
+                field(
+                    "rtt",
+                    &row.peer_status
+                        .map_or_else(none, |status| status.to_string()),
+                ),
+            ]
+            .concat(),
+        ),
+    ]
+}
+
+/// Render the panel of the node given with `--identity`, `row` is none until
+/// its contact info is received.
+pub fn render_identity<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    identity: &Pubkey,
+    row: Option<&NodeRow>,
+) {
+    let paragraph = Paragraph::new(identity_lines(row)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" my node {identity} ")),
+    );
+    f.render_widget(paragraph, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+
+    use solana_gossip_proto::protocol::LegacyContactInfo;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ping::PeerStatus;
+    use crate::ui::core::screen;
+
+    fn text(spans: &Spans) -> String {
+        spans
+            .0
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_identity_lines() {
+        let lines = identity_lines(None);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(text(&lines[0]), "not yet observed");
+
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let info = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            gossip: SocketAddr::new(ip, 8001),
+            tpu: SocketAddr::new(ip, 8003),
+            shred_version: 50_093,
+            ..LegacyContactInfo::default()
+        };
+        let mut row = NodeRow::new(info, None);
+        row.last_vote = Some(1_000);
+        row.vote_lag = Some(3);
+        row.peer_status = Some(PeerStatus::Alive(Duration::from_millis(42)));
+
+        let lines = identity_lines(Some(&row))
+            .iter()
+            .map(text)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "gossip 141.98.219.218:8001  tpu 141.98.219.218:8003  rpc —  "
+        );
+        assert!(lines[1].starts_with("version —  shred_version 50093  wallclock "));
+        assert_eq!(lines[2], "last vote 1000  lag 3  rtt 42ms  ");
+    }
+
+    #[test]
+    fn test_render_identity() {
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let identity = Pubkey::new_unique();
+
+        terminal
+            .draw(|f| render_identity(f, f.size(), &identity, None))
+            .unwrap();
+
+        let screen = screen(terminal.backend().buffer());
+        assert!(screen.contains(&format!(" my node {identity} ")));
+        assert!(screen.contains("not yet observed"));
+    }
+}
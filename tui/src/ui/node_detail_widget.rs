@@ -0,0 +1,198 @@
+use solana_gossip_proto::{
+    protocol::{is_valid_addr, LegacyContactInfo, Version},
+    utils::wallclock_age,
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+fn field<'a>(name: &str, value: String, style: Style) -> Spans<'a> {
+    Spans::from(vec![
+        Span::styled(
+            format!("{name:>14}: "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(value, style),
+    ])
+}
+
+/// Fields which can be highlighted and copied, the pubkey then the service addresses.
+/// Unset addresses have no value.
+pub fn copyable_fields(info: &LegacyContactInfo) -> Vec<(&'static str, Option<String>)> {
+    let mut fields = vec![("pubkey", Some(info.id.to_string()))];
+    fields.extend(info.addrs().into_iter().map(|(name, addr)| {
+        let value = is_valid_addr(&addr).then(|| addr.to_string());
+        (name, value)
+    }));
+    fields
+}
+
+fn detail_lines<'a>(
+    info: &LegacyContactInfo,
+    version: Option<&Version>,
+    hostname: Option<&str>,
+    pruned_at: Option<u64>,
+) -> Vec<Spans<'a>> {
+    let dimmed = Style::default().fg(Color::DarkGray);
+
+    let version = version.map_or_else(
+        || String::from("—"),
+        |version| version.version.to_semver_string(),
+    );
+    let wallclock = format!(
+        "{} ({}s ago)",
+        info.wallclock,
+        wallclock_age(info.wallclock).as_secs()
+    );
+
+    let pruned = match pruned_at {
+        Some(wallclock) => field(
+            "pruned us",
+            format!("{wallclock} ({}s ago)", wallclock_age(wallclock).as_secs()),
+            Style::default().fg(Color::Yellow),
+        ),
+        None => field("pruned us", String::from("—"), dimmed),
+    };
+
+    let mut lines = vec![
+        field("pubkey", info.id.to_string(), Style::default()),
+        field("version", version, Style::default()),
+        field(
+            "shred_version",
+            info.shred_version.to_string(),
+            Style::default(),
+        ),
+        field("wallclock", wallclock, Style::default()),
+        pruned,
+        Spans::default(),
+    ];
+
+    lines.extend(
+        copyable_fields(info)
+            .into_iter()
+            .skip(1)
+            .map(|(name, value)| match (name, value, hostname) {
+                ("gossip", Some(value), Some(hostname)) => {
+                    let mut spans = field(name, value, Style::default());
+                    spans.0.push(Span::styled(format!(" ({hostname})"), dimmed));
+                    spans
+                }
+                (_, Some(value), _) => field(name, value, Style::default()),
+                (_, None, _) => field(name, String::from("—"), dimmed),
+            }),
+    );
+
+    lines
+}
+
+/// Render the node, `selected` is the index of the highlighted copyable field.
+/// The `hostname`, when resolved, is shown next to the gossip address and
+/// `pruned_at` is the wallclock of the prune the node sent us, if any.
+pub fn render_node_detail<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    info: &LegacyContactInfo,
+    version: Option<&Version>,
+    hostname: Option<&str>,
+    pruned_at: Option<u64>,
+    selected: Option<usize>,
+) {
+    let mut lines = detail_lines(info, version, hostname, pruned_at);
+
+    // the pubkey is the first line, the addresses follow the node info
+    let line = selected.map(|i| if i == 0 { 0 } else { i + 5 });
+    if let Some(spans) = line.and_then(|line| lines.get_mut(line)) {
+        for span in &mut spans.0 {
+            span.style = span.style.add_modifier(Modifier::REVERSED);
+        }
+    }
+
+    let paragraph =
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Node "));
+    f.render_widget(paragraph, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_render_node_detail() {
+        let backend = TestBackend::new(80, 18);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let ip = IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218));
+        let info = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            gossip: SocketAddr::new(ip, 8001),
+            rpc: SocketAddr::new(ip, 8899),
+            shred_version: 50_093,
+            ..LegacyContactInfo::default()
+        };
+
+        terminal
+            .draw(|f| {
+                render_node_detail(
+                    f,
+                    f.size(),
+                    &info,
+                    None,
+                    Some("node.example.com"),
+                    Some(1_681_834_717_999),
+                    Some(10),
+                );
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+
+        assert!(line(buffer, 1).contains(&info.id.to_string()));
+        assert!(line(buffer, 2).contains("version: —"));
+        assert!(line(buffer, 3).contains("shred_version: 50093"));
+        assert!(line(buffer, 5).contains("pruned us: 1681834717999"));
+        assert!(line(buffer, 7).contains("gossip: 141.98.219.218:8001 (node.example.com)"));
+        assert!(line(buffer, 8).contains("tvu: —"));
+        assert!(line(buffer, 14).contains("rpc: 141.98.219.218:8899"));
+
+        // unset addresses are dimmed
+        let dash = line(buffer, 8).find('—').unwrap();
+        let x = u16::try_from(line(buffer, 8)[..dash].chars().count()).unwrap();
+        assert_eq!(buffer.get(x, 8).fg, Color::DarkGray);
+        assert_eq!(buffer.get(x, 14).fg, Color::Reset);
+
+        // the selected field is highlighted
+        assert!(line(buffer, 16).contains("serve_repair: —"));
+        assert!(buffer.get(2, 16).modifier.contains(Modifier::REVERSED));
+        assert!(!buffer.get(2, 15).modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_copyable_fields() {
+        let info = LegacyContactInfo {
+            id: Pubkey::new_unique(),
+            rpc: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218)), 8899),
+            ..LegacyContactInfo::default()
+        };
+
+        let fields = copyable_fields(&info);
+        assert_eq!(fields.len(), 11);
+        assert_eq!(fields[0], ("pubkey", Some(info.id.to_string())));
+        assert_eq!(fields[1], ("gossip", None));
+        assert_eq!(
+            fields[8],
+            ("rpc", Some(String::from("141.98.219.218:8899")))
+        );
+    }
+}
@@ -0,0 +1,1357 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use solana_gossip_proto::{
+    crds::{CrdsTable, CrdsValueLabel, VoteTracker},
+    protocol::{
+        is_routable, CrdsData, LegacyContactInfo, LegacyVersion, LowestSlot, Slot, Version,
+    },
+    utils::wallclock_age,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use super::core::short_pubkey;
+use crate::{config::Freshness, ping::PeerStatus};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    Frame,
+};
+
+const NODE_TABLE_HEADERS: [&str; 10] = [
+    "Node Identifier",
+    "Gossip",
+    "Svc",
+    "ShredVer",
+    "Version",
+    "Age",
+    "RTT",
+    "Lowest",
+    "Vote",
+    "Lag",
+];
+/// Index at which the country column is inserted when enabled.
+const COUNTRY_COLUMN: usize = 3;
+/// Slots a node must keep beyond the most pruned one to count as full history,
+/// about an epoch.
+pub const FULL_HISTORY_MARGIN: Slot = 432_000;
+
+/// A discovered node, its contact info and the version it advertises.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeRow {
+    pub info: LegacyContactInfo,
+    pub version: Option<Version>,
+    /// advertised by nodes which predate the `Version` value
+    pub legacy_version: Option<LegacyVersion>,
+    pub country: Option<String>,
+    /// outcome of the ping sent to the node
+    pub peer_status: Option<PeerStatus>,
+    /// oldest slot the node still serves
+    pub lowest_slot: Option<Slot>,
+    /// latest slot voted by the node
+    pub last_vote: Option<Slot>,
+    /// slots the latest vote is behind the cluster tip
+    pub vote_lag: Option<Slot>,
+    /// shred version advertised before the current one, when it changed
+    pub previous_shred_version: Option<u16>,
+}
+
+impl NodeRow {
+    pub fn new(info: LegacyContactInfo, version: Option<&Version>) -> Self {
+        NodeRow {
+            info,
+            version: version.cloned(),
+            legacy_version: None,
+            country: None,
+            peer_status: None,
+            lowest_slot: None,
+            last_vote: None,
+            vote_lag: None,
+            previous_shred_version: None,
+        }
+    }
+
+    /// Fill the values received before the contact info of the node.
+    pub fn backfill(&mut self, crds: &CrdsTable, votes: &VoteTracker) {
+        let id = self.info.id;
+        let data = |label| crds.get(&label).map(|value| &value.data);
+        if let Some(CrdsData::Version(version)) = data(CrdsValueLabel::Version(id)) {
+            self.version = Some(version.clone());
+        }
+        if let Some(CrdsData::LegacyVersion(version)) = data(CrdsValueLabel::LegacyVersion(id)) {
+            self.legacy_version = Some(version.clone());
+        }
+        if let Some(CrdsData::LowestSlot(_, slots)) = data(CrdsValueLabel::LowestSlot(id)) {
+            self.lowest_slot = Some(slots.lowest);
+        }
+        if let Some((slot, _)) = votes.last_vote(&id) {
+            self.last_vote = self.last_vote.max(Some(slot));
+        }
+    }
+
+    /// Major, minor and patch of the advertised version, the legacy one as a fallback.
+    pub fn semver(&self) -> Option<(u16, u16, u16)> {
+        match (&self.version, &self.legacy_version) {
+            (Some(Version { version: v, .. }), _) => Some((v.major, v.minor, v.patch)),
+            (None, Some(LegacyVersion { version: v, .. })) => Some((v.major, v.minor, v.patch)),
+            (None, None) => None,
+        }
+    }
+
+    pub fn version_string(&self) -> String {
+        self.semver().map_or_else(
+            || String::from(" - "),
+            |(major, minor, patch)| format!("{major}.{minor}.{patch}"),
+        )
+    }
+
+    fn cells(&self, show_country: bool) -> Vec<String> {
+        let mut cells = vec![
+            short_pubkey(&self.info.id),
+            compact_addr(&self.info.gossip),
+            // the nodes able to serve shreds are marked with R
+            format!(
+                "{}{}",
+                self.info.advertised_service_count(),
+                if self.info.repair_addrs().is_some() {
+                    "R"
+                } else {
+                    ""
+                }
+            ),
+            match self.previous_shred_version {
+                Some(_) => format!("{}!", self.info.shred_version),
+                None => format!("{}", self.info.shred_version),
+            },
+            self.version_string(),
+            format!("{}s", wallclock_age(self.info.wallclock).as_secs()),
+            self.peer_status
+                .map_or_else(|| String::from(" - "), |status| status.to_string()),
+            self.lowest_slot
+                .map_or_else(|| String::from(" - "), |slot| slot.to_string()),
+            self.last_vote
+                .map_or_else(|| String::from(" - "), |slot| slot.to_string()),
+            self.vote_lag
+                .map_or_else(|| String::from(" - "), |lag| lag.to_string()),
+        ];
+        if show_country {
+            let country = self.country.clone().unwrap_or_else(|| String::from(" - "));
+            cells.insert(COUNTRY_COLUMN, country);
+        }
+        cells
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    Pubkey,
+    GossipIp,
+    Version,
+    #[default]
+    Age,
+}
+
+impl SortKey {
+    /// Next sort column, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Pubkey => SortKey::GossipIp,
+            SortKey::GossipIp => SortKey::Version,
+            SortKey::Version => SortKey::Age,
+            SortKey::Age => SortKey::Pubkey,
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SortKey::Pubkey => write!(f, "Pubkey"),
+            SortKey::GossipIp => write!(f, "IP"),
+            SortKey::Version => write!(f, "Version"),
+            SortKey::Age => write!(f, "Age"),
+        }
+    }
+}
+
+fn compare_nodes(a: &NodeRow, b: &NodeRow, key: SortKey) -> Ordering {
+    match key {
+        SortKey::Pubkey => a.info.id.cmp(&b.info.id),
+        // numeric comparison of the octets, then the port
+        SortKey::GossipIp => a.info.gossip.cmp(&b.info.gossip),
+        // semantic ordering of the shown version, nodes without a version first
+        SortKey::Version => a.semver().cmp(&b.semver()),
+        // youngest first
+        SortKey::Age => b.info.wallclock.cmp(&a.info.wallclock),
+    }
+}
+
+pub fn sort_nodes(rows: &mut [NodeRow], key: SortKey, descending: bool) {
+    rows.sort_by(|a, b| {
+        let ordering = compare_nodes(a, b, key);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Address as shown in the table: IPv6 with its zeros compressed and without
+/// the scope, IPv4-mapped IPv6 as plain IPv4.
+pub fn compact_addr(addr: &SocketAddr) -> String {
+    match addr.ip().to_canonical() {
+        IpAddr::V4(ip) => format!("{ip}:{}", addr.port()),
+        IpAddr::V6(ip) => format!("[{ip}]:{}", addr.port()),
+    }
+}
+
+/// Nodes whose base58 pubkey or gossip address contains the query, ignoring case.
+pub fn filter_nodes<'a>(rows: &'a [NodeRow], query: &str) -> Vec<&'a NodeRow> {
+    let query = query.to_lowercase();
+    rows.iter()
+        .filter(|row| {
+            query.is_empty()
+                || row.info.id.to_string().to_lowercase().contains(&query)
+                || compact_addr(&row.info.gossip).contains(&query)
+        })
+        .collect()
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default)]
+pub struct StatefulNodeTable {
+    pub state: TableState,
+    pub rows: Vec<NodeRow>,
+    pub sort_key: SortKey,
+    pub sort_descending: bool,
+    pub filter: String,
+    pub show_country: bool,
+    /// when set only the nodes serving slots below it are shown
+    pub full_history_below: Option<Slot>,
+    /// when set only the nodes exposing a JSON-RPC endpoint are shown
+    pub rpc_only: bool,
+    /// when set the nodes with a loopback or private gossip address are hidden
+    pub routable_only: bool,
+    /// node pinned at the top of the table
+    pub followed: Option<Pubkey>,
+    /// rows shown by the last render, the page up and down step
+    pub page_size: usize,
+    /// where the last render drew the table
+    area: Rect,
+    /// first row drawn, the table widget keeps its own private
+    offset: usize,
+    /// rows changed since the last sort
+    dirty: bool,
+}
+
+impl StatefulNodeTable {
+    /// Add the node or refresh its contact info when the received one is newer,
+    /// returns true when the node is added.
+    pub fn upsert_contact_info(&mut self, info: LegacyContactInfo) -> bool {
+        let added = match self.rows.iter_mut().find(|row| row.info.id == info.id) {
+            Some(row) if row.info.wallclock < info.wallclock => {
+                row.info = info;
+                false
+            }
+            Some(_) => return false,
+            None => {
+                self.rows.push(NodeRow::new(info, None));
+                true
+            }
+        };
+        self.dirty = true;
+        added
+    }
+
+    /// Fill the node from the values stored before its contact info was received.
+    pub fn backfill(&mut self, id: Pubkey, crds: &CrdsTable, votes: &VoteTracker) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == id) {
+            row.backfill(crds, votes);
+            self.dirty = true;
+        }
+    }
+
+    /// Attach the version to the already known node.
+    pub fn update_version(&mut self, version: &Version) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == version.from) {
+            row.version = Some(version.clone());
+            self.dirty = true;
+        }
+    }
+
+    /// Attach the legacy version to the already known node.
+    pub fn update_legacy_version(&mut self, version: &LegacyVersion) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == version.from) {
+            row.legacy_version = Some(version.clone());
+            self.dirty = true;
+        }
+    }
+
+    /// Attach the lowest slot to the already known node.
+    pub fn update_lowest_slot(&mut self, slots: &LowestSlot) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == slots.from) {
+            row.lowest_slot = Some(slots.lowest);
+        }
+    }
+
+    /// Attach the voted slot to the already known node, the votes are not
+    /// received in order so only the latest slot is kept.
+    pub fn update_last_vote(&mut self, from: Pubkey, slot: Slot) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == from) {
+            row.last_vote = row.last_vote.max(Some(slot));
+        }
+    }
+
+    /// Refresh the vote lag of every node against the cluster tip.
+    pub fn update_vote_lags(&mut self, votes: &VoteTracker, tip: Option<Slot>) {
+        for row in &mut self.rows {
+            row.vote_lag = tip.and_then(|tip| votes.lag(&row.info.id, tip));
+        }
+    }
+
+    /// Flag the nodes whose shred version changed to the advertised one.
+    pub fn update_shred_version_changes(&mut self, changes: &[(Pubkey, u16, u16)]) {
+        for row in &mut self.rows {
+            row.previous_shred_version = changes
+                .iter()
+                .rev()
+                .find(|(id, _, new)| *id == row.info.id && *new == row.info.shred_version)
+                .map(|(_, old, _)| *old);
+        }
+    }
+
+    /// Toggle the full history filter, its threshold is `FULL_HISTORY_MARGIN`
+    /// slots below the highest lowest slot, the most pruned node.
+    pub fn toggle_full_history(&mut self) {
+        let selected = self.selected_id();
+        self.full_history_below = match self.full_history_below {
+            Some(_) => None,
+            None => self
+                .rows
+                .iter()
+                .filter_map(|row| row.lowest_slot)
+                .max()
+                .map(|highest| highest.saturating_sub(FULL_HISTORY_MARGIN)),
+        };
+        self.reselect(selected);
+    }
+
+    pub fn toggle_rpc_only(&mut self) {
+        let selected = self.selected_id();
+        self.rpc_only = !self.rpc_only;
+        self.reselect(selected);
+    }
+
+    pub fn toggle_routable_only(&mut self) {
+        let selected = self.selected_id();
+        self.routable_only = !self.routable_only;
+        self.reselect(selected);
+    }
+
+    /// Keep only the nodes matching the predicate.
+    pub fn retain<F: FnMut(&NodeRow) -> bool>(&mut self, f: F) {
+        let selected = self.selected_id();
+        self.rows.retain(f);
+        self.reselect(selected);
+    }
+
+    pub fn set_peer_status(&mut self, id: Pubkey, status: PeerStatus) {
+        if let Some(row) = self.rows.iter_mut().find(|row| row.info.id == id) {
+            row.peer_status = Some(status);
+        }
+    }
+
+    /// Set the country of the nodes whose gossip address is on the ip.
+    pub fn set_country(&mut self, ip: IpAddr, country: &str) {
+        for row in self
+            .rows
+            .iter_mut()
+            .filter(|row| row.info.gossip.ip() == ip)
+        {
+            row.country = Some(country.to_string());
+        }
+    }
+
+    pub fn next_sort_key(&mut self) {
+        self.sort_key = self.sort_key.next();
+        self.sort();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.sort();
+    }
+
+    pub fn set_filter(&mut self, query: String) {
+        let selected = self.selected_id();
+        self.filter = query;
+        self.reselect(selected);
+    }
+
+    /// Pin the node at the top of the table and select it, false when the
+    /// node is not known.
+    pub fn follow(&mut self, id: Pubkey) -> bool {
+        if !self.rows.iter().any(|row| row.info.id == id) {
+            return false;
+        }
+        self.followed = Some(id);
+        self.reselect(Some(id));
+        true
+    }
+
+    pub fn unfollow(&mut self) {
+        let selected = self.selected_id();
+        self.followed = None;
+        self.reselect(selected);
+    }
+
+    /// Rows matching the current filters, in display order.
+    pub fn visible_rows(&self) -> Vec<&NodeRow> {
+        let mut rows = filter_nodes(&self.rows, &self.filter);
+        if let Some(below) = self.full_history_below {
+            rows.retain(|row| row.lowest_slot.is_some_and(|lowest| lowest < below));
+        }
+        if self.rpc_only {
+            rows.retain(|row| row.info.has_rpc());
+        }
+        if self.routable_only {
+            rows.retain(|row| is_routable(&row.info.gossip));
+        }
+        if let Some(followed) = self.followed {
+            if let Some(i) = rows.iter().position(|row| row.info.id == followed) {
+                let row = rows.remove(i);
+                rows.insert(0, row);
+            }
+        }
+        rows
+    }
+
+    /// Sort the rows changed since the last sort, called once per frame rather
+    /// than on every received value.
+    pub fn sort_if_dirty(&mut self) {
+        if self.dirty {
+            self.sort();
+        }
+    }
+
+    /// Sort the rows keeping the selection on the same node.
+    fn sort(&mut self) {
+        let selected = self.selected_id();
+        sort_nodes(&mut self.rows, self.sort_key, self.sort_descending);
+        self.reselect(selected);
+        self.dirty = false;
+    }
+
+    fn selected_id(&self) -> Option<Pubkey> {
+        self.selected().map(|row| row.info.id)
+    }
+
+    fn reselect(&mut self, id: Option<Pubkey>) {
+        let i = id.and_then(|id| self.visible_rows().iter().position(|row| row.info.id == id));
+        self.state.select(i);
+    }
+
+    pub fn next_row(&mut self) {
+        let len = self.visible_rows().len();
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        if i < len {
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn previous_row(&mut self) {
+        let len = self.visible_rows().len();
+        let i = match self.state.selected() {
+            Some(0) | None => len.saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        if i < len {
+            self.state.select(Some(i));
+        }
+    }
+
+    /// Move the selection down or up by `rows`, stopping at the edges.
+    fn move_selection(&mut self, rows: usize, down: bool) {
+        let len = self.visible_rows().len();
+        if len > 0 {
+            let i = self.state.selected().map_or(0, |i| {
+                if down {
+                    (i + rows).min(len - 1)
+                } else {
+                    i.saturating_sub(rows)
+                }
+            });
+            self.state.select(Some(i));
+        }
+    }
+
+    /// Move down by a page, stopping on the last row.
+    pub fn next_page(&mut self) {
+        self.move_selection(self.page_size.max(1), true);
+    }
+
+    /// Move up by a page, stopping on the first row.
+    pub fn previous_page(&mut self) {
+        self.move_selection(self.page_size.max(1), false);
+    }
+
+    /// Move down by a row, the mouse wheel does not wrap around.
+    pub fn scroll_down(&mut self) {
+        self.move_selection(1, true);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.move_selection(1, false);
+    }
+
+    /// Record where the table was drawn, and follow its scroll offset as the
+    /// widget does, each row being one line high.
+    pub fn set_render_area(&mut self, area: Rect) {
+        self.area = area;
+        self.page_size = node_table_page_size(area);
+        let last = self.visible_rows().len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0).min(last);
+        let page_size = self.page_size.max(1);
+        let mut offset = self.offset.min(last);
+        if selected >= offset + page_size {
+            offset = selected + 1 - page_size;
+        }
+        self.offset = offset.min(selected);
+    }
+
+    /// Index of the visible row drawn at the terminal cell, none over the
+    /// borders, the header or below the last row.
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.area;
+        let first = area.y + 3;
+        if column <= area.x || column + 1 >= area.right() || row < first || row + 1 >= area.bottom()
+        {
+            return None;
+        }
+        let i = self.offset + usize::from(row - first);
+        (i < self.visible_rows().len()).then_some(i)
+    }
+
+    pub fn first_row(&mut self) {
+        if !self.visible_rows().is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn last_row(&mut self) {
+        let len = self.visible_rows().len();
+        if len > 0 {
+            self.state.select(Some(len - 1));
+        }
+    }
+
+    /// The highlighted node, if any.
+    pub fn selected(&self) -> Option<&NodeRow> {
+        let i = self.state.selected()?;
+        self.visible_rows().get(i).copied()
+    }
+
+    pub fn unselect(&mut self) {
+        self.state.select(None);
+    }
+}
+
+/// Green while the node is fresh, yellow while aging and red once stale.
+pub fn freshness_style(age: Duration, freshness: &Freshness) -> Style {
+    let color = if age < freshness.fresh {
+        Color::Green
+    } else if age <= freshness.stale {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    Style::default().fg(color)
+}
+
+/// Rows fitting in the area of the table, inside the borders and below the header.
+pub fn node_table_page_size(area: Rect) -> usize {
+    usize::from(area.height.saturating_sub(4))
+}
+
+/// Rows are colored by freshness, unless `freshness` is none.
+pub fn render_node_table<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    title: &str,
+    nodes: &[&NodeRow],
+    state: &mut TableState,
+    show_country: bool,
+    freshness: Option<&Freshness>,
+) {
+    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+    let header_style = Style::default().bg(Color::LightBlue);
+    let mut headers = NODE_TABLE_HEADERS.to_vec();
+    if show_country {
+        headers.insert(COUNTRY_COLUMN, "Country");
+    }
+    let header_cells = headers
+        .into_iter()
+        .map(|h| Cell::from(h).style(Style::default().fg(Color::White)));
+
+    let header = Row::new(header_cells)
+        .style(header_style)
+        .height(1)
+        .bottom_margin(1);
+
+    let rows = nodes.iter().map(|node| {
+        let cells = node.cells(show_country).into_iter().map(Cell::from);
+        let style = freshness.map_or_else(Style::default, |freshness| {
+            freshness_style(wallclock_age(node.info.wallclock), freshness)
+        });
+        Row::new(cells).style(style).height(1_u16).bottom_margin(0)
+    });
+
+    let widths = if show_country {
+        vec![
+            // short_pubkey
+            Constraint::Length(9),
+            Constraint::Percentage(19),
+            Constraint::Percentage(4),
+            Constraint::Percentage(8),
+            Constraint::Percentage(7),
+            Constraint::Percentage(8),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(9),
+        ]
+    } else {
+        vec![
+            // short_pubkey
+            Constraint::Length(9),
+            Constraint::Percentage(25),
+            Constraint::Percentage(4),
+            Constraint::Percentage(8),
+            Constraint::Percentage(9),
+            Constraint::Percentage(7),
+            Constraint::Percentage(7),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(9),
+        ]
+    };
+
+    let t = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(selected_style)
+        .widths(&widths);
+    f.render_stateful_widget(t, area, state);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    use solana_gossip_proto::utils::since_the_epoch_millis;
+    use tui::{backend::TestBackend, Terminal};
+
+    use solana_gossip_proto::protocol::{CrdsValue, LegacyVersion1, LegacyVersion2};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    fn create_info(id: Pubkey, wallclock: u64) -> LegacyContactInfo {
+        LegacyContactInfo {
+            id,
+            gossip: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(141, 98, 219, 218)), 8001),
+            wallclock,
+            shred_version: 50_093,
+            ..LegacyContactInfo::default()
+        }
+    }
+
+    fn create_version(from: Pubkey) -> Version {
+        Version {
+            from,
+            wallclock: 0,
+            version: LegacyVersion2 {
+                major: 1,
+                minor: 18,
+                patch: 23,
+                commit: None,
+                feature_set: 0,
+            },
+        }
+    }
+
+    fn create_legacy_version(from: Pubkey) -> LegacyVersion {
+        LegacyVersion {
+            from,
+            wallclock: 0,
+            version: LegacyVersion1 {
+                major: 1,
+                minor: 14,
+                patch: 17,
+                commit: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_node_row() {
+        let id = Pubkey::new_unique();
+        let row = NodeRow::new(create_info(id, 0), None);
+        assert_eq!(row.info.id, id);
+        assert_eq!(row.version_string(), " - ");
+
+        let row = NodeRow::new(create_info(id, 0), Some(&create_version(id)));
+        assert_eq!(row.version_string(), "1.18.23");
+    }
+
+    #[test]
+    fn test_node_row_legacy_version() {
+        let id = Pubkey::new_unique();
+        let mut table = StatefulNodeTable::default();
+        table.upsert_contact_info(create_info(id, 0));
+
+        table.update_legacy_version(&create_legacy_version(id));
+        assert_eq!(table.rows[0].semver(), Some((1, 14, 17)));
+        assert_eq!(table.rows[0].version_string(), "1.14.17");
+
+        // the newer version value wins
+        table.update_version(&create_version(id));
+        assert_eq!(table.rows[0].semver(), Some((1, 18, 23)));
+    }
+
+    #[test]
+    fn test_stateful_node_table_backfill() {
+        let keypair = Keypair::new();
+        let id = keypair.pubkey();
+        let mut crds = CrdsTable::default();
+        let mut votes = VoteTracker::default();
+        for data in [
+            CrdsData::Version(create_version(id)),
+            CrdsData::LegacyVersion(create_legacy_version(id)),
+            CrdsData::LowestSlot(0, LowestSlot::new(id, 1_000, 0)),
+        ] {
+            crds.insert(CrdsValue::new_signed(data, &keypair)).unwrap();
+        }
+        votes.update(id, 2_000, 0);
+
+        // the values received before the contact info are not attached
+        let mut table = StatefulNodeTable::default();
+        table.update_lowest_slot(&LowestSlot::new(id, 1_000, 0));
+        assert!(table.upsert_contact_info(create_info(id, 0)));
+        assert_eq!(table.rows[0].lowest_slot, None);
+
+        table.backfill(id, &crds, &votes);
+        let row = &table.rows[0];
+        assert_eq!(row.version, Some(create_version(id)));
+        assert_eq!(row.legacy_version, Some(create_legacy_version(id)));
+        assert_eq!(row.lowest_slot, Some(1_000));
+        assert_eq!(row.last_vote, Some(2_000));
+        assert!(!table.upsert_contact_info(create_info(id, 10)));
+    }
+
+    #[test]
+    fn test_stateful_node_table() {
+        let mut table = StatefulNodeTable::default();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        table.upsert_contact_info(create_info(first, 10));
+        table.upsert_contact_info(create_info(second, 10));
+        table.upsert_contact_info(create_info(first, 20));
+        table.upsert_contact_info(create_info(first, 15));
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].info.wallclock, 20);
+
+        table.update_version(&create_version(second));
+        table.update_version(&create_version(Pubkey::new_unique()));
+        assert_eq!(table.rows[0].version, None);
+        assert_eq!(table.rows[1].version_string(), "1.18.23");
+
+        table.set_peer_status(second, PeerStatus::Alive(Duration::from_millis(42)));
+        assert_eq!(table.rows[0].peer_status, None);
+        assert_eq!(table.rows[1].cells(false)[6], "42ms");
+
+        assert_eq!(table.state.selected(), None);
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(0));
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(1));
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(0));
+        table.previous_row();
+        assert_eq!(table.state.selected(), Some(1));
+        table.unselect();
+        assert_eq!(table.state.selected(), None);
+    }
+
+    #[test]
+    fn test_stateful_node_table_row_at() {
+        let mut table = StatefulNodeTable::default();
+        for wallclock in 0..25 {
+            table.upsert_contact_info(create_info(Pubkey::new_unique(), wallclock));
+        }
+        // 10 rows below the border and the header, from y 13
+        let area = Rect::new(5, 10, 60, 14);
+        table.set_render_area(area);
+        assert_eq!(table.row_at(6, 13), Some(0));
+        assert_eq!(table.row_at(63, 22), Some(9));
+        for (column, row) in [(5, 13), (64, 13), (6, 12), (6, 23)] {
+            assert_eq!(table.row_at(column, row), None);
+        }
+
+        // the selection below the page scrolls the rows
+        table.state.select(Some(14));
+        table.set_render_area(area);
+        assert_eq!(table.row_at(6, 13), Some(5));
+        assert_eq!(table.row_at(6, 22), Some(14));
+        // and it is kept while the selection moves within the page
+        table.state.select(Some(8));
+        table.set_render_area(area);
+        assert_eq!(table.row_at(6, 13), Some(5));
+        table.state.select(Some(2));
+        table.set_render_area(area);
+        assert_eq!(table.row_at(6, 13), Some(2));
+
+        // the rows past the end are not hit
+        table.set_filter(table.rows[3].info.id.to_string());
+        table.state.select(Some(0));
+        table.set_render_area(area);
+        assert_eq!(table.row_at(6, 13), Some(0));
+        assert_eq!(table.row_at(6, 14), None);
+    }
+
+    #[test]
+    fn test_stateful_node_table_pages() {
+        let mut table = StatefulNodeTable::default();
+        table.next_page();
+        table.last_row();
+        assert_eq!(table.state.selected(), None);
+
+        for wallclock in 0..25 {
+            table.upsert_contact_info(create_info(Pubkey::new_unique(), wallclock));
+        }
+        table.page_size = node_table_page_size(Rect::new(0, 0, 80, 14));
+        assert_eq!(table.page_size, 10);
+
+        table.next_page();
+        assert_eq!(table.state.selected(), Some(0));
+        table.next_page();
+        assert_eq!(table.state.selected(), Some(10));
+        table.next_page();
+        table.next_page();
+        assert_eq!(table.state.selected(), Some(24));
+        table.previous_page();
+        assert_eq!(table.state.selected(), Some(14));
+        table.previous_page();
+        table.previous_page();
+        assert_eq!(table.state.selected(), Some(0));
+
+        table.last_row();
+        assert_eq!(table.state.selected(), Some(24));
+        // the arrows wrap around at the edges
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(0));
+        table.previous_row();
+        assert_eq!(table.state.selected(), Some(24));
+        table.first_row();
+        assert_eq!(table.state.selected(), Some(0));
+
+        // the wheel stops at the edges
+        table.scroll_up();
+        assert_eq!(table.state.selected(), Some(0));
+        table.scroll_down();
+        assert_eq!(table.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_sort_nodes_by_ip() {
+        let create_row = |a, b, c, d, port| {
+            let mut info = create_info(Pubkey::new_unique(), 0);
+            info.gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(a, b, c, d)), port);
+            NodeRow::new(info, None)
+        };
+        let mut rows = vec![
+            create_row(100, 1, 1, 1, 8001),
+            create_row(9, 1, 1, 1, 8001),
+            create_row(10, 1, 1, 1, 8001),
+            create_row(10, 1, 1, 1, 8000),
+        ];
+
+        sort_nodes(&mut rows, SortKey::GossipIp, false);
+        let addrs = rows
+            .iter()
+            .map(|row| row.info.gossip.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            addrs,
+            vec![
+                "9.1.1.1:8001",
+                "10.1.1.1:8000",
+                "10.1.1.1:8001",
+                "100.1.1.1:8001"
+            ]
+        );
+
+        sort_nodes(&mut rows, SortKey::GossipIp, true);
+        assert_eq!(rows[0].info.gossip.to_string(), "100.1.1.1:8001");
+    }
+
+    #[test]
+    fn test_sort_nodes_by_version() {
+        let create_row = |minor, patch| {
+            let id = Pubkey::new_unique();
+            let mut version = create_version(id);
+            version.version.minor = minor;
+            version.version.patch = patch;
+            NodeRow::new(create_info(id, 0), Some(&version))
+        };
+        let mut rows = vec![
+            create_row(18, 23),
+            create_row(9, 30),
+            NodeRow::new(create_info(Pubkey::new_unique(), 0), None),
+            create_row(18, 3),
+        ];
+        // sorted by the shown legacy version when there is no version
+        let id = Pubkey::new_unique();
+        let mut legacy = NodeRow::new(create_info(id, 0), None);
+        legacy.legacy_version = Some(create_legacy_version(id));
+        rows.push(legacy);
+
+        sort_nodes(&mut rows, SortKey::Version, false);
+        let versions = rows.iter().map(NodeRow::version_string).collect::<Vec<_>>();
+        assert_eq!(
+            versions,
+            vec![" - ", "1.9.30", "1.14.17", "1.18.3", "1.18.23"]
+        );
+
+        sort_nodes(&mut rows, SortKey::Version, true);
+        assert_eq!(rows[0].version_string(), "1.18.23");
+    }
+
+    #[test]
+    fn test_stateful_node_table_sort() {
+        let mut table = StatefulNodeTable::default();
+        assert_eq!(table.sort_key, SortKey::Age);
+
+        let old = Pubkey::new_unique();
+        let young = Pubkey::new_unique();
+        table.upsert_contact_info(create_info(old, 10));
+        table.upsert_contact_info(create_info(young, 20));
+        // sorted once for all the upserts
+        assert_eq!(table.rows[0].info.id, old);
+        table.sort_if_dirty();
+        assert_eq!(table.rows[0].info.id, young);
+
+        // the selection follows the node
+        table.next_row();
+        table.toggle_sort_order();
+        assert_eq!(table.rows[0].info.id, old);
+        assert_eq!(table.state.selected(), Some(1));
+
+        table.next_sort_key();
+        assert_eq!(table.sort_key, SortKey::Pubkey);
+        table.next_sort_key();
+        table.next_sort_key();
+        table.next_sort_key();
+        assert_eq!(table.sort_key, SortKey::Age);
+    }
+
+    #[test]
+    fn test_filter_nodes() {
+        let first = create_info(Pubkey::new_unique(), 0);
+        let mut second = create_info(Pubkey::new_unique(), 0);
+        second.gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(72, 20, 2, 47)), 8000);
+        let rows = vec![
+            NodeRow::new(first.clone(), None),
+            NodeRow::new(second, None),
+        ];
+
+        assert_eq!(filter_nodes(&rows, "").len(), 2);
+        assert_eq!(filter_nodes(&rows, "72.20").len(), 1);
+        assert_eq!(filter_nodes(&rows, ":8001")[0].info.id, first.id);
+
+        let pubkey = first.id.to_string();
+        let query = pubkey[2..10].to_uppercase();
+        assert!(filter_nodes(&rows, &query)
+            .iter()
+            .any(|row| row.info.id == first.id));
+        assert!(filter_nodes(&rows, "not-a-match").is_empty());
+    }
+
+    #[test]
+    fn test_compact_addr() {
+        let v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(72, 20, 2, 47)), 8000);
+        assert_eq!(compact_addr(&v4), "72.20.2.47:8000");
+
+        let v6: SocketAddr = "[2001:0db8:0000:0000:0000:0000:0000:0001]:8001"
+            .parse()
+            .unwrap();
+        assert_eq!(compact_addr(&v6), "[2001:db8::1]:8001");
+
+        let scoped: SocketAddr = "[fe80::1%2]:8001".parse().unwrap();
+        assert_eq!(compact_addr(&scoped), "[fe80::1]:8001");
+
+        let mapped: SocketAddr = "[::ffff:72.20.2.47]:8000".parse().unwrap();
+        assert_eq!(compact_addr(&mapped), "72.20.2.47:8000");
+    }
+
+    #[test]
+    fn test_node_row_repair_capable() {
+        let mut info = create_info(Pubkey::new_unique(), 0);
+        let services = info.advertised_service_count();
+        assert_eq!(
+            NodeRow::new(info.clone(), None).cells(false)[2],
+            services.to_string()
+        );
+
+        info.tvu = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(72, 20, 2, 47)), 8002);
+        info.serve_repair = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(72, 20, 2, 47)), 8008);
+        assert_eq!(
+            NodeRow::new(info, None).cells(false)[2],
+            format!("{}R", services + 2)
+        );
+    }
+
+    #[test]
+    fn test_ipv6_node_row() {
+        let mut info = create_info(Pubkey::new_unique(), 0);
+        info.gossip = "[2001:db8::1]:8001".parse().unwrap();
+        let rows = vec![NodeRow::new(info, None)];
+
+        assert_eq!(rows[0].cells(false)[1], "[2001:db8::1]:8001");
+        assert_eq!(filter_nodes(&rows, "2001:DB8::").len(), 1);
+    }
+
+    #[test]
+    fn test_stateful_node_table_filter() {
+        let mut table = StatefulNodeTable::default();
+        let first = create_info(Pubkey::new_unique(), 20);
+        let mut second = create_info(Pubkey::new_unique(), 10);
+        second.gossip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(72, 20, 2, 47)), 8000);
+        table.upsert_contact_info(first);
+        table.upsert_contact_info(second.clone());
+
+        table.next_row();
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(1));
+
+        // the selection follows the node in the filtered rows
+        table.set_filter(String::from("72.20."));
+        assert_eq!(table.visible_rows().len(), 1);
+        assert_eq!(table.state.selected(), Some(0));
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(0));
+
+        table.set_filter(String::new());
+        assert_eq!(table.visible_rows().len(), 2);
+        assert_eq!(table.visible_rows()[1].info.id, second.id);
+        assert_eq!(table.state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_stateful_node_table_retain() {
+        let mut table = StatefulNodeTable::default();
+        let first = create_info(Pubkey::new_unique(), 20);
+        let second = create_info(Pubkey::new_unique(), 10);
+        table.upsert_contact_info(first.clone());
+        table.upsert_contact_info(second.clone());
+
+        table.next_row();
+        table.next_row();
+        assert_eq!(table.selected().map(|row| row.info.id), Some(second.id));
+
+        // the selection follows the remaining node
+        table.retain(|row| row.info.id != first.id);
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.state.selected(), Some(0));
+
+        table.retain(|_| false);
+        assert!(table.rows.is_empty());
+        assert_eq!(table.state.selected(), None);
+    }
+
+    #[test]
+    fn test_stateful_node_table_full_history() {
+        let mut table = StatefulNodeTable::default();
+        let archive = create_info(Pubkey::new_unique(), 20);
+        let pruned = create_info(Pubkey::new_unique(), 10);
+        let unknown = create_info(Pubkey::new_unique(), 5);
+        table.upsert_contact_info(archive.clone());
+        table.upsert_contact_info(pruned.clone());
+        table.upsert_contact_info(unknown);
+
+        // nothing to compare against yet
+        table.toggle_full_history();
+        assert_eq!(table.full_history_below, None);
+
+        table.update_lowest_slot(&LowestSlot::new(archive.id, 1_000, 0));
+        table.update_lowest_slot(&LowestSlot::new(pruned.id, 250_000_000, 0));
+        assert_eq!(table.rows[1].cells(false)[7], "250000000");
+
+        table.toggle_full_history();
+        assert_eq!(
+            table.full_history_below,
+            Some(250_000_000 - FULL_HISTORY_MARGIN)
+        );
+        let visible = table.visible_rows();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].info.id, archive.id);
+
+        table.toggle_full_history();
+        assert_eq!(table.visible_rows().len(), 3);
+    }
+
+    #[test]
+    fn test_stateful_node_table_last_vote() {
+        let mut table = StatefulNodeTable::default();
+        let info = create_info(Pubkey::new_unique(), 10);
+        table.upsert_contact_info(info.clone());
+        assert_eq!(table.rows[0].cells(false)[8], " - ");
+
+        table.update_last_vote(info.id, 300);
+        table.update_last_vote(info.id, 200);
+        table.update_last_vote(Pubkey::new_unique(), 400);
+        assert_eq!(table.rows[0].last_vote, Some(300));
+        assert_eq!(table.rows[0].cells(false)[8], "300");
+    }
+
+    #[test]
+    fn test_stateful_node_table_vote_lags() {
+        let mut table = StatefulNodeTable::default();
+        let leading = create_info(Pubkey::new_unique(), 10);
+        let lagging = create_info(Pubkey::new_unique(), 10);
+        let silent = create_info(Pubkey::new_unique(), 10);
+        table.upsert_contact_info(leading.clone());
+        table.upsert_contact_info(lagging.clone());
+        table.upsert_contact_info(silent.clone());
+
+        let mut votes = VoteTracker::default();
+        votes.update(leading.id, 1_000, 0);
+        votes.update(lagging.id, 950, 0);
+        table.update_vote_lags(&votes, Some(1_000));
+
+        let lag = |table: &StatefulNodeTable, id| {
+            table
+                .rows
+                .iter()
+                .find(|row| row.info.id == id)
+                .unwrap()
+                .vote_lag
+        };
+        assert_eq!(lag(&table, leading.id), Some(0));
+        assert_eq!(lag(&table, lagging.id), Some(50));
+        assert_eq!(lag(&table, silent.id), None);
+
+        table.update_vote_lags(&votes, None);
+        assert_eq!(lag(&table, lagging.id), None);
+    }
+
+    #[test]
+    fn test_stateful_node_table_shred_version_changes() {
+        let mut table = StatefulNodeTable::default();
+        let moved = create_info(Pubkey::new_unique(), 10);
+        let stable = create_info(Pubkey::new_unique(), 10);
+        table.upsert_contact_info(moved.clone());
+        table.upsert_contact_info(stable.clone());
+
+        table.update_shred_version_changes(&[(moved.id, 4_711, 50_093)]);
+        let row = |id| table.rows.iter().find(|row| row.info.id == id).unwrap();
+        assert_eq!(row(moved.id).previous_shred_version, Some(4_711));
+        assert_eq!(row(moved.id).cells(false)[3], "50093!");
+        assert_eq!(row(stable.id).previous_shred_version, None);
+        assert_eq!(row(stable.id).cells(false)[3], "50093");
+
+        // a change away from the advertised version is outdated
+        table.update_shred_version_changes(&[(moved.id, 50_093, 4_711)]);
+        assert_eq!(table.rows[0].previous_shred_version, None);
+        assert_eq!(table.rows[1].previous_shred_version, None);
+    }
+
+    #[test]
+    fn test_stateful_node_table_rpc_only() {
+        let mut table = StatefulNodeTable::default();
+        let mut rpc = create_info(Pubkey::new_unique(), 20);
+        rpc.rpc = SocketAddr::new(rpc.gossip.ip(), 8899);
+        let mut archive_rpc = create_info(Pubkey::new_unique(), 10);
+        archive_rpc.rpc = SocketAddr::new(archive_rpc.gossip.ip(), 8899);
+        table.upsert_contact_info(rpc.clone());
+        table.upsert_contact_info(archive_rpc.clone());
+        table.upsert_contact_info(create_info(Pubkey::new_unique(), 5));
+
+        table.toggle_rpc_only();
+        assert_eq!(table.visible_rows().len(), 2);
+
+        // combined with the full history filter
+        table.update_lowest_slot(&LowestSlot::new(archive_rpc.id, 1_000, 0));
+        table.update_lowest_slot(&LowestSlot::new(rpc.id, 250_000_000, 0));
+        table.toggle_full_history();
+        let visible = table.visible_rows();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].info.id, archive_rpc.id);
+
+        table.toggle_full_history();
+        table.toggle_rpc_only();
+        assert_eq!(table.visible_rows().len(), 3);
+    }
+
+    #[test]
+    fn test_stateful_node_table_routable_only() {
+        let mut table = StatefulNodeTable::default();
+        let public = create_info(Pubkey::new_unique(), 30);
+        table.upsert_contact_info(public.clone());
+        for (ip, wallclock) in [
+            (Ipv4Addr::LOCALHOST, 20),
+            (Ipv4Addr::new(10, 0, 0, 7), 10),
+            (Ipv4Addr::new(192, 168, 1, 10), 5),
+        ] {
+            let mut info = create_info(Pubkey::new_unique(), wallclock);
+            info.gossip = SocketAddr::new(IpAddr::V4(ip), 8001);
+            table.upsert_contact_info(info);
+        }
+        table.state.select(Some(3));
+
+        table.toggle_routable_only();
+        let visible = table.visible_rows();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].info.id, public.id);
+        // the selected private node is hidden
+        assert_eq!(table.state.selected(), None);
+
+        table.toggle_routable_only();
+        assert_eq!(table.visible_rows().len(), 4);
+    }
+
+    #[test]
+    fn test_stateful_node_table_follow() {
+        let mut table = StatefulNodeTable::default();
+        for wallclock in [30, 20, 10] {
+            table.upsert_contact_info(create_info(Pubkey::new_unique(), wallclock));
+        }
+        let last = table.visible_rows()[2].info.id;
+
+        assert!(!table.follow(Pubkey::new_unique()));
+        assert_eq!(table.followed, None);
+
+        assert!(table.follow(last));
+        assert_eq!(table.visible_rows()[0].info.id, last);
+        assert_eq!(table.selected().map(|row| row.info.id), Some(last));
+
+        // stays pinned while new nodes are discovered
+        table.upsert_contact_info(create_info(Pubkey::new_unique(), 40));
+        table.sort_if_dirty();
+        assert_eq!(table.visible_rows()[0].info.id, last);
+        assert_eq!(table.selected().map(|row| row.info.id), Some(last));
+
+        table.unfollow();
+        assert_eq!(table.visible_rows()[3].info.id, last);
+        assert_eq!(table.selected().map(|row| row.info.id), Some(last));
+    }
+
+    #[test]
+    fn test_render_node_table_country() {
+        let backend = TestBackend::new(100, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let mut table = StatefulNodeTable::default();
+        let info = create_info(Pubkey::new_unique(), since_the_epoch_millis());
+        let ip = info.gossip.ip();
+        table.upsert_contact_info(info);
+        table.set_country(ip, "Germany");
+        assert_eq!(table.rows[0].country.as_deref(), Some("Germany"));
+
+        terminal
+            .draw(|f| {
+                let rows = table.visible_rows();
+                let mut state = TableState::default();
+                render_node_table(f, f.size(), " nodes ", &rows, &mut state, true, None);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 1).contains("Country"));
+        assert!(line(buffer, 3).contains("Germany"));
+    }
+
+    #[test]
+    fn test_render_node_table() {
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let id = Pubkey::new_unique();
+        let info = create_info(id, since_the_epoch_millis());
+        let node = NodeRow::new(info, Some(&create_version(id)));
+        let mut state = TableState::default();
+
+        terminal
+            .draw(|f| {
+                render_node_table(f, f.size(), " nodes ", &[&node], &mut state, false, None);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = line(buffer, 3);
+
+        assert!(row.contains(&short_pubkey(&id)));
+        assert!(row.contains("141.98.219.218:8001"));
+        assert!(row.contains("141.98.219.218:8001 1 "));
+        assert!(row.contains("50093"));
+        assert!(row.contains("1.18.23"));
+        assert!(row.contains("0s"));
+        assert_eq!(buffer.get(1, 3).fg, Color::Reset);
+    }
+
+    #[test]
+    fn test_freshness_style() {
+        let freshness = Freshness::default();
+        let color = |secs| freshness_style(Duration::from_secs(secs), &freshness).fg;
+
+        assert_eq!(color(0), Some(Color::Green));
+        assert_eq!(color(9), Some(Color::Green));
+        assert_eq!(color(10), Some(Color::Yellow));
+        assert_eq!(color(60), Some(Color::Yellow));
+        assert_eq!(color(61), Some(Color::Red));
+    }
+
+    #[test]
+    fn test_render_node_table_freshness() {
+        let backend = TestBackend::new(80, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let now = since_the_epoch_millis();
+        let recent = NodeRow::new(create_info(Pubkey::new_unique(), now), None);
+        let silent = NodeRow::new(create_info(Pubkey::new_unique(), now - 120_000), None);
+        let mut state = TableState::default();
+        let freshness = Freshness::default();
+
+        terminal
+            .draw(|f| {
+                render_node_table(
+                    f,
+                    f.size(),
+                    " nodes ",
+                    &[&recent, &silent],
+                    &mut state,
+                    false,
+                    Some(&freshness),
+                );
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer.get(1, 3).fg, Color::Green);
+        assert_eq!(buffer.get(1, 4).fg, Color::Red);
+    }
+}
@@ -0,0 +1,149 @@
+use std::time::{Duration, Instant};
+
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{app::Model, ui::core::centered_rect, ui::version_histogram_widget::version_histogram};
+
+/// End of session report, shown before quitting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    pub nodes: usize,
+    /// distinct versions run by the nodes, the unknown one included
+    pub versions: usize,
+    pub duration: Duration,
+    /// packets received by the last connection
+    pub packets: u64,
+    pub values: u64,
+}
+
+impl RunSummary {
+    pub fn new(model: &Model, now: Instant) -> Self {
+        let rows = &model.home_node_table.rows;
+        RunSummary {
+            nodes: rows.len(),
+            versions: version_histogram(rows).len(),
+            duration: now.saturating_duration_since(model.started_at),
+            packets: model.rates.total_packets,
+            values: model.rates.total_values,
+        }
+    }
+}
+
+/// Hours, minutes and seconds, the hours only when reached.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m {secs:02}s")
+    } else {
+        format!("{minutes}m {secs:02}s")
+    }
+}
+
+/// Render the summary in a popup centered over `area`, with the keys to confirm.
+pub fn render_quit_summary<B: Backend>(f: &mut Frame<B>, area: Rect, summary: &RunSummary) {
+    let bbox = centered_rect(50, 50, area);
+
+    let label = Style::default().add_modifier(Modifier::BOLD);
+    let line = |name: &str, value: String| {
+        Spans::from(vec![
+            Span::styled(format!("{name:>10}: "), label),
+            Span::raw(value),
+        ])
+    };
+    let key = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let lines = vec![
+        line("nodes", summary.nodes.to_string()),
+        line("versions", summary.versions.to_string()),
+        line("run time", format_duration(summary.duration)),
+        line("packets", summary.packets.to_string()),
+        line("values", summary.values.to_string()),
+        Spans::default(),
+        Spans::from(vec![
+            Span::styled("q/ENTER", key),
+            Span::raw(" quit   "),
+            Span::styled("ESC", key),
+            Span::raw(" cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Quit? ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+    );
+    f.render_widget(Clear, bbox);
+    f.render_widget(paragraph, bbox);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_gossip_proto::protocol::LegacyContactInfo;
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::screen;
+
+    #[test]
+    fn test_run_summary() {
+        let mut model = Model::default();
+        for _ in 0..3 {
+            model
+                .home_node_table
+                .upsert_contact_info(LegacyContactInfo {
+                    id: Pubkey::new_unique(),
+                    ..LegacyContactInfo::default()
+                });
+        }
+        model.rates.total_packets = 1_234;
+
+        let summary = RunSummary::new(&model, model.started_at + Duration::from_secs(90));
+        assert_eq!(summary.nodes, 3);
+        // none advertised its version yet
+        assert_eq!(summary.versions, 1);
+        assert_eq!(summary.duration, Duration::from_secs(90));
+        assert_eq!(summary.packets, 1_234);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "0m 42s");
+        assert_eq!(format_duration(Duration::from_secs(3_725)), "1h 02m 05s");
+    }
+
+    #[test]
+    fn test_render_quit_summary() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let summary = RunSummary {
+            nodes: 1_500,
+            versions: 7,
+            duration: Duration::from_secs(754),
+            packets: 98_765,
+            values: 123_456,
+        };
+        terminal
+            .draw(|f| render_quit_summary(f, f.size(), &summary))
+            .unwrap();
+
+        let screen = screen(terminal.backend().buffer());
+        assert!(screen.contains(" Quit? "));
+        assert!(screen.contains("nodes: 1500"));
+        assert!(screen.contains("run time: 12m 34s"));
+        assert!(screen.contains("packets: 98765"));
+        assert!(screen.contains("q/ENTER quit"));
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+/// Shred version advertised by most nodes, the lowest one on ties.
+pub fn dominant_shred_version(counts: &BTreeMap<u16, usize>) -> Option<u16> {
+    counts
+        .iter()
+        .max_by(|(a_version, a_count), (b_version, b_count)| {
+            a_count.cmp(b_count).then(b_version.cmp(a_version))
+        })
+        .map(|(shred_version, _)| *shred_version)
+}
+
+/// Render each shred version with its node count, the dominant one highlighted.
+pub fn render_shred_versions<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    counts: &BTreeMap<u16, usize>,
+) {
+    let dominant = dominant_shred_version(counts);
+    let items = counts
+        .iter()
+        .map(|(shred_version, count)| {
+            let item = ListItem::new(format!("{shred_version:>5}: {count} nodes"));
+            if Some(*shred_version) == dominant {
+                item.style(
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                item
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" shred versions "),
+    );
+    f.render_widget(list, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_dominant_shred_version() {
+        assert_eq!(dominant_shred_version(&BTreeMap::new()), None);
+
+        let counts = BTreeMap::from([(0, 1), (4_711, 3), (50_093, 12)]);
+        assert_eq!(dominant_shred_version(&counts), Some(50_093));
+
+        let counts = BTreeMap::from([(4_711, 3), (50_093, 3)]);
+        assert_eq!(dominant_shred_version(&counts), Some(4_711));
+    }
+
+    #[test]
+    fn test_render_shred_versions() {
+        let backend = TestBackend::new(30, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let counts = BTreeMap::from([(4_711, 3), (50_093, 12)]);
+        terminal
+            .draw(|f| render_shred_versions(f, f.size(), &counts))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 0).contains(" shred versions "));
+        assert!(line(buffer, 1).contains(" 4711: 3 nodes"));
+        assert!(line(buffer, 2).contains("50093: 12 nodes"));
+        assert_eq!(buffer.get(1, 1).fg, Color::Reset);
+        assert_eq!(buffer.get(1, 2).fg, Color::Green);
+    }
+}
@@ -0,0 +1,151 @@
+use solana_gossip_proto::protocol::{
+    IncrementalSnapshotHashes, SnapshotHashes, SnapshotHashesKind,
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// One line per advertised hash, the highest slot first.
+fn hashes_lines<'a>(hashes: Option<&SnapshotHashes>) -> Vec<Spans<'a>> {
+    let mut hashes = hashes.map_or_else(Vec::new, |hashes| hashes.hashes.clone());
+    if hashes.is_empty() {
+        return vec![Spans::from(Span::styled(
+            "none",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+    hashes.sort_by(|(a, _), (b, _)| b.cmp(a));
+    hashes
+        .into_iter()
+        .map(|(slot, hash)| {
+            Spans::from(vec![
+                Span::styled(format!("{slot:>11} "), Style::default().fg(Color::DarkGray)),
+                Span::raw(hash.to_string()),
+            ])
+        })
+        .collect()
+}
+
+/// The full snapshot line then one line per incremental, oldest first.
+fn chain_lines<'a>(hashes: Option<&IncrementalSnapshotHashes>) -> Vec<Spans<'a>> {
+    let Some(hashes) = hashes else {
+        return vec![Spans::from(Span::styled(
+            "none",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    };
+    hashes
+        .chain()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (slot, hash))| {
+            let (label, color) = if i == 0 {
+                ("full", Color::Green)
+            } else {
+                ("incr", Color::Cyan)
+            };
+            Spans::from(vec![
+                Span::styled(format!("{label} "), Style::default().fg(color)),
+                Span::styled(format!("{slot:>11} "), Style::default().fg(Color::DarkGray)),
+                Span::raw(hash.to_string()),
+            ])
+        })
+        .collect()
+}
+
+/// Render the base snapshot and the incremental ones applying on top of it.
+pub fn render_incremental_chain<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    hashes: Option<&IncrementalSnapshotHashes>,
+) {
+    let paragraph = Paragraph::new(chain_lines(hashes)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Incremental Snapshots "),
+    );
+    f.render_widget(paragraph, area);
+}
+
+/// Render the hashes of one kind, each kind has its own panel.
+pub fn render_snapshot_hashes<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    kind: SnapshotHashesKind,
+    hashes: Option<&SnapshotHashes>,
+) {
+    let paragraph = Paragraph::new(hashes_lines(hashes)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {kind} ")),
+    );
+    f.render_widget(paragraph, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{hash::Hash, pubkey::Pubkey};
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_render_snapshot_hashes() {
+        let backend = TestBackend::new(70, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let hash = Hash::new_unique();
+        let hashes = SnapshotHashes {
+            from: Pubkey::new_unique(),
+            hashes: vec![(100, Hash::new_unique()), (200, hash)],
+            wallclock: 0,
+        };
+
+        terminal
+            .draw(|f| {
+                let (left, right) = (Rect::new(0, 0, 70, 4), Rect::new(0, 4, 70, 2));
+                render_snapshot_hashes(f, left, SnapshotHashesKind::Accounts, Some(&hashes));
+                render_snapshot_hashes(f, right, SnapshotHashesKind::Snapshot, None);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 0).contains(" Accounts Hashes "));
+        assert!(line(buffer, 1).contains(&format!("200 {hash}")));
+        assert!(line(buffer, 2).contains("100 "));
+        assert!(line(buffer, 4).contains(" Snapshot Hashes "));
+    }
+
+    #[test]
+    fn test_render_incremental_chain() {
+        let backend = TestBackend::new(80, 7);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let (base, incremental) = (Hash::new_unique(), Hash::new_unique());
+        let hashes = IncrementalSnapshotHashes {
+            from: Pubkey::new_unique(),
+            base: (100, base),
+            hashes: vec![(150, Hash::new_unique()), (120, incremental)],
+            wallclock: 0,
+        };
+
+        terminal
+            .draw(|f| {
+                render_incremental_chain(f, Rect::new(0, 0, 80, 5), Some(&hashes));
+                render_incremental_chain(f, Rect::new(0, 5, 80, 2), None);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert!(line(buffer, 0).contains(" Incremental Snapshots "));
+        assert!(line(buffer, 1).contains(&format!("full         100 {base}")));
+        assert!(line(buffer, 2).contains(&format!("incr         120 {incremental}")));
+        assert!(line(buffer, 3).contains("incr         150 "));
+        assert!(line(buffer, 5).contains(" Incremental Snapshots "));
+    }
+}
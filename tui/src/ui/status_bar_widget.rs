@@ -0,0 +1,306 @@
+use std::{
+    fmt,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::Model;
+
+/// Without pull responses for this long the entrypoint is reported as silent.
+pub const NO_DATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// the gossip socket is not bound
+    Disconnected,
+    /// waiting for the first pull response
+    Connecting,
+    Connected,
+    /// no pull response received lately
+    NoData,
+}
+
+impl ConnectionState {
+    pub fn new(
+        connected_at: Option<Instant>,
+        last_response_at: Option<Instant>,
+        now: Instant,
+    ) -> Self {
+        let Some(connected_at) = connected_at else {
+            return ConnectionState::Disconnected;
+        };
+        let silent_since = last_response_at.unwrap_or(connected_at);
+        match last_response_at {
+            _ if now.saturating_duration_since(silent_since) >= NO_DATA_TIMEOUT => {
+                ConnectionState::NoData
+            }
+            Some(_) => ConnectionState::Connected,
+            None => ConnectionState::Connecting,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            ConnectionState::Disconnected => Color::DarkGray,
+            ConnectionState::Connecting => Color::Yellow,
+            ConnectionState::Connected => Color::Green,
+            ConnectionState::NoData => Color::Red,
+        }
+    }
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionState::Disconnected => write!(f, "disconnected"),
+            ConnectionState::Connecting => write!(f, "connecting"),
+            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::NoData => write!(f, "no data"),
+        }
+    }
+}
+
+/// What the status bar shows, taken from the model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppState {
+    pub entrypoint: Option<String>,
+    /// time since the last answer of each entrypoint, listed when rotating across several
+    pub entrypoint_ages: Vec<(SocketAddr, Option<Duration>)>,
+    pub connection: ConnectionState,
+    /// local port of the gossip socket, when bound
+    pub bound_port: Option<u16>,
+    pub peers: usize,
+    pub packets_per_sec: u64,
+    pub sort: String,
+    pub filter: String,
+    /// values queued and dropped while the feed is paused, none while live
+    pub paused: Option<(usize, usize)>,
+}
+
+impl AppState {
+    pub fn new(model: &Model, now: Instant) -> Self {
+        let connection = ConnectionState::new(model.connected_at, model.last_response_at, now);
+        let table = &model.home_node_table;
+        AppState {
+            entrypoint: model.entrypoint.clone(),
+            entrypoint_ages: model
+                .entrypoint_responses
+                .iter()
+                .map(|(addr, at)| (*addr, at.map(|at| now.saturating_duration_since(at))))
+                .collect(),
+            connection,
+            bound_port: model.connected_at.map(|_| model.listern_port),
+            peers: table.rows.len(),
+            packets_per_sec: model.rates.packets_per_sec(),
+            sort: format!(
+                "{} {}",
+                table.sort_key,
+                if table.sort_descending { "▼" } else { "▲" }
+            ),
+            filter: table.filter.clone(),
+            paused: model
+                .paused
+                .then(|| (model.paused_queue.len(), model.paused_queue.dropped())),
+        }
+    }
+}
+
+/// Render a single line with the entrypoint, socket, peers, traffic and table state.
+pub fn render_status_bar<B: Backend>(f: &mut Frame<B>, area: Rect, state: &AppState) {
+    let dimmed = Style::default().fg(Color::DarkGray);
+    let separator = || Span::styled(" | ", dimmed);
+
+    let entrypoint = state.entrypoint.as_deref().unwrap_or("—");
+    let socket = state
+        .bound_port
+        .map_or_else(|| String::from("unbound"), |port| format!("bound :{port}"));
+    let filter = if state.filter.is_empty() {
+        String::from("—")
+    } else {
+        format!("/{}", state.filter)
+    };
+
+    let mut spans = vec![
+        Span::raw(format!(" {entrypoint} ")),
+        Span::styled(
+            format!("[{}]", state.connection),
+            Style::default().fg(state.connection.color()),
+        ),
+        separator(),
+    ];
+    if let Some((queued, dropped)) = state.paused {
+        let paused = match dropped {
+            0 => format!("PAUSED ({queued} queued)"),
+            dropped => format!("PAUSED ({queued} queued, {dropped} dropped)"),
+        };
+        spans.push(Span::styled(paused, Style::default().fg(Color::Yellow)));
+        spans.push(separator());
+    }
+    if state.entrypoint_ages.len() > 1 {
+        let ages = state
+            .entrypoint_ages
+            .iter()
+            .map(|(addr, age)| match age {
+                Some(age) => format!("{addr} {}s", age.as_secs()),
+                None => format!("{addr} —"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        spans.push(Span::raw(format!("entrypoints: {ages}")));
+        spans.push(separator());
+    }
+    spans.extend([
+        Span::raw(format!("socket: {socket}")),
+        separator(),
+        Span::raw(format!("peers: {}", state.peers)),
+        separator(),
+        Span::raw(format!("packets/s: {}", state.packets_per_sec)),
+        separator(),
+        Span::raw(format!("sort: {}", state.sort)),
+        separator(),
+        Span::raw(format!("filter: {filter}")),
+    ]);
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::line;
+
+    #[test]
+    fn test_connection_state() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(3);
+        let state = |connected_at, last_response_at, now| {
+            ConnectionState::new(connected_at, last_response_at, now)
+        };
+
+        assert_eq!(state(None, None, start), ConnectionState::Disconnected);
+        assert_eq!(state(Some(start), None, later), ConnectionState::Connecting);
+        assert_eq!(
+            state(Some(start), None, start + NO_DATA_TIMEOUT),
+            ConnectionState::NoData
+        );
+        assert_eq!(
+            state(Some(start), Some(later), later),
+            ConnectionState::Connected
+        );
+        assert_eq!(
+            state(Some(start), Some(later), later + NO_DATA_TIMEOUT),
+            ConnectionState::NoData
+        );
+        assert_eq!(ConnectionState::NoData.to_string(), "no data");
+    }
+
+    #[test]
+    fn test_app_state() {
+        let mut model = Model::default();
+        let now = Instant::now();
+        let state = AppState::new(&model, now);
+        assert_eq!(state.connection, ConnectionState::Disconnected);
+        assert_eq!(state.bound_port, None);
+        assert_eq!(state.sort, "Age ▲");
+
+        model.entrypoint = Some(String::from("141.98.219.218:8000"));
+        model.connected_at = Some(now);
+        model.last_response_at = Some(now);
+        let state = AppState::new(&model, now);
+        assert_eq!(state.connection, ConnectionState::Connected);
+        assert_eq!(state.bound_port, Some(8001));
+        assert_eq!(state.paused, None);
+
+        model.paused = true;
+        assert_eq!(AppState::new(&model, now).paused, Some((0, 0)));
+
+        let addr: SocketAddr = "141.98.219.218:8000".parse().unwrap();
+        model.entrypoint_responses = vec![(addr, Some(now))];
+        let state = AppState::new(&model, now + Duration::from_secs(2));
+        assert_eq!(
+            state.entrypoint_ages,
+            vec![(addr, Some(Duration::from_secs(2)))]
+        );
+    }
+
+    #[test]
+    fn test_render_status_bar() {
+        let backend = TestBackend::new(120, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let state = AppState {
+            entrypoint: Some(String::from("141.98.219.218:8000")),
+            entrypoint_ages: vec![],
+            connection: ConnectionState::Connecting,
+            bound_port: Some(8001),
+            peers: 42,
+            packets_per_sec: 7,
+            sort: String::from("Age ▲"),
+            filter: String::from("72.20"),
+            paused: None,
+        };
+        terminal
+            .draw(|f| render_status_bar(f, f.size(), &state))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let bar = line(buffer, 0);
+        assert!(bar.starts_with(" 141.98.219.218:8000 [connecting] | socket: bound :8001"));
+        assert!(bar.contains("peers: 42 | packets/s: 7 | sort: Age ▲ | filter: /72.20"));
+        assert_eq!(buffer.get(21, 0).fg, Color::Yellow);
+        assert!(!bar.contains("PAUSED"));
+
+        let state = AppState {
+            paused: Some((3, 1)),
+            ..state
+        };
+        terminal
+            .draw(|f| render_status_bar(f, f.size(), &state))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let bar = line(buffer, 0);
+        assert!(bar.contains("[connecting] | PAUSED (3 queued, 1 dropped) | socket:"));
+    }
+
+    #[test]
+    fn test_render_status_bar_entrypoints() {
+        let backend = TestBackend::new(160, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let state = AppState {
+            entrypoint: Some(String::from("10.0.0.1:8001")),
+            entrypoint_ages: vec![
+                (
+                    "10.0.0.1:8001".parse().unwrap(),
+                    Some(Duration::from_secs(3)),
+                ),
+                ("10.0.0.2:8001".parse().unwrap(), None),
+            ],
+            connection: ConnectionState::Connected,
+            bound_port: Some(8001),
+            peers: 0,
+            packets_per_sec: 0,
+            sort: String::from("Age ▲"),
+            filter: String::new(),
+            paused: None,
+        };
+        terminal
+            .draw(|f| render_status_bar(f, f.size(), &state))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let bar = line(buffer, 0);
+        assert!(bar.contains("| entrypoints: 10.0.0.1:8001 3s, 10.0.0.2:8001 — | socket:"));
+    }
+}
@@ -1,3 +1,4 @@
+use tui::widgets::TableState;
 #[allow(unused_imports)]
 use tui::{
     backend::Backend,
@@ -7,10 +8,6 @@ use tui::{
     widgets::{Block, Borders},
     Frame,
 };
-use tui::{
-    layout::Constraint,
-    widgets::{Cell, Row, Table, TableState},
-};
 
 #[derive(Debug)]
 pub struct StatefulTable<T> {
@@ -84,45 +81,51 @@ impl<T> StatefulTable<T> {
     }
 }
 
-pub fn draw_stateful_table<B: Backend>(
-    f: &mut Frame<B>,
-    bbox: Rect,
-    title: &str,
-    headers: &[&str],
-    widths: &[Constraint],
-    stateful_table: &mut StatefulTable<String>,
-) {
-    let selected_style = Style::default().add_modifier(Modifier::REVERSED);
-    let header_style = Style::default().bg(Color::LightBlue);
-    let header_cells = headers
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::White)));
-
-    let header = Row::new(header_cells)
-        .style(header_style)
-        .height(1)
-        .bottom_margin(1);
-
-    let rows = stateful_table.items.iter().map(|row| {
-        let cells = row.iter().map(|c| Cell::from(c.to_string()));
-        Row::new(cells).height(1_u16).bottom_margin(0)
-    });
-
-    let t = Table::new(rows)
-        .header(header)
-        .block(Block::default().borders(Borders::ALL).title(title))
-        .highlight_style(selected_style)
-        .widths(widths);
-    f.render_stateful_widget(t, bbox, &mut stateful_table.state);
-}
-
 // tests
 #[cfg(test)]
 mod tests {
     use std::vec;
 
     use super::*;
-    use tui::{backend::TestBackend, buffer::Buffer, Terminal};
+    use tui::{
+        backend::TestBackend,
+        buffer::Buffer,
+        layout::Constraint,
+        widgets::{Cell, Row, Table},
+        Terminal,
+    };
+
+    fn draw_stateful_table<B: Backend>(
+        f: &mut Frame<B>,
+        bbox: Rect,
+        title: &str,
+        headers: &[&str],
+        widths: &[Constraint],
+        stateful_table: &mut StatefulTable<String>,
+    ) {
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let header_style = Style::default().bg(Color::LightBlue);
+        let header_cells = headers
+            .iter()
+            .map(|h| Cell::from(*h).style(Style::default().fg(Color::White)));
+
+        let header = Row::new(header_cells)
+            .style(header_style)
+            .height(1)
+            .bottom_margin(1);
+
+        let rows = stateful_table.items.iter().map(|row| {
+            let cells = row.iter().map(|c| Cell::from(c.clone()));
+            Row::new(cells).height(1_u16).bottom_margin(0)
+        });
+
+        let t = Table::new(rows)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(selected_style)
+            .widths(widths);
+        f.render_stateful_widget(t, bbox, &mut stateful_table.state);
+    }
 
     #[test]
     fn test_stateful_table() {
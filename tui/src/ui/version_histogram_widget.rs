@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{BarChart, Block, Borders},
+    Frame,
+};
+
+use super::node_table_widget::NodeRow;
+
+/// Bucket of the nodes which did not advertise any version.
+pub const UNKNOWN_VERSION: &str = "unknown";
+
+const BAR_WIDTH: u16 = 8;
+
+/// Number of nodes running each version, in semantic order, the unknown bucket last.
+/// Nodes advertising only a legacy version share the bucket of the same release.
+pub fn version_histogram(rows: &[NodeRow]) -> Vec<(String, usize)> {
+    let mut buckets: BTreeMap<(u16, u16, u16), usize> = BTreeMap::new();
+    let mut unknown = 0;
+    for row in rows {
+        match row.semver() {
+            Some(semver) => *buckets.entry(semver).or_default() += 1,
+            None => unknown += 1,
+        }
+    }
+
+    let mut histogram = buckets
+        .into_iter()
+        .map(|((major, minor, patch), count)| (format!("{major}.{minor}.{patch}"), count))
+        .collect::<Vec<_>>();
+    if unknown > 0 {
+        histogram.push((String::from(UNKNOWN_VERSION), unknown));
+    }
+    histogram
+}
+
+pub fn render_version_histogram<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    histogram: &[(String, usize)],
+) {
+    let data = histogram
+        .iter()
+        .map(|(label, count)| (label.as_str(), *count as u64))
+        .collect::<Vec<_>>();
+
+    let nodes = histogram.iter().map(|(_, count)| count).sum::<usize>();
+    let title = format!(" Versions [{nodes} nodes] ");
+
+    let chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&data)
+        .bar_width(BAR_WIDTH)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Yellow))
+        .value_style(
+            Style::default()
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(chart, area);
+}
+
+// tests
+#[cfg(test)]
+mod tests {
+    use solana_gossip_proto::protocol::{
+        LegacyContactInfo, LegacyVersion, LegacyVersion1, LegacyVersion2, Version,
+    };
+    use solana_sdk::pubkey::Pubkey;
+    use tui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::ui::core::screen;
+
+    fn create_row(semver: Option<(u16, u16, u16)>, legacy: bool) -> NodeRow {
+        let from = Pubkey::new_unique();
+        let info = LegacyContactInfo {
+            id: from,
+            ..LegacyContactInfo::default()
+        };
+        let mut row = NodeRow::new(info, None);
+        match semver {
+            Some((major, minor, patch)) if legacy => {
+                row.legacy_version = Some(LegacyVersion {
+                    from,
+                    wallclock: 0,
+                    version: LegacyVersion1 {
+                        major,
+                        minor,
+                        patch,
+                        commit: None,
+                    },
+                });
+            }
+            Some((major, minor, patch)) => {
+                row.version = Some(Version {
+                    from,
+                    wallclock: 0,
+                    version: LegacyVersion2 {
+                        major,
+                        minor,
+                        patch,
+                        commit: None,
+                        feature_set: 0,
+                    },
+                });
+            }
+            None => {}
+        }
+        row
+    }
+
+    #[test]
+    fn test_version_histogram() {
+        let rows = vec![
+            create_row(Some((1, 18, 23)), false),
+            create_row(None, false),
+            create_row(Some((1, 9, 30)), false),
+            create_row(Some((1, 18, 23)), true),
+            create_row(Some((1, 14, 17)), true),
+            create_row(Some((1, 18, 3)), false),
+        ];
+
+        assert_eq!(
+            version_histogram(&rows),
+            vec![
+                (String::from("1.9.30"), 1),
+                (String::from("1.14.17"), 1),
+                (String::from("1.18.3"), 1),
+                (String::from("1.18.23"), 2),
+                (String::from(UNKNOWN_VERSION), 1),
+            ]
+        );
+
+        assert!(version_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_render_version_histogram() {
+        let backend = TestBackend::new(40, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let histogram = vec![
+            (String::from("1.17.28"), 3),
+            (String::from(UNKNOWN_VERSION), 1),
+        ];
+
+        terminal
+            .draw(|f| render_version_histogram(f, f.size(), &histogram))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen = screen(buffer);
+        assert!(screen.contains(" Versions [4 nodes] "));
+        assert!(screen.contains("1.17.28"));
+        assert!(screen.contains(UNKNOWN_VERSION));
+    }
+}